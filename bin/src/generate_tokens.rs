@@ -2,17 +2,24 @@ use std::env;
 use std::fs::File;
 use std::io::{self, Write};
 
-use gherkin::{Parser, Result, TokenFormatterBuilder};
+use gherkin::{Parser, ParserOptions, Result, TokenFormatterBuilder};
 
 fn main() -> Result<()> {
     let stdout = io::stdout();
     let mut stdout_handle = stdout.lock();
 
     let mut parser = Parser::with_builder(TokenFormatterBuilder::default());
+    let mut markdown_parser = ParserOptions::with_builder(TokenFormatterBuilder::default())
+        .markdown()
+        .create();
 
     for file_name in env::args().skip(1) {
         let file = File::open(&file_name)?;
-        let result = parser.parse_reader(file)?;
+        let result = if file_name.ends_with(".md") {
+            markdown_parser.parse_reader(file)?
+        } else {
+            parser.parse_reader(file)?
+        };
         stdout_handle.write_all(result.as_bytes())?;
     }
 
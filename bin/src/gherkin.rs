@@ -1,15 +1,56 @@
 use std::borrow::Cow;
 use std::collections::VecDeque;
 use std::env;
-use std::io;
-use std::path::Path;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 use cucumber_messages::id_generator::IncrementingIdGenerator;
 use cucumber_messages::io::{MessageWriter, NdjsonWriter};
+use cucumber_messages::source::SourceReferenceType;
+use cucumber_messages::{Message, ParseError};
 
+use gherkin::changed::changed_pickles;
+use gherkin::cuke::{Compiler, Diagnostic};
+use gherkin::diff::diff_documents;
+use gherkin::docs::Site;
+use gherkin::duplicates::{find_duplicates, DuplicateOptions};
+use gherkin::export::json;
+use gherkin::stats::StatisticsBuilder;
 use gherkin::{IncludeOptions, Result};
 
 fn main() -> Result<()> {
+    let mut args = env::args().skip(1).collect::<VecDeque<String>>();
+
+    match args.front().map(String::as_str) {
+        Some("docs") => {
+            args.pop_front();
+            return docs(args);
+        }
+        Some("export") => {
+            args.pop_front();
+            return export(args);
+        }
+        Some("duplicates") => {
+            args.pop_front();
+            return duplicates(args);
+        }
+        Some("diff") => {
+            args.pop_front();
+            return diff(args);
+        }
+        Some("changed") => {
+            args.pop_front();
+            return changed(args);
+        }
+        Some("stats") => {
+            args.pop_front();
+            return stats(args);
+        }
+        Some("schema") => return schema(),
+        _ => {}
+    }
+
     let stdout = io::stdout();
     let stdout_handle = stdout.lock();
 
@@ -24,8 +65,6 @@ fn main() -> Result<()> {
     let mut format: Cow<'static, str> = Cow::Borrowed("protobuf");
     let mut id_generator = None;
 
-    let mut args = env::args().skip(1).collect::<VecDeque<String>>();
-
     while let Some(arg) = args.pop_front() {
         match arg.as_str() {
             "--no-source" => include_options.source = false,
@@ -37,6 +76,14 @@ fn main() -> Result<()> {
         }
     }
 
+    if format == "json" {
+        // the documents are always printed with predictable ids, but without sources and pickles
+        if !include_options.source || !include_options.gherkin_document || id_generator.is_some() {
+            panic!("--no-source, --no-ast and --predictable-ids can't be used with --format json");
+        }
+        return json(&paths);
+    }
+
     let paths_iter = paths.iter().map(|path_str| Path::new(path_str));
 
     let mut id_generator = match id_generator {
@@ -47,14 +94,397 @@ fn main() -> Result<()> {
     let mut message_writer = match format.as_ref() {
         "ndjson" => NdjsonWriter::new(stdout_handle),
         "protobuf" => unimplemented!("protobuf message writer"),
-        _ => panic!("format needs to be either ndjson or protobuf (default)"),
+        _ => panic!("format needs to be either ndjson, json or protobuf (default)"),
     };
 
-    let messages = gherkin::parse_paths(paths_iter, include_options, &mut id_generator).unwrap();
+    let (messages, diagnostics) =
+        gherkin::parse_paths_with_diagnostics(paths_iter, include_options, &mut id_generator)
+            .unwrap();
     for message in messages {
         message_writer.write(&message).unwrap();
     }
+    print_diagnostics(&diagnostics);
 
     message_writer.flush()?;
     Ok(())
 }
+
+/// Renders the feature files of the given files and directories into a static HTML site.
+///
+/// Usage: `gherkin docs [--output <directory>] <path>...`
+fn docs(mut args: VecDeque<String>) -> Result<()> {
+    let mut output = PathBuf::from("gherkin-docs");
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.pop_front() {
+        match arg.as_str() {
+            "--output" => output = PathBuf::from(args.pop_front().expect("output arg")),
+            _ => find_feature_files(Path::new(&arg), &mut paths)?,
+        }
+    }
+
+    let include_options = IncludeOptions {
+        source: false,
+        gherkin_document: true,
+        pickles: false,
+    };
+    let mut id_generator = IncrementingIdGenerator::new();
+    let messages = gherkin::parse_paths(&paths, include_options, &mut id_generator)?;
+
+    let mut gherkin_documents = Vec::new();
+    for message in messages.into_iter().filter_map(|envelope| envelope.message) {
+        match message {
+            Message::GherkinDocument(gherkin_document) => gherkin_documents.push(gherkin_document),
+            Message::ParseError(parse_error) => print_parse_error(parse_error),
+            _ => {}
+        }
+    }
+
+    Site::render(&gherkin_documents).write_to(&output)?;
+    eprintln!(
+        "Rendered {} feature files into {}",
+        gherkin_documents.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Prints the documents of the given feature files in another format.
+///
+/// Usage: `gherkin export --to markdown <path>...`
+fn export(mut args: VecDeque<String>) -> Result<()> {
+    let mut format: Cow<'static, str> = Cow::Borrowed("markdown");
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.pop_front() {
+        match arg.as_str() {
+            "--to" => format = Cow::Owned(args.pop_front().expect("to arg")),
+            _ => paths.push(arg),
+        }
+    }
+
+    let export = match format.as_ref() {
+        "markdown" => gherkin::export::to_markdown,
+        _ => panic!("export format needs to be markdown"),
+    };
+
+    let include_options = IncludeOptions {
+        source: false,
+        gherkin_document: true,
+        pickles: false,
+    };
+    let mut id_generator = IncrementingIdGenerator::new();
+    let messages = gherkin::parse_paths(&paths, include_options, &mut id_generator)?;
+
+    let stdout = io::stdout();
+    let mut stdout_handle = stdout.lock();
+    let mut is_first = true;
+    for message in messages.into_iter().filter_map(|envelope| envelope.message) {
+        match message {
+            Message::GherkinDocument(gherkin_document) => {
+                if !is_first {
+                    stdout_handle.write_all(b"\n")?;
+                }
+                is_first = false;
+                stdout_handle.write_all(export(&gherkin_document).as_bytes())?;
+            }
+            Message::ParseError(parse_error) => print_parse_error(parse_error),
+            _ => {}
+        }
+    }
+
+    stdout_handle.flush()?;
+    Ok(())
+}
+
+/// Prints the near-duplicate steps and the duplicate scenarios of the given files and directories.
+///
+/// Usage: `gherkin duplicates [--threshold <0..1>] [--min-steps <count>] [--format text|json] <path>...`
+fn duplicates(mut args: VecDeque<String>) -> Result<()> {
+    let mut options = DuplicateOptions::default();
+    let mut format: Cow<'static, str> = Cow::Borrowed("text");
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.pop_front() {
+        match arg.as_str() {
+            "--threshold" => {
+                let threshold = args.pop_front().expect("threshold arg");
+                options.threshold = threshold.parse().expect("threshold between 0 and 1");
+            }
+            "--min-steps" => {
+                let min_steps = args.pop_front().expect("min-steps arg");
+                options.min_steps = min_steps.parse().expect("min-steps count");
+            }
+            "--format" => format = Cow::Owned(args.pop_front().expect("format arg")),
+            _ => find_feature_files(Path::new(&arg), &mut paths)?,
+        }
+    }
+    if format != "text" && format != "json" {
+        panic!("duplicates format needs to be either text (default) or json");
+    }
+
+    let mut id_generator = IncrementingIdGenerator::new();
+    let (parsed_documents, parse_errors) = gherkin::parse_documents(&paths, &mut id_generator)?;
+    for parse_error in parse_errors {
+        print_parse_error(parse_error);
+    }
+
+    let mut compiler = Compiler::new(&mut id_generator);
+    let mut cukes = Vec::new();
+    for parsed_document in &parsed_documents {
+        cukes.extend(compiler.compile_with(
+            &parsed_document.gherkin_document,
+            &parsed_document.step_keyword_types,
+        )?);
+    }
+    print_diagnostics(compiler.diagnostics());
+    let duplicates = find_duplicates(&cukes, &options);
+
+    let stdout = io::stdout();
+    let mut stdout_handle = stdout.lock();
+    if format == "json" {
+        serde_json::to_writer_pretty(&mut stdout_handle, &duplicates).map_err(io::Error::from)?;
+        stdout_handle.write_all(b"\n")?;
+    } else {
+        write!(stdout_handle, "{}", duplicates)?;
+    }
+    stdout_handle.flush()?;
+    Ok(())
+}
+
+/// Prints the semantic changes between two versions of a feature file, one change per line.
+///
+/// Usage: `gherkin diff [--format text|json] <old path> <new path>`
+fn diff(mut args: VecDeque<String>) -> Result<()> {
+    let mut format: Cow<'static, str> = Cow::Borrowed("text");
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.pop_front() {
+        match arg.as_str() {
+            "--format" => format = Cow::Owned(args.pop_front().expect("format arg")),
+            _ => paths.push(arg),
+        }
+    }
+    if format != "text" && format != "json" {
+        panic!("diff format needs to be either text (default) or json");
+    }
+    if paths.len() != 2 {
+        panic!("diff needs exactly two paths, the old and the new version");
+    }
+
+    let include_options = IncludeOptions {
+        source: false,
+        gherkin_document: true,
+        pickles: false,
+    };
+    let mut id_generator = IncrementingIdGenerator::new();
+    let messages = gherkin::parse_paths(&paths, include_options, &mut id_generator)?;
+
+    let mut gherkin_documents = Vec::new();
+    let mut has_parse_errors = false;
+    for message in messages.into_iter().filter_map(|envelope| envelope.message) {
+        match message {
+            Message::GherkinDocument(gherkin_document) => gherkin_documents.push(gherkin_document),
+            Message::ParseError(parse_error) => {
+                print_parse_error(parse_error);
+                has_parse_errors = true;
+            }
+            _ => {}
+        }
+    }
+    if has_parse_errors || gherkin_documents.len() != 2 {
+        std::process::exit(2);
+    }
+    let changes = diff_documents(&gherkin_documents[0], &gherkin_documents[1]);
+
+    let stdout = io::stdout();
+    let mut stdout_handle = stdout.lock();
+    if format == "json" {
+        serde_json::to_writer_pretty(&mut stdout_handle, &changes).map_err(io::Error::from)?;
+        stdout_handle.write_all(b"\n")?;
+    } else {
+        for change in &changes {
+            writeln!(stdout_handle, "{}", change)?;
+        }
+    }
+    stdout_handle.flush()?;
+    Ok(())
+}
+
+/// Prints the pickles of the new version of a feature file which changed compared to the old one,
+/// e.g. with the old version from `git show <revision>:<path>`. A missing old file selects all
+/// pickles.
+///
+/// Usage: `gherkin changed [--format text|json] <old path> <new path>`
+fn changed(mut args: VecDeque<String>) -> Result<()> {
+    let mut format: Cow<'static, str> = Cow::Borrowed("text");
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.pop_front() {
+        match arg.as_str() {
+            "--format" => format = Cow::Owned(args.pop_front().expect("format arg")),
+            _ => paths.push(arg),
+        }
+    }
+    if format != "text" && format != "json" {
+        panic!("changed format needs to be either text (default) or json");
+    }
+    if paths.len() != 2 {
+        panic!("changed needs exactly two paths, the old and the new version");
+    }
+
+    let old_source = match fs::read_to_string(&paths[0]) {
+        Ok(old_source) => old_source,
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(error.into()),
+    };
+    let new_source = fs::read_to_string(&paths[1])?;
+    let mut id_generator = IncrementingIdGenerator::new();
+    let changed_pickles = changed_pickles(&paths[1], &old_source, &new_source, &mut id_generator)?;
+
+    let stdout = io::stdout();
+    let mut stdout_handle = stdout.lock();
+    if format == "json" {
+        serde_json::to_writer_pretty(&mut stdout_handle, &changed_pickles)
+            .map_err(io::Error::from)?;
+        stdout_handle.write_all(b"\n")?;
+    } else {
+        for changed_pickle in &changed_pickles {
+            writeln!(stdout_handle, "{}", changed_pickle)?;
+        }
+    }
+    stdout_handle.flush()?;
+    Ok(())
+}
+
+/// Prints statistics about the feature files of the given files and directories.
+///
+/// Usage: `gherkin stats [--format table|json] <path>...`
+fn stats(mut args: VecDeque<String>) -> Result<()> {
+    let mut format: Cow<'static, str> = Cow::Borrowed("table");
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.pop_front() {
+        match arg.as_str() {
+            "--format" => format = Cow::Owned(args.pop_front().expect("format arg")),
+            _ => find_feature_files(Path::new(&arg), &mut paths)?,
+        }
+    }
+    if format != "table" && format != "json" {
+        panic!("stats format needs to be either table (default) or json");
+    }
+
+    let include_options = IncludeOptions {
+        source: false,
+        gherkin_document: true,
+        pickles: true,
+    };
+    let mut id_generator = IncrementingIdGenerator::new();
+    let (messages, diagnostics) =
+        gherkin::parse_paths_with_diagnostics(&paths, include_options, &mut id_generator)?;
+    print_diagnostics(&diagnostics);
+
+    // the documents of a file precede its pickles
+    let mut statistics_builder = StatisticsBuilder::default();
+    for message in messages.into_iter().filter_map(|envelope| envelope.message) {
+        match message {
+            Message::GherkinDocument(gherkin_document) => {
+                statistics_builder.add_gherkin_document(&gherkin_document)
+            }
+            Message::Pickle(pickle) => statistics_builder.add_pickle(&pickle),
+            Message::ParseError(parse_error) => print_parse_error(parse_error),
+            _ => {}
+        }
+    }
+    let statistics = statistics_builder.build();
+
+    let stdout = io::stdout();
+    let mut stdout_handle = stdout.lock();
+    if format == "json" {
+        serde_json::to_writer_pretty(&mut stdout_handle, &statistics).map_err(io::Error::from)?;
+        stdout_handle.write_all(b"\n")?;
+    } else {
+        write!(stdout_handle, "{}", statistics)?;
+    }
+    stdout_handle.flush()?;
+    Ok(())
+}
+
+/// Prints one JSON document per feature file and line, in the shape described by the schema.
+fn json(paths: &[String]) -> Result<()> {
+    let include_options = IncludeOptions {
+        source: false,
+        gherkin_document: true,
+        pickles: false,
+    };
+    let mut id_generator = IncrementingIdGenerator::new();
+    let messages = gherkin::parse_paths(paths, include_options, &mut id_generator)?;
+
+    let stdout = io::stdout();
+    let mut stdout_handle = stdout.lock();
+    for message in messages.into_iter().filter_map(|envelope| envelope.message) {
+        match message {
+            Message::GherkinDocument(gherkin_document) => {
+                let document = json::to_json(&gherkin_document);
+                serde_json::to_writer(&mut stdout_handle, &document).map_err(io::Error::from)?;
+                stdout_handle.write_all(b"\n")?;
+            }
+            Message::ParseError(parse_error) => print_parse_error(parse_error),
+            _ => {}
+        }
+    }
+
+    stdout_handle.flush()?;
+    Ok(())
+}
+
+/// Prints the JSON Schema of the documents printed with `--format json`.
+///
+/// Usage: `gherkin schema`
+fn schema() -> Result<()> {
+    let stdout = io::stdout();
+    let mut stdout_handle = stdout.lock();
+    serde_json::to_writer_pretty(&mut stdout_handle, &json::schema()).map_err(io::Error::from)?;
+    stdout_handle.write_all(b"\n")?;
+    stdout_handle.flush()?;
+    Ok(())
+}
+
+/// Prints the problems of scenario outlines, which don't prevent compiling their pickles.
+fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+}
+
+fn print_parse_error(parse_error: ParseError) {
+    let uri = match parse_error.source.and_then(|source| source.reference) {
+        Some(SourceReferenceType::Uri(uri)) => uri,
+        None => String::from("<unknown>"),
+    };
+    eprintln!("{}: {}", uri, parse_error.message);
+}
+
+/// Collects a feature file, or the feature files in a directory and its subdirectories
+/// ordered by path.
+fn find_feature_files(path: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        paths.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    entries.sort();
+
+    for entry in entries {
+        let file_name = entry.file_name().unwrap_or_default().to_string_lossy();
+        if entry.is_dir() {
+            find_feature_files(&entry, paths)?;
+        } else if file_name.ends_with(".feature") || file_name.ends_with(".feature.md") {
+            paths.push(entry);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+#![no_main]
+use std::sync::Arc;
+
+use libfuzzer_sys::fuzz_target;
+
+use gherkin::ast::{DataTable, GherkinDocument};
+use gherkin::cuke::{Compiler, Table};
+use gherkin::visit::{self, Context, Visitor};
+use gherkin::IncrementingIdGenerator;
+
+/// Uses the data tables of the document like step definitions do.
+struct DataTableUser;
+
+impl<'a> Visitor<'a> for DataTableUser {
+    fn visit_data_table(&mut self, data_table: &'a DataTable, context: &Context<'a>) {
+        let table = Table::from(data_table);
+        let _ = table.get_location();
+        let _ = table.rows_as_maps();
+        let _ = table.deserialize::<Vec<Vec<String>>>();
+        visit::walk_data_table(self, data_table, context);
+    }
+}
+
+// the seed corpus contains the documents of the acceptance tests as JSON
+fuzz_target!(|data: &[u8]| {
+    let gherkin_document = match serde_json::from_slice::<GherkinDocument>(data) {
+        Ok(gherkin_document) => Arc::new(gherkin_document),
+        Err(_) => return,
+    };
+
+    let mut id_generator = IncrementingIdGenerator::new();
+    let mut compiler = Compiler::new(&mut id_generator);
+    if let Ok(cukes) = compiler.compile(&gherkin_document) {
+        for cuke in cukes {
+            let _ = cuke.into_owned(&gherkin_document);
+        }
+    }
+    let _ = compiler.compile_grouped(&gherkin_document);
+    if let Ok(cukes) = compiler.compile_owned(&gherkin_document) {
+        let _ = serde_json::to_string(&cukes);
+    }
+
+    DataTableUser.visit_gherkin_document(&gherkin_document);
+});
@@ -0,0 +1,17 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use gherkin::Line;
+
+fuzz_target!(|data: &[u8]| {
+    let source = match std::str::from_utf8(data) {
+        Ok(source) => source,
+        Err(_) => return,
+    };
+
+    for (index, text) in source.lines().enumerate() {
+        let line = Line::new(text, index as u32 + 1);
+        let _ = line.get_tags();
+        let _ = line.get_table_cells();
+    }
+});
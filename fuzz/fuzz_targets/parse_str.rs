@@ -0,0 +1,22 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use gherkin::cuke::Compiler;
+use gherkin::{DocumentBuilder, IncrementingIdGenerator, Parser};
+
+fuzz_target!(|data: &[u8]| {
+    let source = match std::str::from_utf8(data) {
+        Ok(source) => source,
+        Err(_) => return,
+    };
+
+    let mut id_generator = IncrementingIdGenerator::new();
+    let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+    let mut parser = Parser::with_builder(builder);
+    if let Ok(gherkin_document) = parser.parse_str(source) {
+        let mut id_generator = IncrementingIdGenerator::new();
+        Compiler::new(&mut id_generator)
+            .compile(&gherkin_document)
+            .expect("documents built by the parser can be compiled");
+    }
+});
@@ -0,0 +1,29 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use gherkin::{StrTokenScanner, Token, TokenScan, TokenScanner};
+
+fuzz_target!(|data: &[u8]| {
+    let reader_tokens = scan(&mut TokenScanner::from(data));
+
+    // the reader fails on invalid UTF-8, the string slice scanner can not be used at all
+    if let Ok(source) = std::str::from_utf8(data) {
+        let str_tokens = scan(&mut StrTokenScanner::from(source));
+        assert_eq!(reader_tokens, str_tokens);
+    }
+});
+
+/// Returns the line texts of the tokens until the end of the file or the first error.
+fn scan<'s, TS: TokenScan<'s>>(token_scan: &mut TS) -> Vec<String> {
+    let mut lines = Vec::new();
+    loop {
+        let token: Token<'s> = match token_scan.next() {
+            Ok(token) => token,
+            Err(_) => return lines,
+        };
+        if token.is_eof() {
+            return lines;
+        }
+        lines.push(token.get_token_value().to_string());
+    }
+}
@@ -2,15 +2,46 @@
 
 extern crate test;
 
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts allocations to compare the allocation behavior of the different parser inputs.
+struct CountingAllocator;
+
+thread_local! {
+    /// The allocations of the current thread, so tests running in parallel don't interfere.
+    static ALLOCATIONS: Cell<usize> = Cell::new(0);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // the thread local is not accessible while the thread is being torn down
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
     use std::fs;
     use std::path::{Path, PathBuf};
     use test::Bencher;
 
     use cucumber_messages::id_generator::IncrementingIdGenerator;
 
-    use gherkin::{self, DocumentBuilder, IncludeOptions, Parser};
+    use gherkin::{self, DocumentBuilder, IncludeOptions, Parser, TokenFormatterBuilder};
+
+    use super::ALLOCATIONS;
+
+    const VERY_LONG_FEATURE: &str = "../testdata/good/very_long.feature";
 
     #[bench]
     fn bench_parser_good_features(bencher: &mut Bencher) {
@@ -78,6 +109,77 @@ mod tests {
         });
     }
 
+    #[bench]
+    fn bench_tokens_very_long_str(bencher: &mut Bencher) {
+        let feature = fs::read_to_string(VERY_LONG_FEATURE).unwrap();
+        let mut parser = Parser::with_builder(TokenFormatterBuilder::default());
+
+        bencher.bytes = feature.len() as u64;
+        bencher.iter(|| parser.parse_str(&feature).unwrap());
+    }
+
+    #[bench]
+    fn bench_tokens_very_long_reader(bencher: &mut Bencher) {
+        let feature = fs::read_to_string(VERY_LONG_FEATURE).unwrap();
+        let mut parser = Parser::with_builder(TokenFormatterBuilder::default());
+
+        bencher.bytes = feature.len() as u64;
+        bencher.iter(|| parser.parse_reader(feature.as_bytes()).unwrap());
+    }
+
+    #[bench]
+    fn bench_parser_very_long_str(bencher: &mut Bencher) {
+        let feature = fs::read_to_string(VERY_LONG_FEATURE).unwrap();
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+
+        bencher.bytes = feature.len() as u64;
+        bencher.iter(|| parser.parse_str(&feature).unwrap());
+    }
+
+    #[bench]
+    fn bench_parser_very_long_reader(bencher: &mut Bencher) {
+        let feature = fs::read_to_string(VERY_LONG_FEATURE).unwrap();
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+
+        bencher.bytes = feature.len() as u64;
+        bencher.iter(|| parser.parse_reader(feature.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn count_allocations_very_long() {
+        let feature = fs::read_to_string(VERY_LONG_FEATURE).unwrap();
+        let mut tokens_parser = Parser::with_builder(TokenFormatterBuilder::default());
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut document_parser = Parser::with_builder(builder);
+
+        let tokens_str = count_allocations(|| {
+            tokens_parser.parse_str(&feature).unwrap();
+        });
+        let tokens_reader = count_allocations(|| {
+            tokens_parser.parse_reader(feature.as_bytes()).unwrap();
+        });
+        let document_str = count_allocations(|| {
+            document_parser.parse_str(&feature).unwrap();
+        });
+        let document_reader = count_allocations(|| {
+            document_parser.parse_reader(feature.as_bytes()).unwrap();
+        });
+
+        assert!(tokens_str < tokens_reader);
+        assert!(document_str < document_reader);
+    }
+
+    fn count_allocations<F: FnOnce()>(f: F) -> usize {
+        let before = ALLOCATIONS.with(Cell::get);
+        f();
+        ALLOCATIONS.with(Cell::get) - before
+    }
+
     fn read_features<P: AsRef<Path>>(path: P) -> Vec<String> {
         feature_paths_iter(path)
             .map(fs::read_to_string)
@@ -1,19 +1,73 @@
-use std::any::Any;
-use std::collections::{HashMap, VecDeque};
+use std::result;
+
+use cucumber_messages::ast::*;
 
 use crate::parser::{RuleType, TokenType};
 use crate::token::Token;
 
+/// A child of an `AstNode`: either a token, a node which has not been transformed,
+/// or the AST element a node has been transformed into.
+pub enum AstItem {
+    Token(Token<'static>),
+    Node(AstNode),
+    Step(Step),
+    DocString(DocString),
+    DataTable(DataTable),
+    Background(Background),
+    Scenario(Scenario),
+    Examples(Examples),
+    TableRows(Vec<TableRow>),
+    Description(String),
+    Rule(Rule),
+    Feature(Feature),
+    GherkinDocument(GherkinDocument),
+}
+
+/// A type which can be stored as an `AstItem` of an `AstNode`.
+pub trait AstNodeItem: Sized {
+    fn from_item(item: AstItem) -> result::Result<Self, AstItem>;
+}
+
+macro_rules! impl_ast_node_item {
+    ($($variant:ident($item_type:ty)),* $(,)?) => {
+        $(
+            impl AstNodeItem for $item_type {
+                fn from_item(item: AstItem) -> result::Result<Self, AstItem> {
+                    match item {
+                        AstItem::$variant(item) => Ok(item),
+                        item => Err(item),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_ast_node_item!(
+    Step(Step),
+    DocString(DocString),
+    DataTable(DataTable),
+    Background(Background),
+    Scenario(Scenario),
+    Examples(Examples),
+    TableRows(Vec<TableRow>),
+    Description(String),
+    Rule(Rule),
+    Feature(Feature),
+    GherkinDocument(GherkinDocument),
+);
+
 pub struct AstNode {
     rule_type: RuleType,
-    items: HashMap<RuleType, VecDeque<Box<dyn Any>>>,
+    // removed items leave an empty slot behind, which keeps the order of the others
+    items: Vec<Option<AstItem>>,
 }
 
 impl AstNode {
     pub fn new(rule_type: RuleType) -> AstNode {
         AstNode {
             rule_type,
-            items: HashMap::new(),
+            items: Vec::new(),
         }
     }
 
@@ -21,80 +75,103 @@ impl AstNode {
         self.rule_type
     }
 
-    pub fn add(&mut self, rule_type: RuleType, node: Box<dyn Any>) {
-        self.items
-            .entry(rule_type)
-            .or_insert_with(VecDeque::new)
-            .push_back(node);
+    pub fn add(&mut self, item: AstItem) {
+        self.items.push(Some(item));
     }
 
-    pub fn remove<T: 'static>(&mut self, rule_type: RuleType) -> T {
-        let items = self.items.remove(&rule_type);
-        match items {
-            Some(mut items) => *items
-                .pop_front()
-                .unwrap()
-                .downcast::<T>()
-                .expect("failed to downcast item"),
-            None => panic!("could not find item for RuleType::{}", rule_type),
-        }
+    pub fn remove_opt<T: AstNodeItem>(&mut self) -> Option<T> {
+        self.remove_first(T::from_item)
+    }
+
+    pub fn remove_items<T: AstNodeItem>(&mut self) -> Vec<T> {
+        self.remove_all(T::from_item)
+    }
+
+    pub fn remove_node_opt(&mut self, rule_type: RuleType) -> Option<AstNode> {
+        self.remove_first(|item| match item {
+            AstItem::Node(node) if node.rule_type == rule_type => Ok(node),
+            item => Err(item),
+        })
+    }
+
+    pub fn remove_token_opt(&mut self, token_type: TokenType) -> Option<Token<'static>> {
+        self.remove_first(|item| match item {
+            AstItem::Token(token) if token.matched_type == Some(token_type) => Ok(token),
+            item => Err(item),
+        })
+    }
+
+    pub fn remove_tokens(&mut self, token_type: TokenType) -> Vec<Token<'static>> {
+        self.remove_all(|item| match item {
+            AstItem::Token(token) if token.matched_type == Some(token_type) => Ok(token),
+            item => Err(item),
+        })
     }
 
-    pub fn remove_or<T: 'static>(&mut self, rule_type: RuleType, default: T) -> T {
-        let items = self.items.remove(&rule_type);
-        match items {
-            Some(mut items) => {
-                if items.is_empty() {
-                    default
-                } else {
-                    *items
-                        .pop_front()
-                        .unwrap()
-                        .downcast::<T>()
-                        .expect("failed to downcast item")
+    fn remove_first<T, F>(&mut self, mut from_item: F) -> Option<T>
+    where
+        F: FnMut(AstItem) -> result::Result<T, AstItem>,
+    {
+        for slot in self.items.iter_mut() {
+            if let Some(item) = slot.take() {
+                match from_item(item) {
+                    Ok(item) => return Some(item),
+                    Err(item) => *slot = Some(item),
                 }
             }
-            None => default,
         }
+        None
     }
 
-    pub fn remove_opt<T: 'static>(&mut self, rule_type: RuleType) -> Option<T> {
-        let items = self.items.remove(&rule_type);
-        match items {
-            Some(mut items) => {
-                if items.is_empty() {
-                    None
-                } else {
-                    let item = *items
-                        .pop_front()
-                        .unwrap()
-                        .downcast::<T>()
-                        .expect("failed to downcast item");
-                    Some(item)
+    fn remove_all<T, F>(&mut self, mut from_item: F) -> Vec<T>
+    where
+        F: FnMut(AstItem) -> result::Result<T, AstItem>,
+    {
+        let mut removed = Vec::new();
+        for slot in self.items.iter_mut() {
+            if let Some(item) = slot.take() {
+                match from_item(item) {
+                    Ok(item) => removed.push(item),
+                    Err(item) => *slot = Some(item),
                 }
             }
-            None => None,
         }
+        removed
     }
+}
 
-    pub fn remove_items<T: 'static>(&mut self, rule_type: RuleType) -> Vec<T> {
-        let items = self.items.remove(&rule_type);
-        match items {
-            Some(items) => items
-                .into_iter()
-                .map(|item| *item.downcast::<T>().expect("failed to downcast item"))
-                .collect::<Vec<T>>(),
-            None => Vec::new(),
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    pub fn remove_token(&mut self, token_type: TokenType) -> Token {
-        let rule_type = RuleType::from(token_type);
-        self.remove(rule_type)
-    }
+    #[test]
+    fn removes_items_by_type_in_order() {
+        let mut node = AstNode::new(RuleType::Feature);
+        node.add(AstItem::Description(String::from("description")));
+        for name in &["first", "second"] {
+            node.add(AstItem::Scenario(Scenario {
+                name: String::from(*name),
+                ..Scenario::default()
+            }));
+        }
+        node.add(AstItem::Node(AstNode::new(RuleType::FeatureHeader)));
 
-    pub fn remove_tokens(&mut self, token_type: TokenType) -> Vec<Token> {
-        let rule_type = RuleType::from(token_type);
-        self.remove_items(rule_type)
+        assert!(node.remove_opt::<Background>().is_none());
+        assert!(node.remove_node_opt(RuleType::RuleHeader).is_none());
+        assert_eq!(
+            node.remove_node_opt(RuleType::FeatureHeader)
+                .unwrap()
+                .rule_type(),
+            RuleType::FeatureHeader
+        );
+        let names: Vec<String> = node
+            .remove_items::<Scenario>()
+            .into_iter()
+            .map(|scenario| scenario.name)
+            .collect();
+        assert_eq!(names, vec!["first", "second"]);
+        assert_eq!(node.remove_opt::<String>().unwrap(), "description");
+        assert!(node.remove_token_opt(TokenType::FeatureLine).is_none());
+        assert!(node.remove_items::<Scenario>().is_empty());
     }
 }
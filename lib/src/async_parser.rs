@@ -0,0 +1,225 @@
+use std::io;
+use std::path::Path;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use tokio::fs;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use cucumber_messages::id_generator::IdGenerator;
+use cucumber_messages::Envelope;
+
+use crate::error::Result;
+use crate::parser::{Builder, Parser};
+use crate::IncludeOptions;
+
+/// The number of feature files which `parse_paths_async` reads at once,
+/// so large feature trees don't exhaust the file descriptors.
+pub const MAX_CONCURRENT_READS: usize = 32;
+
+impl<B: Builder> Parser<B> {
+    /// Reads the whole source from an `AsyncRead` and parses it like `parse_str`.
+    pub async fn parse_async_reader<R>(&mut self, mut source: R) -> Result<B::BuilderResult>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut text = String::new();
+        source.read_to_string(&mut text).await?;
+        self.parse_str(&text)
+    }
+}
+
+/// Like `parse_paths`, but reads up to `MAX_CONCURRENT_READS` feature files concurrently.
+///
+/// The files are parsed in the given order after all of them have been read,
+/// so the messages and ids are the same as the ones of `parse_paths`.
+/// The id generator is generic, so the returned future is `Send` if the id generator is.
+pub async fn parse_paths_async<P, I>(
+    paths: P,
+    include_options: IncludeOptions,
+    id_generator: &mut I,
+) -> io::Result<Vec<Envelope>>
+where
+    P: IntoIterator,
+    P::Item: AsRef<Path>,
+    I: IdGenerator,
+{
+    let envelopes = stream::iter(paths)
+        .map(create_envelope_from_path)
+        .buffered(MAX_CONCURRENT_READS)
+        .try_collect::<Vec<Envelope>>()
+        .await?;
+    let envelopes = envelopes.into_iter().map(Ok);
+
+    // like `parse_paths`, the diagnostics are dropped
+    crate::parse_source_envelopes(envelopes, &include_options, id_generator, &mut Vec::new())
+}
+
+async fn create_envelope_from_path<P: AsRef<Path>>(path: P) -> io::Result<Envelope> {
+    let data = fs::read_to_string(path.as_ref()).await?;
+    let envelope = crate::create_source_envelope(data, path.as_ref());
+    Ok(envelope)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp;
+    use std::path::PathBuf;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::{DocumentBuilder, Error};
+
+    use super::*;
+
+    const FEATURE: &str = "\
+# language: de
+Funktionalität: Grüße
+  Szenario: ein Gruß
+    Angenommen ich sage \"Grüß Gott\"
+";
+
+    /// An in-memory source which is pending before every read and only returns a few bytes
+    /// at once, like a slow stream.
+    struct ChunkedReader {
+        data: &'static [u8],
+        chunk_size: usize,
+        is_ready: bool,
+    }
+
+    impl ChunkedReader {
+        fn new(data: &'static str) -> ChunkedReader {
+            ChunkedReader {
+                data: data.as_bytes(),
+                chunk_size: 3,
+                is_ready: false,
+            }
+        }
+    }
+
+    impl AsyncRead for ChunkedReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            if !self.is_ready {
+                self.is_ready = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            let len = cmp::min(cmp::min(self.data.len(), self.chunk_size), buf.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            self.is_ready = false;
+            Poll::Ready(Ok(len))
+        }
+    }
+
+    /// A source which fails with an I/O error.
+    struct FailingReader;
+
+    impl AsyncRead for FailingReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, "stream closed")))
+        }
+    }
+
+    #[tokio::test]
+    async fn parses_async_reader_like_str() {
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut parser =
+            Parser::with_builder(DocumentBuilder::with_id_generator(&mut id_generator));
+        let async_document = parser
+            .parse_async_reader(ChunkedReader::new(FEATURE))
+            .await
+            .unwrap();
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut parser =
+            Parser::with_builder(DocumentBuilder::with_id_generator(&mut id_generator));
+        let document = parser.parse_str(FEATURE).unwrap();
+
+        assert_eq!(async_document, document);
+        assert_eq!(document.feature.unwrap().name, "Grüße");
+    }
+
+    #[tokio::test]
+    async fn reports_errors_of_async_reader() {
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut parser =
+            Parser::with_builder(DocumentBuilder::with_id_generator(&mut id_generator));
+
+        let parse_error = parser
+            .parse_async_reader(ChunkedReader::new("Given a step without a feature\n"))
+            .await
+            .unwrap_err();
+        match parse_error {
+            Error::Composite(errors) => assert_eq!(errors.len(), 1),
+            error => panic!("unexpected error: {}", error),
+        }
+
+        let io_error = parser.parse_async_reader(FailingReader).await.unwrap_err();
+        match io_error {
+            Error::Io(error) => assert_eq!(error.to_string(), "stream closed"),
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn parses_paths_like_parse_paths() {
+        let mut paths = std::fs::read_dir("../testdata/good")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().map_or(false, |ext| ext == "feature"))
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+        assert!(paths.len() > MAX_CONCURRENT_READS);
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let async_envelopes = parse_paths_async(&paths, include_all(), &mut id_generator)
+            .await
+            .unwrap();
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let envelopes = crate::parse_paths(&paths, include_all(), &mut id_generator).unwrap();
+
+        assert!(!envelopes.is_empty());
+        assert_eq!(async_envelopes, envelopes);
+    }
+
+    #[tokio::test]
+    async fn fails_on_missing_path() {
+        let mut id_generator = IncrementingIdGenerator::new();
+        let paths = [
+            "../testdata/good/minimal.feature",
+            "../testdata/missing.feature",
+        ];
+        let result = parse_paths_async(&paths, include_all(), &mut id_generator).await;
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn parse_paths_async_is_send() {
+        fn assert_send<T: Send>(_: T) {}
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let paths = vec![PathBuf::from("../testdata/good/minimal.feature")];
+        assert_send(parse_paths_async(paths, include_all(), &mut id_generator));
+    }
+
+    fn include_all() -> IncludeOptions {
+        IncludeOptions {
+            source: true,
+            gherkin_document: true,
+            pickles: true,
+        }
+    }
+}
@@ -0,0 +1,371 @@
+//! Selection of the pickles which changed between two versions of a feature file.
+//!
+//! The versions are compared on the AST, so formatting-only changes select nothing. A pickle is
+//! selected if its scenario is new, or if its tags, background steps, scenario steps or examples
+//! row changed. Scenarios are matched by their rule and name, so renamed scenarios are new.
+
+use std::fmt;
+use std::path::Path;
+
+use cucumber_messages::ast;
+use cucumber_messages::id_generator::IdGenerator;
+use cucumber_messages::pickle::Pickle;
+use serde::Serialize;
+
+use crate::cuke::{Compiler, Cuke, Location};
+use crate::diff::{cells, step_keys, StepKey};
+use crate::source_parser::SourceParser;
+use crate::{DocumentBuilder, Result};
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeReason {
+    /// There is no scenario with the same rule and name in the old version.
+    Added,
+    Tags,
+    Background,
+    Steps,
+    ExamplesRow,
+}
+
+/// A cuke of the new version with the reasons why it is selected.
+#[derive(Debug)]
+pub struct ChangedCuke<'d> {
+    pub cuke: Cuke<'d>,
+    pub reasons: Vec<ChangeReason>,
+}
+
+/// A pickle of the new version with the locations of its cuke and the reasons why it is selected.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedPickle {
+    pub pickle: Pickle,
+    pub locations: Vec<Location>,
+    pub reasons: Vec<ChangeReason>,
+}
+
+impl<'d> From<ChangedCuke<'d>> for ChangedPickle {
+    fn from(changed_cuke: ChangedCuke<'d>) -> Self {
+        ChangedPickle {
+            locations: changed_cuke.cuke.locations.clone(),
+            pickle: Pickle::from(changed_cuke.cuke),
+            reasons: changed_cuke.reasons,
+        }
+    }
+}
+
+/// Returns the cukes of the new version which changed compared to the old document.
+pub fn changed_cukes<'d>(old: &ast::GherkinDocument, new: Vec<Cuke<'d>>) -> Vec<ChangedCuke<'d>> {
+    let old_scenarios = OldScenario::collect(old);
+    new.into_iter()
+        .filter_map(|cuke| {
+            let reasons = change_reasons(&old_scenarios, &cuke);
+            if reasons.is_empty() {
+                None
+            } else {
+                Some(ChangedCuke { cuke, reasons })
+            }
+        })
+        .collect()
+}
+
+/// Parses both versions of the feature file at `uri` and returns the changed pickles of the
+/// new version. An empty old source selects all pickles.
+pub fn changed_pickles(
+    uri: &str,
+    old_source: &str,
+    new_source: &str,
+    id_generator: &mut dyn IdGenerator,
+) -> Result<Vec<ChangedPickle>> {
+    let builder = DocumentBuilder::with_id_generator(id_generator);
+    let mut parser = SourceParser::new(builder);
+    let media_type = crate::media_type(Path::new(uri));
+
+    let mut old = parser.parse_str(old_source, media_type)?;
+    old.uri = uri.to_string();
+    let mut new = parser.parse_str(new_source, media_type)?;
+    new.uri = uri.to_string();
+
+    let builder = parser.builder_mut();
+    let step_keyword_types = builder.take_step_keyword_types();
+    let mut compiler = Compiler::new(builder.id_generator_mut());
+    let cukes = compiler.compile_with(&new, &step_keyword_types)?;
+    let changed_pickles = changed_cukes(&old, cukes)
+        .into_iter()
+        .map(ChangedPickle::from)
+        .collect();
+    Ok(changed_pickles)
+}
+
+/// A scenario of the old version with the context its cukes are compiled in.
+struct OldScenario<'a> {
+    feature: &'a ast::Feature,
+    rule: Option<&'a str>,
+    background_steps: Vec<StepKey<'a>>,
+    scenario: &'a ast::Scenario,
+}
+
+impl<'a> OldScenario<'a> {
+    fn collect(gherkin_document: &'a ast::GherkinDocument) -> Vec<OldScenario<'a>> {
+        let mut old_scenarios = Vec::new();
+        let feature = match &gherkin_document.feature {
+            Some(feature) => feature,
+            None => return old_scenarios,
+        };
+
+        let mut feature_background = None;
+        for feature_child in &feature.children {
+            match &feature_child.value {
+                Some(ast::FeatureChildValue::Background(background)) => {
+                    feature_background = Some(background)
+                }
+                Some(ast::FeatureChildValue::Scenario(scenario)) => {
+                    old_scenarios.push(OldScenario {
+                        feature,
+                        rule: None,
+                        background_steps: background_steps(feature_background, None),
+                        scenario,
+                    })
+                }
+                Some(ast::FeatureChildValue::Rule(rule)) => {
+                    let mut rule_background = None;
+                    for rule_child in &rule.children {
+                        match &rule_child.value {
+                            Some(ast::RuleChildValue::Background(background)) => {
+                                rule_background = Some(background)
+                            }
+                            Some(ast::RuleChildValue::Scenario(scenario)) => {
+                                old_scenarios.push(OldScenario {
+                                    feature,
+                                    rule: Some(&rule.name),
+                                    background_steps: background_steps(
+                                        feature_background,
+                                        rule_background,
+                                    ),
+                                    scenario,
+                                })
+                            }
+                            None => {}
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+        old_scenarios
+    }
+}
+
+fn change_reasons(old_scenarios: &[OldScenario<'_>], cuke: &Cuke<'_>) -> Vec<ChangeReason> {
+    let rule = cuke.rule.as_ref().map(|rule| rule.name);
+    let old = old_scenarios
+        .iter()
+        .find(|old| old.rule == rule && old.scenario.name == cuke.scenario.name);
+    let old = match old {
+        Some(old) => old,
+        None => return vec![ChangeReason::Added],
+    };
+
+    // the examples and row of an outline cuke, identified by the id of the row
+    let examples_row = cuke.ast_node_ids.get(1).and_then(|&row_id| {
+        cuke.scenario.examples.iter().find_map(|examples| {
+            let row = examples.table_body.iter().find(|row| row.id == row_id)?;
+            Some((examples, row))
+        })
+    });
+    let old_examples = examples_row.and_then(|(examples, _row)| {
+        old.scenario
+            .examples
+            .iter()
+            .find(|old_examples| old_examples.name == examples.name)
+    });
+
+    let mut reasons = Vec::new();
+
+    let mut tags = cuke.tags.iter().map(|tag| tag.name).collect::<Vec<&str>>();
+    let mut old_tags = old
+        .feature
+        .tags
+        .iter()
+        .chain(&old.scenario.tags)
+        .chain(old_examples.map_or(&[][..], |examples| &examples.tags))
+        .map(|tag| tag.name.as_str())
+        .collect::<Vec<&str>>();
+    tags.sort();
+    old_tags.sort();
+    if tags != old_tags {
+        reasons.push(ChangeReason::Tags);
+    }
+
+    let background_steps = background_steps(cuke.feature_background, cuke.rule_background);
+    if background_steps != old.background_steps {
+        reasons.push(ChangeReason::Background);
+    }
+
+    if step_keys(&cuke.scenario.steps) != step_keys(&old.scenario.steps) {
+        reasons.push(ChangeReason::Steps);
+    }
+
+    if let Some((examples, row)) = examples_row {
+        let header = examples.table_header.as_ref().map(cells);
+        let row = cells(row);
+        let has_old_row = old_examples.map_or(false, |old_examples| {
+            old_examples.table_header.as_ref().map(cells) == header
+                && old_examples
+                    .table_body
+                    .iter()
+                    .any(|old_row| cells(old_row) == row)
+        });
+        if !has_old_row {
+            reasons.push(ChangeReason::ExamplesRow);
+        }
+    }
+
+    reasons
+}
+
+fn background_steps<'a>(
+    feature_background: Option<&'a ast::Background>,
+    rule_background: Option<&'a ast::Background>,
+) -> Vec<StepKey<'a>> {
+    feature_background
+        .into_iter()
+        .chain(rule_background)
+        .flat_map(|background| step_keys(&background.steps))
+        .collect()
+}
+
+impl fmt::Display for ChangeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChangeReason::Added => "added",
+            ChangeReason::Tags => "tags",
+            ChangeReason::Background => "background",
+            ChangeReason::Steps => "steps",
+            ChangeReason::ExamplesRow => "examples row",
+        })
+    }
+}
+
+/// Displays the changed pickle as `<uri>:<line>: <name> (<reasons>)`,
+/// with the line of the examples row for outlines.
+impl fmt::Display for ChangedPickle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = self.locations.first().map_or(0, |location| location.line);
+        write!(f, "{}:{}: {} (", self.pickle.uri, line, self.pickle.name)?;
+        for (index, reason) in self.reasons.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", reason)?;
+        }
+        f.write_str(")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use super::*;
+
+    const OLD_SOURCE: &str = r#"
+Feature: Cukes
+  Background:
+    Given a basket
+
+  Scenario: eating
+    When I eat 2 cukes
+
+  Scenario: unchanged
+    Then nothing happens
+
+  Rule: Outlines
+    Scenario Outline: counting
+      Given I have <count> cukes
+
+      Examples:
+        | count |
+        | 1     |
+        | 2     |
+"#;
+
+    fn changed(new_source: &str) -> Vec<String> {
+        let mut id_generator = IncrementingIdGenerator::new();
+        changed_pickles("cukes.feature", OLD_SOURCE, new_source, &mut id_generator)
+            .unwrap()
+            .iter()
+            .map(ChangedPickle::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn selects_nothing_for_formatting_changes() {
+        let new_source = r#"
+Feature:  Cukes
+  # a comment
+  Background:
+      Given a basket
+  Scenario: eating
+      When   I eat 2 cukes
+  Scenario: unchanged
+      Then nothing happens
+  Rule: Outlines
+      Scenario Outline: counting
+        Given I have <count> cukes
+        Examples:
+          | count |
+          |     1 |
+          |     2 |
+"#;
+        assert_eq!(changed(new_source), Vec::<String>::new());
+        assert_eq!(changed("").len(), 0);
+    }
+
+    #[test]
+    fn selects_changed_pickles() {
+        let new_source = r#"
+Feature: Cukes
+  Background:
+    Given a basket
+
+  @hungry
+  Scenario: eating
+    When I eat 3 cukes
+
+  Scenario: unchanged
+    Then nothing happens
+
+  Scenario: added
+    Then something happens
+
+  Rule: Outlines
+    Background:
+      Given a table
+
+    Scenario Outline: counting
+      Given I have <count> cukes
+
+      Examples:
+        | count |
+        | 1     |
+        | 3     |
+"#;
+        assert_eq!(
+            changed(new_source),
+            vec![
+                "cukes.feature:7: eating (tags, steps)",
+                "cukes.feature:13: added (added)",
+                "cukes.feature:25: counting (background)",
+                "cukes.feature:26: counting (background, examples row)",
+            ]
+        );
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let all = changed_pickles("cukes.feature", "", OLD_SOURCE, &mut id_generator).unwrap();
+        assert_eq!(all.len(), 4);
+        assert!(all
+            .iter()
+            .all(|changed_pickle| changed_pickle.reasons == vec![ChangeReason::Added]));
+    }
+}
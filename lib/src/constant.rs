@@ -4,3 +4,6 @@ pub const TITLE_KEYWORD_SEPARATOR: &str = ":";
 pub const TABLE_CELL_SEPARATOR: &str = "|";
 pub const DOCSTRING_SEPARATOR: &str = "\"\"\"";
 pub const DOCSTRING_ALTERNATIVE_SEPARATOR: &str = "```";
+pub const MEDIA_TYPE_PLAIN: &str = "text/x.cucumber.gherkin+plain";
+pub const MEDIA_TYPE_MARKDOWN: &str = "text/x.cucumber.gherkin+markdown";
+pub const MARKDOWN_FILE_SUFFIX: &str = ".feature.md";
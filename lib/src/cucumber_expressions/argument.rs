@@ -0,0 +1,64 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::cucumber_expressions::{ParameterType, Result};
+
+/// A capturing group of a match with its nested groups.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Group<'t> {
+    /// The matched text, `None` if the group did not participate in the match.
+    pub value: Option<&'t str>,
+    /// The byte range of the matched text.
+    pub span: Option<Range<usize>>,
+    pub children: Vec<Group<'t>>,
+}
+
+/// The text matched by a parameter of an expression.
+#[derive(Debug, Clone)]
+pub struct Argument<'t> {
+    group: Group<'t>,
+    parameter_type: Arc<ParameterType>,
+}
+
+impl<'t> Argument<'t> {
+    pub(crate) fn new(group: Group<'t>, parameter_type: Arc<ParameterType>) -> Argument<'t> {
+        Argument {
+            group,
+            parameter_type,
+        }
+    }
+
+    pub fn group(&self) -> &Group<'t> {
+        &self.group
+    }
+
+    pub fn parameter_type(&self) -> &ParameterType {
+        &self.parameter_type
+    }
+
+    pub fn text(&self) -> Option<&'t str> {
+        self.group.value
+    }
+
+    /// Returns the byte range of the argument in the matched text.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.group.span.clone()
+    }
+
+    /// Transforms the matched text with the transformer of the parameter type.
+    pub fn value(&self) -> Result<Value> {
+        if self.group.children.is_empty() {
+            self.parameter_type.transform(&[self.group.value])
+        } else {
+            let values = self
+                .group
+                .children
+                .iter()
+                .map(|child| child.value)
+                .collect::<Vec<Option<&str>>>();
+            self.parameter_type.transform(&values)
+        }
+    }
+}
@@ -0,0 +1,549 @@
+use crate::cucumber_expressions::{Error, Result};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum TokenType {
+    StartOfLine,
+    EndOfLine,
+    WhiteSpace,
+    BeginOptional,
+    EndOptional,
+    BeginParameter,
+    EndParameter,
+    Alternation,
+    Text,
+}
+
+/// A token of an expression, `start` and `end` are char indices.
+#[derive(Debug)]
+struct Token {
+    token_type: TokenType,
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// A node of a parsed expression, `start` and `end` are char indices.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum Node {
+    Text {
+        text: String,
+        start: usize,
+        end: usize,
+    },
+    Optional {
+        nodes: Vec<Node>,
+        start: usize,
+        end: usize,
+    },
+    Alternation {
+        alternatives: Vec<Alternative>,
+        start: usize,
+        end: usize,
+    },
+    Parameter {
+        name: String,
+        start: usize,
+        end: usize,
+    },
+    /// The `/` between two alternatives, which only exists while parsing an alternation.
+    AlternativeSeparator { start: usize, end: usize },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct Alternative {
+    pub nodes: Vec<Node>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Node {
+    pub fn start(&self) -> usize {
+        match *self {
+            Node::Text { start, .. }
+            | Node::Optional { start, .. }
+            | Node::Alternation { start, .. }
+            | Node::Parameter { start, .. }
+            | Node::AlternativeSeparator { start, .. } => start,
+        }
+    }
+
+    pub fn end(&self) -> usize {
+        match *self {
+            Node::Text { end, .. }
+            | Node::Optional { end, .. }
+            | Node::Alternation { end, .. }
+            | Node::Parameter { end, .. }
+            | Node::AlternativeSeparator { end, .. } => end,
+        }
+    }
+}
+
+/// Creates the error of a problem with the chars from `start` to `end` of an expression.
+pub(crate) fn expression_error(
+    expression: &str,
+    start: usize,
+    end: usize,
+    problem: &str,
+    solution: &str,
+) -> Error {
+    Error::CucumberExpression {
+        expression: expression.to_string(),
+        start,
+        end,
+        message: format!("{}.\n{}", problem, solution),
+    }
+}
+
+/// Parses a Cucumber Expression into its nodes.
+pub(crate) fn parse(expression: &str) -> Result<Vec<Node>> {
+    let tokens = tokenize(expression)?;
+    let parser = Parser { expression, tokens };
+    // the first token is the start of line
+    let (_, nodes) = parser.parse_tokens_until(&TOP_LEVEL_PARSERS, 1, &[TokenType::EndOfLine])?;
+    Ok(nodes)
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![Token {
+        token_type: TokenType::StartOfLine,
+        text: String::new(),
+        start: 0,
+        end: 0,
+    }];
+    let mut chars = expression.chars().enumerate();
+    let mut end = 0;
+
+    while let Some((index, c)) = chars.next() {
+        end = index + 1;
+        let (token_type, c) = if c == '\\' {
+            match chars.next() {
+                Some((_, escaped)) if is_escapable(escaped) => {
+                    end = index + 2;
+                    (TokenType::Text, escaped)
+                }
+                Some(_) => {
+                    return Err(expression_error(
+                        expression,
+                        index,
+                        index + 1,
+                        "Only the characters '{', '}', '(', ')', '\\', '/' and whitespace can be escaped",
+                        "If you did mean to use an '\\' you can use '\\\\' to escape it",
+                    ));
+                }
+                None => {
+                    return Err(expression_error(
+                        expression,
+                        index,
+                        index + 1,
+                        "The end of line can not be escaped",
+                        "You can use '\\\\' to escape the '\\'",
+                    ));
+                }
+            }
+        } else {
+            (token_type_of(c), c)
+        };
+
+        let merges_with_previous = match tokens.last() {
+            Some(previous) => {
+                previous.token_type == token_type
+                    && (token_type == TokenType::Text || token_type == TokenType::WhiteSpace)
+            }
+            None => false,
+        };
+        if merges_with_previous {
+            let previous = tokens.last_mut().unwrap();
+            previous.text.push(c);
+            previous.end = end;
+        } else {
+            tokens.push(Token {
+                token_type,
+                text: c.to_string(),
+                start: index,
+                end,
+            });
+        }
+    }
+
+    tokens.push(Token {
+        token_type: TokenType::EndOfLine,
+        text: String::new(),
+        start: end,
+        end,
+    });
+    Ok(tokens)
+}
+
+fn is_escapable(c: char) -> bool {
+    c.is_whitespace() || "{}()\\/".contains(c)
+}
+
+fn token_type_of(c: char) -> TokenType {
+    match c {
+        '(' => TokenType::BeginOptional,
+        ')' => TokenType::EndOptional,
+        '{' => TokenType::BeginParameter,
+        '}' => TokenType::EndParameter,
+        '/' => TokenType::Alternation,
+        c if c.is_whitespace() => TokenType::WhiteSpace,
+        _ => TokenType::Text,
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum NodeParser {
+    Alternation,
+    AlternativeSeparator,
+    Optional,
+    Parameter,
+    Name,
+    Text,
+}
+
+const TOP_LEVEL_PARSERS: [NodeParser; 4] = [
+    NodeParser::Alternation,
+    NodeParser::Optional,
+    NodeParser::Parameter,
+    NodeParser::Text,
+];
+const ALTERNATIVE_PARSERS: [NodeParser; 4] = [
+    NodeParser::AlternativeSeparator,
+    NodeParser::Optional,
+    NodeParser::Parameter,
+    NodeParser::Text,
+];
+const OPTIONAL_PARSERS: [NodeParser; 3] = [
+    NodeParser::Optional,
+    NodeParser::Parameter,
+    NodeParser::Text,
+];
+const PARAMETER_PARSERS: [NodeParser; 1] = [NodeParser::Name];
+
+/// The result of a parser: the number of consumed tokens and the parsed nodes.
+type Parsed = Result<(usize, Vec<Node>)>;
+
+struct Parser<'e> {
+    expression: &'e str,
+    tokens: Vec<Token>,
+}
+
+impl<'e> Parser<'e> {
+    fn parse_tokens_until(
+        &self,
+        parsers: &[NodeParser],
+        start: usize,
+        end_token_types: &[TokenType],
+    ) -> Parsed {
+        let mut current = start;
+        let mut nodes = Vec::new();
+
+        while current < self.tokens.len() {
+            let token = &self.tokens[current];
+            if end_token_types.contains(&token.token_type) {
+                break;
+            }
+
+            let (consumed, parsed_nodes) = self.parse_token(parsers, current)?;
+            if consumed == 0 {
+                return Err(expression_error(
+                    self.expression,
+                    token.start,
+                    token.end,
+                    &format!("Unexpected '{}'", token.text),
+                    "You can use '\\' to escape it",
+                ));
+            }
+            current += consumed;
+            nodes.extend(parsed_nodes);
+        }
+
+        Ok((current - start, nodes))
+    }
+
+    fn parse_token(&self, parsers: &[NodeParser], current: usize) -> Parsed {
+        for parser in parsers {
+            let parsed = match parser {
+                NodeParser::Alternation => self.parse_alternation(current)?,
+                NodeParser::AlternativeSeparator => self.parse_alternative_separator(current),
+                NodeParser::Optional => self.parse_optional(current)?,
+                NodeParser::Parameter => self.parse_parameter(current)?,
+                NodeParser::Name => self.parse_name(current)?,
+                NodeParser::Text => self.parse_text(current)?,
+            };
+            if parsed.0 != 0 {
+                return Ok(parsed);
+            }
+        }
+        Ok((0, Vec::new()))
+    }
+
+    fn token_type(&self, index: usize) -> TokenType {
+        self.tokens[index].token_type
+    }
+
+    fn parse_alternation(&self, current: usize) -> Parsed {
+        let left_boundaries = [
+            TokenType::StartOfLine,
+            TokenType::WhiteSpace,
+            TokenType::EndParameter,
+        ];
+        if current == 0 || !left_boundaries.contains(&self.token_type(current - 1)) {
+            return Ok((0, Vec::new()));
+        }
+
+        let right_boundaries = [
+            TokenType::WhiteSpace,
+            TokenType::EndOfLine,
+            TokenType::BeginParameter,
+        ];
+        let (consumed, nodes) =
+            self.parse_tokens_until(&ALTERNATIVE_PARSERS, current, &right_boundaries)?;
+        let has_separator = nodes.iter().any(|node| match node {
+            Node::AlternativeSeparator { .. } => true,
+            _ => false,
+        });
+        if !has_separator {
+            return Ok((0, Vec::new()));
+        }
+
+        // the right boundary is not consumed
+        let start = self.tokens[current].start;
+        let end = self.tokens[current + consumed].start;
+        let alternation = Node::Alternation {
+            alternatives: split_alternatives(start, end, nodes),
+            start,
+            end,
+        };
+        Ok((consumed, vec![alternation]))
+    }
+
+    fn parse_alternative_separator(&self, current: usize) -> (usize, Vec<Node>) {
+        let token = &self.tokens[current];
+        if token.token_type != TokenType::Alternation {
+            return (0, Vec::new());
+        }
+
+        let separator = Node::AlternativeSeparator {
+            start: token.start,
+            end: token.end,
+        };
+        (1, vec![separator])
+    }
+
+    fn parse_optional(&self, current: usize) -> Parsed {
+        let begin = &self.tokens[current];
+        if begin.token_type != TokenType::BeginOptional {
+            return Ok((0, Vec::new()));
+        }
+
+        let end_token_types = [TokenType::EndOptional, TokenType::EndOfLine];
+        let (consumed, nodes) =
+            self.parse_tokens_until(&OPTIONAL_PARSERS, current + 1, &end_token_types)?;
+        let end = &self.tokens[current + 1 + consumed];
+        if end.token_type != TokenType::EndOptional {
+            return Err(expression_error(
+                self.expression,
+                begin.start,
+                begin.end,
+                "The '(' does not have a matching ')'",
+                "If you did not intend to use optional text you can use '\\(' to escape the optional text",
+            ));
+        }
+
+        let optional = Node::Optional {
+            nodes,
+            start: begin.start,
+            end: end.end,
+        };
+        Ok((consumed + 2, vec![optional]))
+    }
+
+    fn parse_parameter(&self, current: usize) -> Parsed {
+        let begin = &self.tokens[current];
+        if begin.token_type != TokenType::BeginParameter {
+            return Ok((0, Vec::new()));
+        }
+
+        let end_token_types = [TokenType::EndParameter, TokenType::EndOfLine];
+        let (consumed, nodes) =
+            self.parse_tokens_until(&PARAMETER_PARSERS, current + 1, &end_token_types)?;
+        let end = &self.tokens[current + 1 + consumed];
+        if end.token_type != TokenType::EndParameter {
+            return Err(expression_error(
+                self.expression,
+                begin.start,
+                begin.end,
+                "The '{' does not have a matching '}'",
+                "If you did not intend to use a parameter you can use '\\{' to escape the parameter",
+            ));
+        }
+
+        let name = nodes
+            .into_iter()
+            .filter_map(|node| match node {
+                Node::Text { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect();
+        let parameter = Node::Parameter {
+            name,
+            start: begin.start,
+            end: end.end,
+        };
+        Ok((consumed + 2, vec![parameter]))
+    }
+
+    fn parse_name(&self, current: usize) -> Parsed {
+        let token = &self.tokens[current];
+        match token.token_type {
+            TokenType::WhiteSpace | TokenType::Text => Ok((1, vec![self.text_node(token)])),
+            TokenType::BeginOptional
+            | TokenType::EndOptional
+            | TokenType::BeginParameter
+            | TokenType::Alternation => Err(expression_error(
+                self.expression,
+                token.start,
+                token.end,
+                "Parameter names may not contain '{', '}', '(', ')', '\\' or '/'",
+                "Did you mean to use a regular expression?",
+            )),
+            TokenType::StartOfLine | TokenType::EndOfLine | TokenType::EndParameter => {
+                Ok((0, Vec::new()))
+            }
+        }
+    }
+
+    fn parse_text(&self, current: usize) -> Parsed {
+        let token = &self.tokens[current];
+        match token.token_type {
+            TokenType::WhiteSpace
+            | TokenType::Text
+            | TokenType::EndParameter
+            | TokenType::EndOptional => Ok((1, vec![self.text_node(token)])),
+            TokenType::Alternation => Err(expression_error(
+                self.expression,
+                token.start,
+                token.end,
+                "An alternation can not be used inside an optional",
+                "You can use '\\/' to escape the '/'",
+            )),
+            TokenType::StartOfLine
+            | TokenType::EndOfLine
+            | TokenType::BeginOptional
+            | TokenType::BeginParameter => Ok((0, Vec::new())),
+        }
+    }
+
+    fn text_node(&self, token: &Token) -> Node {
+        Node::Text {
+            text: token.text.clone(),
+            start: token.start,
+            end: token.end,
+        }
+    }
+}
+
+fn split_alternatives(start: usize, end: usize, nodes: Vec<Node>) -> Vec<Alternative> {
+    let mut alternatives = Vec::new();
+    let mut alternative_start = start;
+    let mut alternative_nodes = Vec::new();
+
+    for node in nodes {
+        match node {
+            Node::AlternativeSeparator {
+                start: separator_start,
+                end: separator_end,
+            } => {
+                alternatives.push(Alternative {
+                    nodes: alternative_nodes,
+                    start: alternative_start,
+                    end: separator_start,
+                });
+                alternative_nodes = Vec::new();
+                alternative_start = separator_end;
+            }
+            node => alternative_nodes.push(node),
+        }
+    }
+
+    alternatives.push(Alternative {
+        nodes: alternative_nodes,
+        start: alternative_start,
+        end,
+    });
+    alternatives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str, start: usize) -> Node {
+        Node::Text {
+            text: text.to_string(),
+            start,
+            end: start + text.chars().count(),
+        }
+    }
+
+    #[test]
+    fn parses_nodes_with_char_positions() {
+        let nodes = parse("{int} cuke(s) in my belly/stomach").unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![
+                Node::Parameter {
+                    name: String::from("int"),
+                    start: 0,
+                    end: 5,
+                },
+                text(" ", 5),
+                text("cuke", 6),
+                Node::Optional {
+                    nodes: vec![text("s", 11)],
+                    start: 10,
+                    end: 13,
+                },
+                text(" ", 13),
+                text("in", 14),
+                text(" ", 16),
+                text("my", 17),
+                text(" ", 19),
+                Node::Alternation {
+                    alternatives: vec![
+                        Alternative {
+                            nodes: vec![text("belly", 20)],
+                            start: 20,
+                            end: 25,
+                        },
+                        Alternative {
+                            nodes: vec![text("stomach", 26)],
+                            start: 26,
+                            end: 33,
+                        },
+                    ],
+                    start: 20,
+                    end: 33,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_escaped_chars_as_text() {
+        let nodes = parse("\\(a\\) \\{b\\} c\\/d e\\\\f g\\ h").unwrap();
+        let texts = nodes
+            .iter()
+            .map(|node| match node {
+                Node::Text { text, .. } => text.as_str(),
+                node => panic!("unexpected node: {:?}", node),
+            })
+            .collect::<Vec<&str>>();
+
+        assert_eq!(
+            texts,
+            vec!["(a)", " ", "{b}", " ", "c/d", " ", "e\\f", " ", "g h"]
+        );
+    }
+}
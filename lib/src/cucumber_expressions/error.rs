@@ -0,0 +1,49 @@
+use std::fmt;
+use std::result;
+
+use failure::Fail;
+
+/// A type alias for `Result<T, cucumber_expressions::Error>`.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error of defining a parameter type, compiling an expression or transforming an argument.
+#[derive(Fail, Debug)]
+pub enum Error {
+    /// A Cucumber Expression is invalid, `start` and `end` are the chars of the problem.
+    CucumberExpression {
+        expression: String,
+        start: usize,
+        end: usize,
+        message: String,
+    },
+    /// A parameter type could not be defined or could not transform an argument.
+    ParameterType { name: String, message: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::CucumberExpression {
+                ref expression,
+                start,
+                end,
+                ref message,
+            } => {
+                let mut pointer = " ".repeat(start) + "^";
+                if start + 1 < end {
+                    pointer.push_str(&"-".repeat(end - start - 2));
+                    pointer.push('^');
+                }
+                write!(
+                    f,
+                    "This Cucumber Expression has a problem at column {}:\n\n{}\n{}\n{}",
+                    start + 1,
+                    expression,
+                    pointer,
+                    message
+                )
+            }
+            Error::ParameterType { ref message, .. } => f.write_str(message),
+        }
+    }
+}
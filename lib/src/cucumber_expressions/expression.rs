@@ -0,0 +1,404 @@
+use std::sync::Arc;
+
+use regex::Regex;
+
+use crate::cucumber_expressions::ast::{self, Alternative, Node};
+use crate::cucumber_expressions::tree_regexp::GroupBuilder;
+use crate::cucumber_expressions::{Argument, Error, ParameterType, ParameterTypeRegistry, Result};
+use crate::cuke;
+
+/// A compiled Cucumber Expression, like `I have {int} cuke(s)`.
+#[derive(Debug)]
+pub struct CucumberExpression {
+    source: String,
+    regex: Regex,
+    group_builder: GroupBuilder,
+    parameter_types: Vec<Arc<ParameterType>>,
+}
+
+impl CucumberExpression {
+    pub fn new(expression: &str, registry: &ParameterTypeRegistry) -> Result<CucumberExpression> {
+        let nodes = ast::parse(expression)?;
+
+        let mut compiler = Compiler {
+            expression,
+            registry,
+            parameter_types: Vec::new(),
+        };
+        let mut pattern = String::from("^");
+        for node in &nodes {
+            compiler.rewrite(node, &mut pattern)?;
+        }
+        pattern.push('$');
+
+        let regex = Regex::new(&pattern).map_err(|error| {
+            ast::expression_error(
+                expression,
+                0,
+                expression.chars().count(),
+                &format!("The compiled regex is invalid: {}", error),
+                "Please check the regexps of the parameter types",
+            )
+        })?;
+
+        Ok(CucumberExpression {
+            source: expression.to_string(),
+            group_builder: GroupBuilder::new(&pattern),
+            regex,
+            parameter_types: compiler.parameter_types,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    pub fn parameter_types(&self) -> &[Arc<ParameterType>] {
+        &self.parameter_types
+    }
+
+    /// Matches the whole text and returns the arguments of the parameters in order,
+    /// or `None` if the text does not match.
+    pub fn match_text<'t>(&self, text: &'t str) -> Option<Vec<Argument<'t>>> {
+        let captures = self.regex.captures(text)?;
+        let group = self.group_builder.build(&captures);
+
+        // parameters are the only capturing groups of the compiled regex
+        let arguments = group
+            .children
+            .into_iter()
+            .zip(&self.parameter_types)
+            .map(|(group, parameter_type)| Argument::new(group, Arc::clone(parameter_type)))
+            .collect();
+        Some(arguments)
+    }
+
+    /// Matches the text of a step.
+    pub fn match_step<'s>(&self, step: &'s cuke::Step<'_>) -> Option<Vec<Argument<'s>>> {
+        self.match_text(&step.text)
+    }
+}
+
+struct Compiler<'e, 'r> {
+    expression: &'e str,
+    registry: &'r ParameterTypeRegistry,
+    parameter_types: Vec<Arc<ParameterType>>,
+}
+
+impl<'e, 'r> Compiler<'e, 'r> {
+    fn rewrite(&mut self, node: &Node, pattern: &mut String) -> Result<()> {
+        match node {
+            Node::Text { text, .. } => pattern.push_str(&regex::escape(text)),
+            Node::Optional { nodes, .. } => {
+                self.assert_no_parameters(nodes)?;
+                self.assert_no_optionals(nodes)?;
+                self.assert_not_empty(nodes, node, "An optional must contain some text")?;
+
+                pattern.push_str("(?:");
+                for node in nodes {
+                    self.rewrite(node, pattern)?;
+                }
+                pattern.push_str(")?");
+            }
+            Node::Alternation { alternatives, .. } => {
+                pattern.push_str("(?:");
+                for (index, alternative) in alternatives.iter().enumerate() {
+                    self.assert_alternative_not_empty(alternative)?;
+                    if index > 0 {
+                        pattern.push('|');
+                    }
+                    for node in &alternative.nodes {
+                        self.rewrite(node, pattern)?;
+                    }
+                }
+                pattern.push(')');
+            }
+            Node::Parameter { name, start, end } => {
+                let parameter_type = self.lookup_parameter_type(name, *start, *end)?;
+                push_parameter_regexps(parameter_type.regexps(), pattern);
+                self.parameter_types.push(parameter_type);
+            }
+            Node::AlternativeSeparator { .. } => unreachable!("separators are split off"),
+        }
+        Ok(())
+    }
+
+    fn lookup_parameter_type(
+        &self,
+        name: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<Arc<ParameterType>> {
+        if name.chars().any(|c| "[]$.|?*+".contains(c)) {
+            return Err(ast::expression_error(
+                self.expression,
+                start,
+                end,
+                "Parameter names may not contain '[', ']', '(', ')', '$', '.', '|', '?', '*', '+'",
+                "Did you mean to use a regular expression?",
+            ));
+        }
+
+        match self.registry.lookup_by_type_name(name) {
+            Some(parameter_type) => Ok(Arc::clone(parameter_type)),
+            None => Err(ast::expression_error(
+                self.expression,
+                start,
+                end,
+                &format!("Undefined parameter type '{}'", name),
+                &format!("Please register a ParameterType for '{}'", name),
+            )),
+        }
+    }
+
+    fn assert_no_parameters(&self, nodes: &[Node]) -> Result<()> {
+        match nodes.iter().find(|node| match node {
+            Node::Parameter { .. } => true,
+            _ => false,
+        }) {
+            Some(node) => Err(self.error(
+                node,
+                "An optional may not contain a parameter type",
+                "If you did not mean to use a parameter type you can use '\\{' to escape the '{'",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn assert_no_optionals(&self, nodes: &[Node]) -> Result<()> {
+        match nodes.iter().find(|node| match node {
+            Node::Optional { .. } => true,
+            _ => false,
+        }) {
+            Some(node) => Err(self.error(
+                node,
+                "An optional may not contain an other optional",
+                "If you did not mean to use an optional type you can use '\\(' to escape the '('. \
+                 For more complicated expressions consider using a regular expression instead",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn assert_not_empty(&self, nodes: &[Node], node: &Node, problem: &str) -> Result<()> {
+        let has_text = nodes.iter().any(|node| match node {
+            Node::Text { .. } => true,
+            _ => false,
+        });
+        if has_text {
+            Ok(())
+        } else {
+            Err(self.error(
+                node,
+                problem,
+                "If you did not mean to use an optional you can use '\\(' to escape the '('",
+            ))
+        }
+    }
+
+    fn assert_alternative_not_empty(&self, alternative: &Alternative) -> Result<()> {
+        if alternative.nodes.is_empty() {
+            return Err(ast::expression_error(
+                self.expression,
+                alternative.start,
+                alternative.end,
+                "Alternative may not be empty",
+                "If you did not mean to use an alternative you can use '\\/' to escape the '/'",
+            ));
+        }
+
+        let is_optional = |node: &Node| match node {
+            Node::Optional { .. } => true,
+            _ => false,
+        };
+        if alternative.nodes.iter().all(is_optional) {
+            return Err(ast::expression_error(
+                self.expression,
+                alternative.start,
+                alternative.end,
+                "An alternative may not exclusively contain optionals",
+                "If you did not mean to use an optional you can use '\\(' to escape the '('",
+            ));
+        }
+        Ok(())
+    }
+
+    fn error(&self, node: &Node, problem: &str, solution: &str) -> Error {
+        ast::expression_error(self.expression, node.start(), node.end(), problem, solution)
+    }
+}
+
+fn push_parameter_regexps(regexps: &[String], pattern: &mut String) {
+    pattern.push('(');
+    if regexps.len() == 1 {
+        pattern.push_str(&regexps[0]);
+    } else {
+        for (index, regexp) in regexps.iter().enumerate() {
+            if index > 0 {
+                pattern.push('|');
+            }
+            pattern.push_str("(?:");
+            pattern.push_str(regexp);
+            pattern.push(')');
+        }
+    }
+    pattern.push(')');
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn compile(expression: &str) -> Result<CucumberExpression> {
+        CucumberExpression::new(expression, &ParameterTypeRegistry::new())
+    }
+
+    fn error_message(expression: &str) -> String {
+        compile(expression).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn extracts_arguments_with_byte_spans() {
+        let expression = compile("Grüße an {word} mit {int} Äpfel(n)").unwrap();
+        let arguments = expression
+            .match_text("Grüße an Jürgen mit 12 Äpfeln")
+            .unwrap();
+
+        let spans = arguments
+            .iter()
+            .map(|argument| argument.span().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![11..18, 23..25]);
+        assert_eq!(arguments[0].text(), Some("Jürgen"));
+        assert_eq!(arguments[0].parameter_type().name(), "word");
+        assert_eq!(arguments[1].value().unwrap(), json!(12));
+
+        assert!(expression
+            .match_text("Grüße an Jürgen mit zwölf Äpfeln")
+            .is_none());
+    }
+
+    #[test]
+    fn uses_custom_parameter_types() {
+        let mut registry = ParameterTypeRegistry::new();
+        let color = ParameterType::new("color", &["red|green", "blue"], |groups| {
+            Ok(json!(groups[0].map(str::to_uppercase)))
+        })
+        .unwrap();
+        registry.define_parameter_type(color).unwrap();
+        let point = ParameterType::new("point", &[r"(\d+),(\d+)"], |groups| {
+            Ok(json!({ "x": groups[0], "y": groups[1] }))
+        })
+        .unwrap();
+        registry.define_parameter_type(point).unwrap();
+
+        let expression = CucumberExpression::new("a {color} dot at {point}", &registry).unwrap();
+        assert_eq!(
+            expression.regex().as_str(),
+            r"^a ((?:red|green)|(?:blue)) dot at ((\d+),(\d+))$"
+        );
+
+        let arguments = expression.match_text("a blue dot at 3,4").unwrap();
+        assert_eq!(arguments[0].value().unwrap(), json!("BLUE"));
+        assert_eq!(arguments[1].value().unwrap(), json!({ "x": "3", "y": "4" }));
+        assert_eq!(arguments[1].group().children[1].span, Some(16..17));
+
+        let duplicate = ParameterType::new("color", &["red"], |_| Ok(json!(null))).unwrap();
+        assert_eq!(
+            registry
+                .define_parameter_type(duplicate)
+                .unwrap_err()
+                .to_string(),
+            "There is already a parameter type with name color"
+        );
+        assert!(ParameterType::new("a.b", &["x"], |_| Ok(json!(null))).is_err());
+        assert!(ParameterType::new("unbalanced", &["(x"], |_| Ok(json!(null))).is_err());
+    }
+
+    #[test]
+    fn reports_transformation_errors() {
+        let expression = compile("{int} cukes").unwrap();
+        let arguments = expression.match_text("99999999999 cukes").unwrap();
+
+        assert!(arguments[0].value().is_err());
+    }
+
+    #[test]
+    fn points_at_the_problem() {
+        assert_eq!(
+            error_message("three () mice"),
+            "This Cucumber Expression has a problem at column 7:\n\
+             \n\
+             three () mice\n      ^^\n\
+             An optional must contain some text.\n\
+             If you did not mean to use an optional you can use '\\(' to escape the '('"
+        );
+        assert_eq!(
+            error_message("I have {color} cukes"),
+            "This Cucumber Expression has a problem at column 8:\n\
+             \n\
+             I have {color} cukes\n       ^-----^\n\
+             Undefined parameter type 'color'.\n\
+             Please register a ParameterType for 'color'"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        let problem = |expression: &str| {
+            let message = error_message(expression);
+            message.lines().nth(4).unwrap().to_string()
+        };
+
+        assert_eq!(
+            problem("three (blind) ((very)) mice"),
+            "An optional may not contain an other optional."
+        );
+        assert_eq!(
+            problem("three ({int}) mice"),
+            "An optional may not contain a parameter type."
+        );
+        assert_eq!(
+            problem("three (blind/hungry) mice"),
+            "An alternation can not be used inside an optional."
+        );
+        assert_eq!(
+            problem("three /blind mice"),
+            "Alternative may not be empty."
+        );
+        assert_eq!(
+            problem("three (blind)/hungry mice"),
+            "An alternative may not exclusively contain optionals."
+        );
+        assert_eq!(
+            problem("three (blind mice"),
+            "The '(' does not have a matching ')'."
+        );
+        assert_eq!(
+            problem("three {int mice"),
+            "The '{' does not have a matching '}'."
+        );
+        assert_eq!(
+            problem("three {(int)} mice"),
+            "Parameter names may not contain '{', '}', '(', ')', '\\' or '/'."
+        );
+        assert_eq!(
+            problem("three {int*} mice"),
+            "Parameter names may not contain '[', ']', '(', ')', '$', '.', '|', '?', '*', '+'."
+        );
+        assert_eq!(
+            problem("three \\[blind] mice"),
+            "Only the characters '{', '}', '(', ')', '\\', '/' and whitespace can be escaped."
+        );
+        assert_eq!(
+            problem("three blind mice\\"),
+            "The end of line can not be escaped."
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+/// A Cucumber Expression generated from a step text.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GeneratedExpression {
+    pub source: String,
+    pub parameters: Vec<GeneratedParameter>,
+}
+
+/// A parameter of a generated expression, named after its type and numbered from the second
+/// occurrence on, like `int`, `int2`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GeneratedParameter {
+    pub name: String,
+    pub type_name: &'static str,
+}
+
+/// Generates a Cucumber Expression which matches the text, with numbers replaced by `{int}` or
+/// `{float}` and quoted text replaced by `{string}`.
+///
+/// Parameters must start and end at word boundaries, so `don't` or `mp3` stay literal text.
+pub fn generate_expression(text: &str) -> GeneratedExpression {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut source = String::with_capacity(text.len());
+    let mut parameters = Vec::new();
+    let mut type_counts = HashMap::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let parameter = if i == 0 || !chars[i - 1].is_alphanumeric() {
+            match_parameter(&chars, i)
+        } else {
+            None
+        };
+
+        match parameter {
+            Some((type_name, end)) => {
+                let count = type_counts.entry(type_name).or_insert(0);
+                *count += 1;
+                let name = if *count == 1 {
+                    type_name.to_string()
+                } else {
+                    format!("{}{}", type_name, count)
+                };
+
+                source.push('{');
+                source.push_str(type_name);
+                source.push('}');
+                parameters.push(GeneratedParameter { name, type_name });
+                i = end;
+            }
+            None => {
+                if "\\({/".contains(chars[i]) {
+                    source.push('\\');
+                }
+                source.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+
+    GeneratedExpression { source, parameters }
+}
+
+/// Returns the parameter type and end of the parameter which starts at `start`.
+fn match_parameter(chars: &[char], start: usize) -> Option<(&'static str, usize)> {
+    let (type_name, end) = match chars[start] {
+        '"' | '\'' => ("string", match_quoted(chars, start)?),
+        _ => match_number(chars, start)?,
+    };
+
+    if end < chars.len() && chars[end].is_alphanumeric() {
+        return None;
+    }
+    Some((type_name, end))
+}
+
+fn match_quoted(chars: &[char], start: usize) -> Option<usize> {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn match_number(chars: &[char], start: usize) -> Option<(&'static str, usize)> {
+    let is_digit = |i: usize| i < chars.len() && chars[i].is_ascii_digit();
+
+    let mut i = start;
+    if chars[i] == '-' {
+        i += 1;
+    }
+    if !is_digit(i) {
+        return None;
+    }
+    while is_digit(i) {
+        i += 1;
+    }
+
+    if i < chars.len() && chars[i] == '.' && is_digit(i + 1) {
+        i += 1;
+        while is_digit(i) {
+            i += 1;
+        }
+        return Some(("float", i));
+    }
+    Some(("int", i))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cucumber_expressions::{CucumberExpression, ParameterTypeRegistry};
+
+    use super::*;
+
+    #[test]
+    fn generates_matching_expressions() {
+        let registry = ParameterTypeRegistry::new();
+        let examples = vec![
+            ("I have 3 cukes", "I have {int} cukes"),
+            (
+                "I have -3 cukes and 42 more",
+                "I have {int} cukes and {int} more",
+            ),
+            ("it costs 1.5 or .5", "it costs {float} or .{int}"),
+            (
+                r#"I buy "red" and 'blue' cukes"#,
+                "I buy {string} and {string} cukes",
+            ),
+            (r#"a "quoted \" text""#, "a {string}"),
+            ("I don't play mp3 files", "I don't play mp3 files"),
+            (
+                "a (half) {empty}/full glass",
+                r"a \(half) \{empty}\/full glass",
+            ),
+            ("an \"unclosed quote", "an \"unclosed quote"),
+        ];
+
+        for (text, expected_source) in examples {
+            let expression = generate_expression(text);
+            assert_eq!(expression.source, expected_source, "text: {}", text);
+
+            let cucumber_expression = CucumberExpression::new(&expression.source, &registry)
+                .unwrap_or_else(|error| panic!("text: {}, error: {}", text, error));
+            assert!(
+                cucumber_expression.match_text(text).is_some(),
+                "text: {}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn numbers_parameter_names() {
+        let expression = generate_expression(r#"3 "a" 4 1.5 "b" 5"#);
+        let names = expression
+            .parameters
+            .iter()
+            .map(|parameter| parameter.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            names,
+            vec!["int", "string", "int2", "float", "string2", "int3"]
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! [Cucumber Expressions](https://github.com/cucumber/cucumber-expressions) to bind step texts
+//! to step definitions.
+//!
+//! A `CucumberExpression` is compiled into a regular expression with the help of a
+//! `ParameterTypeRegistry`, which contains the built-in parameter types `{int}`, `{float}`,
+//! `{word}`, `{string}` and `{}` as well as the custom ones.
+//! Step texts can also be matched with a `RegularExpression`, and `generate_expression` suggests
+//! an expression for a step text which doesn't match anything yet.
+
+pub use self::argument::*;
+pub use self::error::*;
+pub use self::expression::*;
+pub use self::generator::*;
+pub use self::parameter_type::*;
+pub use self::regular_expression::*;
+
+mod argument;
+mod ast;
+mod error;
+mod expression;
+mod generator;
+mod parameter_type;
+mod regular_expression;
+mod tree_regexp;
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::result;
+use std::sync::Arc;
+
+use regex::Regex;
+use serde_json::{Number, Value};
+
+use crate::cucumber_expressions::{Error, Result};
+
+/// Transforms the matched groups of a parameter into its value.
+///
+/// The groups are the capturing groups of the parameter type regexps,
+/// or the whole match if the regexps don't have any capturing groups.
+pub type Transformer = dyn Fn(&[Option<&str>]) -> result::Result<Value, String> + Send + Sync;
+
+/// A named type of the parameters in a Cucumber Expression, like `{int}`.
+pub struct ParameterType {
+    name: String,
+    regexps: Vec<String>,
+    transformer: Box<Transformer>,
+}
+
+impl ParameterType {
+    /// Creates a parameter type which matches any of the given regexps.
+    pub fn new<F>(name: &str, regexps: &[&str], transformer: F) -> Result<ParameterType>
+    where
+        F: Fn(&[Option<&str>]) -> result::Result<Value, String> + Send + Sync + 'static,
+    {
+        if let Some(illegal_char) = name.chars().find(|c| "[]()$.|?*+{}/\\".contains(*c)) {
+            return Err(Error::ParameterType {
+                name: name.to_string(),
+                message: format!(
+                    "Illegal character '{}' in parameter name {{{}}}",
+                    illegal_char, name
+                ),
+            });
+        }
+        for regexp in regexps {
+            if let Err(error) = Regex::new(regexp) {
+                return Err(Error::ParameterType {
+                    name: name.to_string(),
+                    message: format!("Invalid regexp '{}': {}", regexp, error),
+                });
+            }
+        }
+
+        Ok(ParameterType {
+            name: name.to_string(),
+            regexps: regexps.iter().map(|regexp| String::from(*regexp)).collect(),
+            transformer: Box::new(transformer),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn regexps(&self) -> &[String] {
+        &self.regexps
+    }
+
+    pub fn transform(&self, groups: &[Option<&str>]) -> Result<Value> {
+        (self.transformer)(groups).map_err(|message| Error::ParameterType {
+            name: self.name.clone(),
+            message,
+        })
+    }
+}
+
+impl fmt::Debug for ParameterType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParameterType")
+            .field("name", &self.name)
+            .field("regexps", &self.regexps)
+            .finish()
+    }
+}
+
+/// The parameter types which can be used in Cucumber Expressions.
+#[derive(Debug)]
+pub struct ParameterTypeRegistry {
+    parameter_types: HashMap<String, Arc<ParameterType>>,
+}
+
+impl Default for ParameterTypeRegistry {
+    fn default() -> Self {
+        ParameterTypeRegistry::new()
+    }
+}
+
+impl ParameterTypeRegistry {
+    /// Creates a registry with the built-in parameter types.
+    pub fn new() -> ParameterTypeRegistry {
+        let mut registry = ParameterTypeRegistry {
+            parameter_types: HashMap::new(),
+        };
+
+        let built_in_parameter_types = vec![
+            ParameterType::new("int", &[r"-?\d+", r"\d+"], transform_int),
+            ParameterType::new(
+                "float",
+                &[r"[-+]?\d*\.?\d+(?:[eE][-+]?\d+)?"],
+                transform_float,
+            ),
+            ParameterType::new("word", &[r"[^\s]+"], transform_text),
+            ParameterType::new(
+                "string",
+                &[r#""([^"\\]*(\\.[^"\\]*)*)"|'([^'\\]*(\\.[^'\\]*)*)'"#],
+                transform_string,
+            ),
+            ParameterType::new("", &[".*"], transform_text),
+        ];
+        for parameter_type in built_in_parameter_types {
+            registry
+                .define_parameter_type(parameter_type.unwrap())
+                .unwrap();
+        }
+
+        registry
+    }
+
+    /// Adds a custom parameter type, whose name must not be used by another one yet.
+    pub fn define_parameter_type(&mut self, parameter_type: ParameterType) -> Result<()> {
+        if self.parameter_types.contains_key(&parameter_type.name) {
+            return Err(Error::ParameterType {
+                name: parameter_type.name.clone(),
+                message: format!(
+                    "There is already a parameter type with name {}",
+                    parameter_type.name
+                ),
+            });
+        }
+
+        self.parameter_types
+            .insert(parameter_type.name.clone(), Arc::new(parameter_type));
+        Ok(())
+    }
+
+    pub fn lookup_by_type_name(&self, name: &str) -> Option<&Arc<ParameterType>> {
+        self.parameter_types.get(name)
+    }
+
+    pub fn parameter_types(&self) -> impl Iterator<Item = &ParameterType> {
+        self.parameter_types
+            .values()
+            .map(|parameter_type| parameter_type.as_ref())
+    }
+}
+
+fn transform_int(groups: &[Option<&str>]) -> result::Result<Value, String> {
+    match groups[0] {
+        Some(text) => text
+            .parse::<i32>()
+            .map(Value::from)
+            .map_err(|error| format!("Cannot transform '{}' to an int: {}", text, error)),
+        None => Ok(Value::Null),
+    }
+}
+
+fn transform_float(groups: &[Option<&str>]) -> result::Result<Value, String> {
+    match groups[0] {
+        Some(text) => match text.parse::<f64>().ok().and_then(Number::from_f64) {
+            Some(number) => Ok(Value::Number(number)),
+            None => Err(format!("Cannot transform '{}' to a float", text)),
+        },
+        None => Ok(Value::Null),
+    }
+}
+
+fn transform_text(groups: &[Option<&str>]) -> result::Result<Value, String> {
+    Ok(groups[0].map_or(Value::Null, Value::from))
+}
+
+/// Uses the double or single quoted text without the quotes and unescapes the quotes within.
+fn transform_string(groups: &[Option<&str>]) -> result::Result<Value, String> {
+    let double_quoted = groups.get(0).cloned().unwrap_or(None);
+    let single_quoted = groups.get(1).cloned().unwrap_or(None);
+
+    let value = match (double_quoted, single_quoted) {
+        (Some(text), _) => Value::from(text.replace("\\\"", "\"")),
+        (None, Some(text)) => Value::from(text.replace("\\'", "'")),
+        (None, None) => Value::Null,
+    };
+    Ok(value)
+}
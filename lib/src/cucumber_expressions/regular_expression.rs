@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use regex::Regex;
+
+use crate::cucumber_expressions::tree_regexp::GroupBuilder;
+use crate::cucumber_expressions::{Argument, ParameterType, ParameterTypeRegistry};
+use crate::cuke;
+
+/// A regular expression to match step texts, like `^I have (\d+) cukes?$`.
+///
+/// Its arguments are the top level capturing groups, which have the anonymous parameter type.
+#[derive(Debug)]
+pub struct RegularExpression {
+    regex: Regex,
+    group_builder: GroupBuilder,
+    parameter_type: Arc<ParameterType>,
+}
+
+impl RegularExpression {
+    pub fn new(regex: Regex, registry: &ParameterTypeRegistry) -> RegularExpression {
+        let parameter_type = registry
+            .lookup_by_type_name("")
+            .expect("the anonymous parameter type is built-in");
+
+        RegularExpression {
+            group_builder: GroupBuilder::new(regex.as_str()),
+            regex,
+            parameter_type: Arc::clone(parameter_type),
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    /// Matches the text and returns the arguments of the capturing groups in order,
+    /// or `None` if the text does not match.
+    pub fn match_text<'t>(&self, text: &'t str) -> Option<Vec<Argument<'t>>> {
+        let captures = self.regex.captures(text)?;
+        let group = self.group_builder.build(&captures);
+
+        let arguments = group
+            .children
+            .into_iter()
+            .map(|group| Argument::new(group, Arc::clone(&self.parameter_type)))
+            .collect();
+        Some(arguments)
+    }
+
+    /// Matches the text of a step.
+    pub fn match_step<'s>(&self, step: &'s cuke::Step<'_>) -> Option<Vec<Argument<'s>>> {
+        self.match_text(&step.text)
+    }
+}
@@ -0,0 +1,100 @@
+use regex::Captures;
+
+use crate::cucumber_expressions::Group;
+
+/// The tree of the capturing groups of a regex, to build nested `Group`s from its captures.
+#[derive(Debug)]
+pub(crate) struct GroupBuilder {
+    index: usize,
+    capturing: bool,
+    children: Vec<GroupBuilder>,
+}
+
+impl GroupBuilder {
+    /// Scans the parentheses of a regex, where the root is the whole match.
+    pub fn new(regex: &str) -> GroupBuilder {
+        let mut stack = vec![GroupBuilder {
+            index: 0,
+            capturing: true,
+            children: Vec::new(),
+        }];
+        let mut next_index = 1;
+        let mut escaping = false;
+        let mut in_char_class = false;
+
+        for (index, c) in regex.char_indices() {
+            if !escaping {
+                match c {
+                    '[' => in_char_class = true,
+                    ']' => in_char_class = false,
+                    '(' if !in_char_class => {
+                        let capturing = is_capturing(&regex[index + 1..]);
+                        stack.push(GroupBuilder {
+                            index: if capturing { next_index } else { 0 },
+                            capturing,
+                            children: Vec::new(),
+                        });
+                        if capturing {
+                            next_index += 1;
+                        }
+                    }
+                    ')' if !in_char_class && stack.len() > 1 => {
+                        let group_builder = stack.pop().unwrap();
+                        let parent = stack.last_mut().unwrap();
+                        if group_builder.capturing {
+                            parent.children.push(group_builder);
+                        } else {
+                            // the captures of a non-capturing group belong to its parent
+                            parent.children.extend(group_builder.children);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            escaping = c == '\\' && !escaping;
+        }
+
+        stack.swap_remove(0)
+    }
+
+    pub fn build<'t>(&self, captures: &Captures<'t>) -> Group<'t> {
+        let capture = captures.get(self.index);
+
+        Group {
+            value: capture.map(|capture| capture.as_str()),
+            span: capture.map(|capture| capture.range()),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.build(captures))
+                .collect(),
+        }
+    }
+}
+
+fn is_capturing(group: &str) -> bool {
+    !group.starts_with('?') || group.starts_with("?P<")
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+
+    #[test]
+    fn builds_nested_groups() {
+        let regex = r#"^a(?:b(c)(?i)[(]\()(d(e)?)$"#;
+        let group_builder = GroupBuilder::new(regex);
+        let captures = Regex::new(regex).unwrap().captures("abc((d").unwrap();
+        let group = group_builder.build(&captures);
+
+        assert_eq!(group.value, Some("abc((d"));
+        assert_eq!(group.children.len(), 2);
+        assert_eq!(group.children[0].value, Some("c"));
+        assert_eq!(group.children[0].span, Some(2..3));
+        assert_eq!(group.children[1].value, Some("d"));
+        assert_eq!(group.children[1].children[0].value, None);
+        assert_eq!(group.children[1].children[0].span, None);
+    }
+}
@@ -1,10 +1,16 @@
 use cucumber_messages::pickle;
+use serde::{Deserialize, Serialize};
 
 use crate::cuke::{Location, String, Table};
 
-#[derive(Debug, Clone)]
+/// The argument of a step.
+///
+/// It serializes to an object with the argument as `docString` or `dataTable` property.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Argument<'d> {
+    #[serde(rename = "docString")]
     String(String<'d>),
+    #[serde(rename = "dataTable")]
     Table(Table<'d>),
 }
 
@@ -15,6 +21,13 @@ impl<'d> Argument<'d> {
             Argument::Table(table) => table.get_location(),
         }
     }
+
+    pub fn into_owned(self) -> Argument<'static> {
+        match self {
+            Argument::String(string) => Argument::String(string.into_owned()),
+            Argument::Table(table) => Argument::Table(table.into_owned()),
+        }
+    }
 }
 
 impl<'d> From<Argument<'d>> for pickle::Argument {
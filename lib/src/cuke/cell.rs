@@ -1,15 +1,25 @@
 use std::borrow::Cow;
 
 use cucumber_messages::pickle;
+use serde::{Deserialize, Serialize};
 
 use crate::cuke::Location;
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Cell<'d> {
     pub location: Location,
     pub value: Cow<'d, str>,
 }
 
+impl<'d> Cell<'d> {
+    pub fn into_owned(self) -> Cell<'static> {
+        Cell {
+            location: self.location,
+            value: Cow::Owned(self.value.into_owned()),
+        }
+    }
+}
+
 impl<'d> AsRef<str> for Cell<'d> {
     fn as_ref(&self) -> &str {
         &self.value.as_ref()
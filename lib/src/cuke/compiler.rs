@@ -1,120 +1,332 @@
 use std::borrow::Cow;
+use std::sync::Arc;
 
 use cucumber_messages::ast;
 use cucumber_messages::id_generator::IdGenerator;
 
-use crate::cuke;
+use crate::cuke::owned::AstPath;
+use crate::cuke::{self, validation};
+use crate::dialect::{Dialect, StepKeywordType, StepKeywordTypes};
+use crate::dialect_provider::BuiltInDialectProvider;
+use crate::parser::DialectProvider;
+use crate::{Error, Location, Result};
 
 pub struct Compiler<'id_gen> {
     id_generator: &'id_gen mut dyn IdGenerator,
+    diagnostics: Vec<cuke::Diagnostic>,
+}
+
+/// A `<placeholder>` in the name, the steps or the step arguments of a scenario outline.
+struct Placeholder<'d> {
+    name: &'d str,
+    location: cuke::Location,
 }
 
 /// A session to store data for the compilation of a single `GherkinDocument`.
 ///
-/// `'d` is the lifetime of the `GherkinDocument`.
-struct Session<'d> {
+/// `'d` is the lifetime of the `GherkinDocument` and `'t` the one of its step keyword types.
+struct Session<'d, 't> {
     cukes: Vec<cuke::Cuke<'d>>,
     uri: &'d str,
     feature: &'d ast::Feature,
+    step_keyword_types: &'t StepKeywordTypes,
+    /// The built-in dialect of the feature language, for steps without a recorded keyword type.
+    dialect: Option<Arc<Dialect>>,
     feature_background: Option<&'d ast::Background>,
     feature_background_steps: Vec<cuke::Step<'d>>,
-    rule: Option<&'d ast::Rule>,
+    rules: Vec<Arc<cuke::Rule<'d>>>,
+    rule: Option<Arc<cuke::Rule<'d>>>,
     rule_background: Option<&'d ast::Background>,
     rule_background_steps: Vec<cuke::Step<'d>>,
+    /// The path of the current scenario.
+    path: AstPath,
+}
+
+impl<'d, 't> Session<'d, 't> {
+    fn new(
+        gherkin_document: &'d ast::GherkinDocument,
+        feature: &'d ast::Feature,
+        step_keyword_types: &'t StepKeywordTypes,
+    ) -> Session<'d, 't> {
+        let dialect = BuiltInDialectProvider::default()
+            .get_dialect(&feature.language, Location::new(0, 0))
+            .ok();
+
+        Session {
+            cukes: Vec::with_capacity(feature.children.len()),
+            uri: &gherkin_document.uri,
+            feature,
+            step_keyword_types,
+            dialect,
+            feature_background: None,
+            feature_background_steps: Vec::new(),
+            rules: Vec::new(),
+            rule: None,
+            rule_background: None,
+            rule_background_steps: Vec::new(),
+            path: AstPath::default(),
+        }
+    }
+
+    fn step_keyword_type(&self, step: &ast::Step) -> StepKeywordType {
+        if let Some(keyword_type) = self.step_keyword_types.get(&step.id) {
+            return *keyword_type;
+        }
+
+        match &self.dialect {
+            Some(dialect) => dialect.get_step_keyword_type(&step.keyword),
+            None => StepKeywordType::Unknown,
+        }
+    }
+
+    /// Returns the location of a node, which only documents built by the parser always have.
+    fn location(
+        &self,
+        location: Option<ast::Location>,
+        node: &str,
+        id: &str,
+    ) -> Result<cuke::Location> {
+        location
+            .map(cuke::Location::from)
+            .ok_or_else(|| Error::InvalidDocument {
+                uri: self.uri.to_string(),
+                message: format!("{} {:?} has no location", node, id),
+            })
+    }
+
+    fn tags(&self, tags: &'d [ast::Tag]) -> Result<Vec<cuke::Tag<'d>>> {
+        tags.iter()
+            .map(|tag| {
+                Ok(cuke::Tag {
+                    name: &tag.name,
+                    location: self.location(tag.location, "tag", &tag.id)?,
+                    ast_node_id: tag.id.as_str(),
+                })
+            })
+            .collect()
+    }
 }
 
 impl<'id_gen> Compiler<'id_gen> {
     pub fn new(id_generator: &'id_gen mut dyn IdGenerator) -> Compiler<'id_gen> {
-        Compiler { id_generator }
+        Compiler {
+            id_generator,
+            diagnostics: Vec::new(),
+        }
     }
 
+    /// Returns the diagnostics of all documents compiled so far.
+    pub fn diagnostics(&self) -> &[cuke::Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<cuke::Diagnostic> {
+        std::mem::replace(&mut self.diagnostics, Vec::new())
+    }
+
+    /// Compiles the `GherkinDocument` into one cuke per scenario and examples row.
+    ///
+    /// The keyword types of the steps are looked up in the built-in dialect of the feature
+    /// language, use `compile_with` for documents of the parser, which may use a custom dialect.
+    ///
+    /// Documents built by the parser can always be compiled, others fail with
+    /// `Error::InvalidDocument` if a node has no location or an examples row has
+    /// a different number of cells than the header, without keeping their diagnostics.
     pub fn compile<'d>(
         &mut self,
         gherkin_document: &'d ast::GherkinDocument,
-    ) -> Vec<cuke::Cuke<'d>> {
+    ) -> Result<Vec<cuke::Cuke<'d>>> {
+        self.compile_with(gherkin_document, &StepKeywordTypes::new())
+    }
+
+    /// Compiles the `GherkinDocument` like `compile`, with the keyword types of its steps
+    /// recorded by the `DocumentBuilder` that built it.
+    ///
+    /// Steps without a recorded keyword type are looked up in the built-in dialect.
+    pub fn compile_with<'d>(
+        &mut self,
+        gherkin_document: &'d ast::GherkinDocument,
+        step_keyword_types: &StepKeywordTypes,
+    ) -> Result<Vec<cuke::Cuke<'d>>> {
         let feature = match &gherkin_document.feature {
             Some(feature) => feature,
-            None => return Vec::new(),
+            None => return Ok(Vec::new()),
         };
+        validation::validate(gherkin_document)?;
 
-        let mut session = Session {
-            cukes: Vec::with_capacity(feature.children.len()),
-            uri: &gherkin_document.uri,
-            feature,
-            feature_background: None,
-            feature_background_steps: Vec::new(),
-            rule: None,
-            rule_background: None,
-            rule_background_steps: Vec::new(),
+        let mut session = Session::new(gherkin_document, feature, step_keyword_types);
+        self.compile_document(&mut session)?;
+        Ok(session.cukes)
+    }
+
+    /// Compiles the shared `GherkinDocument` into cukes which can be sent to other threads.
+    pub fn compile_owned(
+        &mut self,
+        gherkin_document: &Arc<ast::GherkinDocument>,
+    ) -> Result<Vec<cuke::OwnedCuke>> {
+        let cukes = self
+            .compile(gherkin_document)?
+            .into_iter()
+            .map(|cuke| cuke::OwnedCuke::new(cuke, gherkin_document))
+            .collect();
+        Ok(cukes)
+    }
+
+    /// Compiles the `GherkinDocument` like `compile`, but groups the cukes by their rule.
+    pub fn compile_grouped<'d>(
+        &mut self,
+        gherkin_document: &'d ast::GherkinDocument,
+    ) -> Result<Option<cuke::Feature<'d>>> {
+        let feature = match &gherkin_document.feature {
+            Some(feature) => feature,
+            None => return Ok(None),
         };
-        self.compile_feature(&mut session);
-        session.cukes
+        validation::validate(gherkin_document)?;
+
+        let step_keyword_types = StepKeywordTypes::new();
+        let mut session = Session::new(gherkin_document, feature, &step_keyword_types);
+        self.compile_document(&mut session)?;
+        Ok(Some(cuke::Feature::new(
+            session.uri,
+            feature,
+            session.rules,
+            session.cukes,
+        )))
+    }
+
+    /// Compiles the feature of the session, dropping its diagnostics if it is invalid.
+    fn compile_document(&mut self, session: &mut Session<'_, '_>) -> Result<()> {
+        let diagnostics = self.diagnostics.len();
+        let result = self.compile_feature(session);
+        if result.is_err() {
+            self.diagnostics.truncate(diagnostics);
+        }
+        result
     }
 
-    fn compile_feature<'d>(&mut self, session: &mut Session<'d>) {
+    fn compile_feature<'d>(&mut self, session: &mut Session<'d, '_>) -> Result<()> {
         session.feature_background = None;
         session.feature_background_steps = Vec::new();
+        session.path = AstPath::default();
 
-        for feature_child in &session.feature.children {
+        for (index, feature_child) in session.feature.children.iter().enumerate() {
             if let Some(value) = &feature_child.value {
                 match value {
                     ast::FeatureChildValue::Background(background) => {
                         session.feature_background = Some(background);
-                        session.feature_background_steps = self.background_cuke_steps(background);
+                        session.feature_background_steps =
+                            self.background_cuke_steps(session, background)?;
+                        session.path.feature_background = Some(index);
                     }
                     ast::FeatureChildValue::Rule(rule) => {
-                        self.compile_rule(session, rule);
+                        session.path.rule = Some(index);
+                        self.compile_rule(session, rule)?;
+                        session.path.rule = None;
                     }
                     ast::FeatureChildValue::Scenario(scenario) => {
+                        session.path.scenario = index;
                         if scenario.examples.is_empty() {
-                            self.compile_scenario(session, scenario);
+                            self.compile_scenario(session, scenario)?;
                         } else {
-                            self.compile_scenario_outline(session, scenario);
+                            self.compile_scenario_outline(session, scenario)?;
                         }
                     }
                 }
             }
         }
+
+        Ok(())
     }
 
-    fn compile_rule<'d>(&mut self, session: &mut Session<'d>, rule: &'d ast::Rule) {
+    fn compile_rule<'d>(
+        &mut self,
+        session: &mut Session<'d, '_>,
+        rule: &'d ast::Rule,
+    ) -> Result<()> {
+        let cuke_rule = Arc::new(self.cuke_rule(session, rule)?);
+        session.rules.push(Arc::clone(&cuke_rule));
+        session.rule = Some(cuke_rule);
         session.rule_background = None;
         session.rule_background_steps = Vec::new();
+        session.path.rule_background = None;
 
-        for rule_child in &rule.children {
+        for (index, rule_child) in rule.children.iter().enumerate() {
             if let Some(value) = &rule_child.value {
                 match value {
                     ast::RuleChildValue::Background(background) => {
                         session.rule_background = Some(background);
-                        session.rule_background_steps = self.background_cuke_steps(background);
+                        session.rule_background_steps =
+                            self.background_cuke_steps(session, background)?;
+                        session.path.rule_background = Some(index);
                     }
                     ast::RuleChildValue::Scenario(scenario) => {
+                        session.path.scenario = index;
                         if scenario.examples.is_empty() {
-                            self.compile_scenario(session, scenario);
+                            self.compile_scenario(session, scenario)?;
                         } else {
-                            self.compile_scenario_outline(session, scenario);
+                            self.compile_scenario_outline(session, scenario)?;
                         }
                     }
                 }
             }
         }
+
+        session.rule = None;
+        session.rule_background = None;
+        session.rule_background_steps = Vec::new();
+        session.path.rule_background = None;
+        Ok(())
     }
 
-    fn compile_scenario<'d>(&mut self, session: &mut Session<'d>, scenario: &'d ast::Scenario) {
+    fn cuke_rule<'d>(
+        &mut self,
+        session: &Session<'d, '_>,
+        rule: &'d ast::Rule,
+    ) -> Result<cuke::Rule<'d>> {
+        let mut background = None;
+        let mut scenarios = Vec::new();
+
+        for rule_child in &rule.children {
+            match &rule_child.value {
+                Some(ast::RuleChildValue::Background(rule_background)) => {
+                    background = Some(rule_background);
+                }
+                Some(ast::RuleChildValue::Scenario(scenario)) => scenarios.push(scenario),
+                None => {}
+            }
+        }
+
+        Ok(cuke::Rule {
+            ast_node_id: rule.id.as_str(),
+            location: session.location(rule.location, "rule", &rule.id)?,
+            keyword: &rule.keyword,
+            name: &rule.name,
+            description: &rule.description,
+            background,
+            scenarios,
+            tags: session.tags(&session.feature.tags)?,
+        })
+    }
+
+    fn compile_scenario<'d>(
+        &mut self,
+        session: &mut Session<'d, '_>,
+        scenario: &'d ast::Scenario,
+    ) -> Result<()> {
         let name = Cow::Borrowed(scenario.name.as_str());
         let language = &session.feature.language;
         let (feature_background_steps, rule_background_steps) =
             self.compile_feature_and_rule_background_steps(session, !scenario.steps.is_empty());
-        let scenario_steps = self.compile_scenario_steps(scenario);
-        let tags = self.compile_scenario_tags(session, scenario);
-        let locations = vec![cuke::Location::from(scenario.location.unwrap())];
+        let scenario_steps = self.compile_scenario_steps(session, scenario)?;
+        let tags = self.compile_scenario_tags(session, scenario)?;
+        let locations = vec![session.location(scenario.location, "scenario", &scenario.id)?];
         let ast_node_ids = vec![scenario.id.as_str()];
         let cuke = cuke::Cuke {
             id: self.id_generator.new_id(),
             uri: session.uri,
             feature: session.feature,
             feature_background: session.feature_background,
-            rule: session.rule,
+            rule: session.rule.clone(),
             rule_background: session.rule_background,
             scenario,
             name,
@@ -125,124 +337,282 @@ impl<'id_gen> Compiler<'id_gen> {
             tags,
             locations,
             ast_node_ids,
+            path: session.path,
         };
 
         session.cukes.push(cuke);
+        Ok(())
     }
 
-    fn compile_scenario_steps<'d>(&mut self, scenario: &'d ast::Scenario) -> Vec<cuke::Step<'d>> {
+    fn compile_scenario_steps<'d>(
+        &mut self,
+        session: &Session<'d, '_>,
+        scenario: &'d ast::Scenario,
+    ) -> Result<Vec<cuke::Step<'d>>> {
         let mut scenario_steps = Vec::with_capacity(scenario.steps.len());
 
         for step in &scenario.steps {
-            let cuke_step = self.cuke_step(step);
+            let cuke_step = self.cuke_step(session, step)?;
             scenario_steps.push(cuke_step);
         }
 
-        scenario_steps
+        resolve_keyword_types(&mut scenario_steps);
+        Ok(scenario_steps)
     }
 
     fn compile_scenario_tags<'d>(
         &mut self,
-        session: &mut Session<'d>,
+        session: &mut Session<'d, '_>,
         scenario: &'d ast::Scenario,
-    ) -> Vec<cuke::Tag<'d>> {
+    ) -> Result<Vec<cuke::Tag<'d>>> {
         let feature_tags = &session.feature.tags;
         let scenario_tags = &scenario.tags;
         let tags_capacity = feature_tags.len() + scenario_tags.len();
 
         let mut tags = Vec::with_capacity(tags_capacity);
 
-        feature_tags
-            .iter()
-            .map(cuke::Tag::from)
-            .for_each(|tag| tags.push(tag));
-        scenario_tags
-            .iter()
-            .map(cuke::Tag::from)
-            .for_each(|tag| tags.push(tag));
+        tags.extend(session.tags(feature_tags)?);
+        tags.extend(session.tags(scenario_tags)?);
 
-        tags
+        Ok(tags)
     }
 
     fn compile_scenario_outline<'d>(
         &mut self,
-        session: &mut Session<'d>,
+        session: &mut Session<'d, '_>,
         scenario: &'d ast::Scenario,
-    ) {
+    ) -> Result<()> {
+        let placeholders = self.outline_placeholders(session, scenario)?;
+
         for examples in &scenario.examples {
-            let table_header: &ast::TableRow = match &examples.table_header {
-                Some(table_header) => table_header,
-                None => return,
+            self.compile_examples(session, scenario, examples, &placeholders)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compiles one cuke per row of the examples, independently of the other examples
+    /// of the scenario outline.
+    fn compile_examples<'d>(
+        &mut self,
+        session: &mut Session<'d, '_>,
+        scenario: &'d ast::Scenario,
+        examples: &'d ast::Examples,
+        placeholders: &[Placeholder<'d>],
+    ) -> Result<()> {
+        let examples_location = session.location(examples.location, "examples", &examples.id)?;
+        let table_header: &ast::TableRow = match &examples.table_header {
+            Some(table_header) => table_header,
+            None => {
+                self.diagnose(
+                    session,
+                    examples_location,
+                    cuke::DiagnosticKind::MissingExamplesHeader,
+                );
+                return Ok(());
+            }
+        };
+        self.check_examples(
+            session,
+            examples,
+            examples_location,
+            table_header,
+            placeholders,
+        )?;
+
+        let variable_cells = &table_header.cells;
+        for values in &examples.table_body {
+            let value_cells = &values.cells;
+
+            let name = self.interpolate(&scenario.name, variable_cells, value_cells);
+            let language = &session.feature.language;
+            let (feature_background_steps, rule_background_steps) =
+                self.compile_feature_and_rule_background_steps(session, !scenario.steps.is_empty());
+            let scenario_steps = self.compile_scenario_outline_steps(
+                session,
+                scenario,
+                variable_cells,
+                value_cells,
+                values,
+            )?;
+            let tags = self.compile_scenario_outline_tags(session, scenario, examples)?;
+            let locations = vec![
+                session.location(values.location, "table row", &values.id)?,
+                session.location(scenario.location, "scenario", &scenario.id)?,
+            ];
+            let ast_node_ids = vec![scenario.id.as_str(), values.id.as_str()];
+            let cuke = cuke::Cuke {
+                id: self.id_generator.new_id(),
+                uri: session.uri,
+                feature: session.feature,
+                feature_background: session.feature_background,
+                rule: session.rule.clone(),
+                rule_background: session.rule_background,
+                scenario,
+                name,
+                language,
+                feature_background_steps,
+                rule_background_steps,
+                scenario_steps,
+                tags,
+                locations,
+                ast_node_ids,
+                path: session.path,
             };
 
-            let variable_cells = &table_header.cells;
-            for values in &examples.table_body {
-                let value_cells = &values.cells;
-
-                let name = self.interpolate(&scenario.name, variable_cells, value_cells);
-                let language = &session.feature.language;
-                let (feature_background_steps, rule_background_steps) = self
-                    .compile_feature_and_rule_background_steps(session, !scenario.steps.is_empty());
-                let scenario_steps = self.compile_scenario_outline_steps(
-                    scenario,
-                    variable_cells,
-                    value_cells,
-                    values,
+            session.cukes.push(cuke);
+        }
+
+        Ok(())
+    }
+
+    fn outline_placeholders<'d>(
+        &mut self,
+        session: &Session<'d, '_>,
+        scenario: &'d ast::Scenario,
+    ) -> Result<Vec<Placeholder<'d>>> {
+        let mut placeholders = Vec::new();
+
+        let scenario_location = session.location(scenario.location, "scenario", &scenario.id)?;
+        add_placeholders(&mut placeholders, &scenario.name, scenario_location, false);
+
+        for step in &scenario.steps {
+            let step_location = self.cuke_step_location(session, step)?;
+            add_placeholders(&mut placeholders, &step.text, step_location, true);
+
+            match &step.argument {
+                Some(ast::Argument::DocString(doc_string)) => {
+                    let location =
+                        session.location(doc_string.location, "doc string of step", &step.id)?;
+                    add_placeholders(&mut placeholders, &doc_string.media_type, location, false);
+                    for (index, line) in doc_string.content.lines().enumerate() {
+                        let line_location = cuke::Location {
+                            line: location.line + 1 + index as u32,
+                            column: location.column,
+                        };
+                        add_placeholders(&mut placeholders, line, line_location, false);
+                    }
+                }
+                Some(ast::Argument::DataTable(data_table)) => {
+                    for cell in data_table.rows.iter().flat_map(|row| &row.cells) {
+                        let location = session.location(
+                            cell.location,
+                            "a cell of the data table of step",
+                            &step.id,
+                        )?;
+                        add_placeholders(&mut placeholders, &cell.value, location, true);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        Ok(placeholders)
+    }
+
+    /// Reports duplicate and unused columns, missing rows and placeholders without a column.
+    fn check_examples<'d>(
+        &mut self,
+        session: &Session<'d, '_>,
+        examples: &'d ast::Examples,
+        examples_location: cuke::Location,
+        table_header: &'d ast::TableRow,
+        placeholders: &[Placeholder<'d>],
+    ) -> Result<()> {
+        let columns = &table_header.cells;
+
+        for (index, column) in columns.iter().enumerate() {
+            let location =
+                session.location(column.location, "a cell of the examples", &examples.id)?;
+            if columns[..index]
+                .iter()
+                .any(|previous| previous.value == column.value)
+            {
+                let name = column.value.clone();
+                self.diagnose(
+                    session,
+                    location,
+                    cuke::DiagnosticKind::DuplicateColumn { name },
                 );
-                let tags = self.compile_scenario_outline_tags(session, scenario, examples);
-                let locations = vec![
-                    cuke::Location::from(values.location.unwrap()),
-                    cuke::Location::from(scenario.location.unwrap()),
-                ];
-                let ast_node_ids = vec![scenario.id.as_str(), values.id.as_str()];
-                let cuke = cuke::Cuke {
-                    id: self.id_generator.new_id(),
-                    uri: session.uri,
-                    feature: session.feature,
-                    feature_background: session.feature_background,
-                    rule: session.rule,
-                    rule_background: session.rule_background,
-                    scenario,
+            } else if !placeholders
+                .iter()
+                .any(|placeholder| placeholder.name == column.value)
+            {
+                let name = column.value.clone();
+                self.diagnose(
+                    session,
+                    location,
+                    cuke::DiagnosticKind::UnusedColumn { name },
+                );
+            }
+        }
+
+        if examples.table_body.is_empty() {
+            self.diagnose(
+                session,
+                examples_location,
+                cuke::DiagnosticKind::EmptyExamples,
+            );
+        }
+
+        for placeholder in placeholders {
+            if !columns
+                .iter()
+                .any(|column| column.value == placeholder.name)
+            {
+                let name = placeholder.name.to_string();
+                let kind = cuke::DiagnosticKind::UnknownPlaceholder {
                     name,
-                    language,
-                    feature_background_steps,
-                    rule_background_steps,
-                    scenario_steps,
-                    tags,
-                    locations,
-                    ast_node_ids,
+                    examples: examples_location,
                 };
-
-                session.cukes.push(cuke);
+                self.diagnose(session, placeholder.location, kind);
             }
         }
+
+        Ok(())
+    }
+
+    fn diagnose(
+        &mut self,
+        session: &Session<'_, '_>,
+        location: cuke::Location,
+        kind: cuke::DiagnosticKind,
+    ) {
+        self.diagnostics.push(cuke::Diagnostic {
+            uri: session.uri.to_string(),
+            location,
+            kind,
+        });
     }
 
     fn compile_scenario_outline_steps<'d>(
         &mut self,
+        session: &Session<'d, '_>,
         scenario: &'d ast::Scenario,
         variable_cells: &'d [ast::TableCell],
         value_cells: &'d [ast::TableCell],
         values: &'d ast::TableRow,
-    ) -> Vec<cuke::Step<'d>> {
+    ) -> Result<Vec<cuke::Step<'d>>> {
         if scenario.steps.is_empty() {
-            Vec::new()
+            Ok(Vec::new())
         } else {
             let mut steps = Vec::with_capacity(scenario.steps.len());
 
             for step in &scenario.steps {
                 let keyword = &step.keyword;
+                let keyword_type = session.step_keyword_type(step);
                 let text = self.interpolate(&step.text, variable_cells, value_cells);
                 let argument =
-                    self.create_cuke_argument(step.argument.as_ref(), variable_cells, value_cells);
+                    self.create_cuke_argument(session, step, variable_cells, value_cells)?;
                 let locations = vec![
-                    cuke::Location::from(values.location.unwrap()),
-                    self.cuke_step_location(step),
+                    session.location(values.location, "table row", &values.id)?,
+                    self.cuke_step_location(session, step)?,
                 ];
                 let ast_node_ids = vec![step.id.as_str(), values.id.as_str()];
                 let cuke_step = cuke::Step {
                     id: self.id_generator.new_id(),
                     keyword,
+                    keyword_type,
                     text,
                     argument,
                     locations,
@@ -252,16 +622,17 @@ impl<'id_gen> Compiler<'id_gen> {
                 steps.push(cuke_step);
             }
 
-            steps
+            resolve_keyword_types(&mut steps);
+            Ok(steps)
         }
     }
 
     fn compile_scenario_outline_tags<'d>(
         &mut self,
-        session: &mut Session<'d>,
+        session: &mut Session<'d, '_>,
         scenario: &'d ast::Scenario,
         examples: &'d ast::Examples,
-    ) -> Vec<cuke::Tag<'d>> {
+    ) -> Result<Vec<cuke::Tag<'d>>> {
         let feature_tags = &session.feature.tags;
         let scenario_outline_tags = &scenario.tags;
         let examples_tags = &examples.tags;
@@ -269,36 +640,29 @@ impl<'id_gen> Compiler<'id_gen> {
 
         let mut tags = Vec::with_capacity(tags_capacity);
 
-        feature_tags
-            .iter()
-            .map(cuke::Tag::from)
-            .for_each(|tag| tags.push(tag));
-        scenario_outline_tags
-            .iter()
-            .map(cuke::Tag::from)
-            .for_each(|tag| tags.push(tag));
-        examples_tags
-            .iter()
-            .map(cuke::Tag::from)
-            .for_each(|tag| tags.push(tag));
+        tags.extend(session.tags(feature_tags)?);
+        tags.extend(session.tags(scenario_outline_tags)?);
+        tags.extend(session.tags(examples_tags)?);
 
-        tags
+        Ok(tags)
     }
 
     fn create_cuke_argument<'d>(
         &mut self,
-        argument: Option<&'d ast::Argument>,
+        session: &Session<'d, '_>,
+        step: &'d ast::Step,
         variable_cells: &'d [ast::TableCell],
         value_cells: &'d [ast::TableCell],
-    ) -> Option<cuke::Argument<'d>> {
-        let argument = match argument {
+    ) -> Result<Option<cuke::Argument<'d>>> {
+        let argument = match &step.argument {
             Some(argument) => argument,
-            None => return None,
+            None => return Ok(None),
         };
 
         match argument {
             ast::Argument::DocString(doc_string) => {
-                let location = cuke::Location::from(doc_string.location.unwrap());
+                let location =
+                    session.location(doc_string.location, "doc string of step", &step.id)?;
                 let content = self.interpolate(&doc_string.content, variable_cells, value_cells);
                 let media_type =
                     self.interpolate(&doc_string.media_type, variable_cells, value_cells);
@@ -308,7 +672,7 @@ impl<'id_gen> Compiler<'id_gen> {
                     media_type,
                 };
 
-                Some(cuke::Argument::String(cuke_string))
+                Ok(Some(cuke::Argument::String(cuke_string)))
             }
             ast::Argument::DataTable(data_table) => {
                 let rows = data_table
@@ -319,56 +683,73 @@ impl<'id_gen> Compiler<'id_gen> {
                             .cells
                             .iter()
                             .map(|cell: &ast::TableCell| {
-                                let location = cuke::Location::from(cell.location.unwrap());
+                                let location = session.location(
+                                    cell.location,
+                                    "a cell of the data table of step",
+                                    &step.id,
+                                )?;
                                 let value =
                                     self.interpolate(&cell.value, variable_cells, value_cells);
 
-                                cuke::Cell { location, value }
+                                Ok(cuke::Cell { location, value })
                             })
-                            .collect::<Vec<cuke::Cell<'_>>>();
+                            .collect::<Result<Vec<cuke::Cell<'_>>>>()?;
 
-                        cuke::Row { cells }
+                        Ok(cuke::Row { cells })
                     })
-                    .collect::<Vec<cuke::Row<'_>>>();
+                    .collect::<Result<Vec<cuke::Row<'_>>>>()?;
                 let cuke_table = cuke::Table { rows };
 
-                Some(cuke::Argument::Table(cuke_table))
+                Ok(Some(cuke::Argument::Table(cuke_table)))
             }
         }
     }
 
     fn background_cuke_steps<'d>(
         &mut self,
+        session: &Session<'d, '_>,
         background: &'d ast::Background,
-    ) -> Vec<cuke::Step<'d>> {
-        background
+    ) -> Result<Vec<cuke::Step<'d>>> {
+        let mut steps = background
             .steps
             .iter()
-            .map(|step| self.cuke_step_without_id(step))
-            .collect()
+            .map(|step| self.cuke_step_without_id(session, step))
+            .collect::<Result<Vec<cuke::Step<'d>>>>()?;
+        resolve_keyword_types(&mut steps);
+        Ok(steps)
     }
 
-    fn cuke_step<'d>(&mut self, step: &'d ast::Step) -> cuke::Step<'d> {
-        let mut cuke_step = self.cuke_step_without_id(step);
+    fn cuke_step<'d>(
+        &mut self,
+        session: &Session<'d, '_>,
+        step: &'d ast::Step,
+    ) -> Result<cuke::Step<'d>> {
+        let mut cuke_step = self.cuke_step_without_id(session, step)?;
         cuke_step.id = self.id_generator.new_id();
-        cuke_step
+        Ok(cuke_step)
     }
 
-    fn cuke_step_without_id<'d>(&mut self, step: &'d ast::Step) -> cuke::Step<'d> {
+    fn cuke_step_without_id<'d>(
+        &mut self,
+        session: &Session<'d, '_>,
+        step: &'d ast::Step,
+    ) -> Result<cuke::Step<'d>> {
         let keyword = &step.keyword;
+        let keyword_type = session.step_keyword_type(step);
         let text = Cow::Borrowed(step.text.as_str());
-        let argument = self.create_cuke_argument(step.argument.as_ref(), &[], &[]);
-        let locations = vec![self.cuke_step_location(step)];
+        let argument = self.create_cuke_argument(session, step, &[], &[])?;
+        let locations = vec![self.cuke_step_location(session, step)?];
         let ast_node_ids = vec![step.id.as_str()];
 
-        cuke::Step {
+        Ok(cuke::Step {
             id: String::new(),
             keyword,
+            keyword_type,
             text,
             argument,
             locations,
             ast_node_ids,
-        }
+        })
     }
 
     fn interpolate<'d>(
@@ -382,9 +763,7 @@ impl<'id_gen> Compiler<'id_gen> {
         } else {
             let mut interpolated_text = text.to_owned();
 
-            assert_eq!(variable_cells.len(), value_cells.len());
-            for (column, variable_cell) in variable_cells.iter().enumerate() {
-                let value_cell = &value_cells[column];
+            for (variable_cell, value_cell) in variable_cells.iter().zip(value_cells) {
                 let header = &variable_cell.value;
                 let value = &value_cell.value;
                 interpolated_text = interpolated_text.replace(&format!("<{}>", header), value);
@@ -394,21 +773,25 @@ impl<'id_gen> Compiler<'id_gen> {
         }
     }
 
-    fn cuke_step_location(&mut self, step: &ast::Step) -> cuke::Location {
+    fn cuke_step_location(
+        &mut self,
+        session: &Session<'_, '_>,
+        step: &ast::Step,
+    ) -> Result<cuke::Location> {
         let keyword_column = if step.keyword.is_empty() {
             0
         } else {
             step.keyword.chars().count() as u32
         };
-        let step_location = step.location.unwrap();
+        let step_location = session.location(step.location, "step", &step.id)?;
         let line = step_location.line;
         let column = step_location.column + keyword_column;
-        cuke::Location { line, column }
+        Ok(cuke::Location { line, column })
     }
 
     fn compile_feature_and_rule_background_steps<'d>(
         &mut self,
-        session: &mut Session<'d>,
+        session: &mut Session<'d, '_>,
         should_compile: bool,
     ) -> (Vec<cuke::Step<'d>>, Vec<cuke::Step<'d>>) {
         if should_compile {
@@ -432,3 +815,52 @@ impl<'id_gen> Compiler<'id_gen> {
             .collect()
     }
 }
+
+/// Adds the `<placeholder>`s of the text, whose names must not start or end with whitespace.
+///
+/// The placeholders are located at the start of the text unless `exact_columns` is set.
+fn add_placeholders<'d>(
+    placeholders: &mut Vec<Placeholder<'d>>,
+    text: &'d str,
+    location: cuke::Location,
+    exact_columns: bool,
+) {
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let after_start = &rest[start + 1..];
+        let end = match after_start.find(|c| c == '<' || c == '>') {
+            Some(end) => end,
+            None => return,
+        };
+
+        let name = &after_start[..end];
+        if after_start[end..].starts_with('>') && !name.is_empty() && name.trim() == name {
+            let offset = text.len() - rest.len() + start;
+            let column = if exact_columns {
+                location.column + text[..offset].chars().count() as u32
+            } else {
+                location.column
+            };
+            placeholders.push(Placeholder {
+                name,
+                location: cuke::Location {
+                    line: location.line,
+                    column,
+                },
+            });
+        }
+        rest = &after_start[end..];
+    }
+}
+
+/// Replaces conjunction keyword types (`And`, `But`) with the type of the preceding step.
+fn resolve_keyword_types(steps: &mut [cuke::Step<'_>]) {
+    let mut last_keyword_type = StepKeywordType::Unknown;
+
+    for step in steps {
+        if step.keyword_type == StepKeywordType::Conjunction {
+            step.keyword_type = last_keyword_type;
+        }
+        last_keyword_type = step.keyword_type;
+    }
+}
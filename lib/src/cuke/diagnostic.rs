@@ -0,0 +1,80 @@
+use std::fmt;
+
+use crate::cuke::Location;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DiagnosticKind {
+    /// A `<placeholder>` of a scenario outline that is not a column of the examples
+    /// at the `examples` location.
+    UnknownPlaceholder { name: String, examples: Location },
+    /// A column of the examples that is not used by any placeholder of the scenario outline.
+    UnusedColumn { name: String },
+    /// A column of the examples whose name is already used by a previous column.
+    DuplicateColumn { name: String },
+    /// Examples without a table header, which don't produce any cukes.
+    MissingExamplesHeader,
+    /// Examples with a table header but without body rows, which don't produce any cukes.
+    EmptyExamples,
+}
+
+impl DiagnosticKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            DiagnosticKind::UnusedColumn { .. } | DiagnosticKind::EmptyExamples => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// A problem of a scenario outline, which is reported by the `Compiler` while it still
+/// compiles the outline as far as possible.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub uri: String,
+    pub location: Location,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity() {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{}:{}:{}: {}: ",
+            self.uri, self.location.line, self.location.column, severity
+        )?;
+
+        match &self.kind {
+            DiagnosticKind::UnknownPlaceholder { name, examples } => write!(
+                f,
+                "placeholder <{}> is not a column of the examples at line {}",
+                name, examples.line
+            ),
+            DiagnosticKind::UnusedColumn { name } => {
+                write!(f, "column '{}' is not used by the scenario outline", name)
+            }
+            DiagnosticKind::DuplicateColumn { name } => {
+                write!(f, "column '{}' is already defined", name)
+            }
+            DiagnosticKind::MissingExamplesHeader => f.write_str("examples have no table header"),
+            DiagnosticKind::EmptyExamples => f.write_str("examples have no rows"),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use cucumber_messages::ast;
+
+use crate::cuke::{Cuke, Rule};
+
+/// The cukes of a `GherkinDocument` grouped by the rule they belong to.
+#[derive(Debug)]
+pub struct Feature<'d> {
+    pub uri: &'d str,
+    pub feature: &'d ast::Feature,
+    /// The cukes of the scenarios which are not part of a rule.
+    pub cukes: Vec<Cuke<'d>>,
+    /// All rules of the feature in document order, including the ones without cukes.
+    pub rules: Vec<RuleCukes<'d>>,
+}
+
+#[derive(Debug)]
+pub struct RuleCukes<'d> {
+    pub rule: Arc<Rule<'d>>,
+    pub cukes: Vec<Cuke<'d>>,
+}
+
+impl<'d> Feature<'d> {
+    pub(crate) fn new(
+        uri: &'d str,
+        feature: &'d ast::Feature,
+        rules: Vec<Arc<Rule<'d>>>,
+        cukes: Vec<Cuke<'d>>,
+    ) -> Feature<'d> {
+        let mut rules = rules
+            .into_iter()
+            .map(|rule| RuleCukes {
+                rule,
+                cukes: Vec::new(),
+            })
+            .collect::<Vec<RuleCukes<'d>>>();
+        let mut feature_cukes = Vec::new();
+
+        for cuke in cukes {
+            let rule_cukes = match &cuke.rule {
+                Some(rule) => rules
+                    .iter_mut()
+                    .find(|rule_cukes| Arc::ptr_eq(&rule_cukes.rule, rule)),
+                None => None,
+            };
+            match rule_cukes {
+                Some(rule_cukes) => rule_cukes.cukes.push(cuke),
+                None => feature_cukes.push(cuke),
+            }
+        }
+
+        Feature {
+            uri,
+            feature,
+            cukes: feature_cukes,
+            rules,
+        }
+    }
+
+    /// Iterates over all cukes of the feature in document order.
+    pub fn all_cukes(&self) -> impl Iterator<Item = &Cuke<'d>> {
+        self.cukes
+            .iter()
+            .chain(self.rules.iter().flat_map(|rule_cukes| &rule_cukes.cukes))
+    }
+}
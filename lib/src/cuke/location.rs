@@ -1,6 +1,7 @@
 use cucumber_messages::ast;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Location {
     pub line: u32,
     pub column: u32,
@@ -23,3 +24,9 @@ impl From<Location> for ast::Location {
         }
     }
 }
+
+impl From<Location> for crate::Location {
+    fn from(location: Location) -> Self {
+        crate::Location::new(location.line, location.column)
+    }
+}
@@ -1,37 +1,56 @@
 pub use self::argument::*;
 pub use self::cell::*;
 pub use self::compiler::*;
+pub use self::diagnostic::*;
+pub use self::feature::*;
 pub use self::location::*;
+pub use self::owned::*;
 pub use self::row::*;
+pub use self::rule::*;
 pub use self::step::*;
 pub use self::string::*;
 pub use self::table::*;
+pub use self::table_diff::*;
 pub use self::tag::*;
 
 use std::borrow::Cow;
 use std::string::String as StdString;
+use std::sync::Arc;
 
 use cucumber_messages::ast;
 use cucumber_messages::pickle;
 
+use crate::cuke::owned::AstPath;
+use crate::StepKeywordTypes;
+
 mod argument;
 mod cell;
 mod compiler;
+mod diagnostic;
+mod feature;
 mod location;
+mod owned;
 mod row;
+mod rule;
+mod serialize;
 mod step;
 mod string;
 mod table;
+mod table_de;
+mod table_diff;
 mod tag;
-//mod rule;
+mod validation;
 
+/// A scenario or an example of a scenario outline, ready to be executed.
+///
+/// It serializes to the same JSON as an `OwnedCuke`, see there.
 #[derive(Debug)]
 pub struct Cuke<'d> {
     pub id: StdString,
     pub uri: &'d str,
     pub feature: &'d ast::Feature,
     pub feature_background: Option<&'d ast::Background>,
-    pub rule: Option<&'d ast::Rule>,
+    pub rule: Option<Arc<Rule<'d>>>,
     pub rule_background: Option<&'d ast::Background>,
     pub scenario: &'d ast::Scenario,
     pub name: Cow<'d, str>,
@@ -42,8 +61,30 @@ pub struct Cuke<'d> {
     pub tags: Vec<Tag<'d>>,
     pub locations: Vec<Location>,
     pub ast_node_ids: Vec<&'d str>,
+    /// The indexes of the backgrounds, the rule and the scenario in the document.
+    pub(crate) path: AstPath,
+}
+
+impl<'d> Cuke<'d> {
+    /// Iterates over the background and scenario steps in the order of the pickle steps.
+    pub fn steps(&self) -> impl Iterator<Item = &Step<'d>> {
+        self.feature_background_steps
+            .iter()
+            .chain(&self.rule_background_steps)
+            .chain(&self.scenario_steps)
+    }
+
+    /// Returns the keyword types of the steps by the id of their pickle step,
+    /// which has no field for it.
+    pub fn step_keyword_types(&self) -> StepKeywordTypes {
+        self.steps()
+            .map(|step| (step.id.clone(), step.keyword_type))
+            .collect()
+    }
 }
 
+/// Converts the cuke into a pickle, dropping the keyword types of the steps,
+/// see `Cuke::step_keyword_types`.
 impl<'d> From<Cuke<'d>> for pickle::Pickle {
     fn from(cuke: Cuke<'d>) -> Self {
         let steps_capacity = cuke.feature_background_steps.len()
@@ -77,6 +118,13 @@ impl<'d> From<Cuke<'d>> for pickle::Pickle {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::dialect::Dialect;
+    use crate::{DialectProvider, DocumentBuilder, Parser, ParserOptions, StepKeywordType};
+
     use super::*;
 
     fn assert_sync<T: Sync>() {}
@@ -87,4 +135,456 @@ mod tests {
         assert_send::<Cuke<'_>>();
         assert_sync::<Cuke<'_>>();
     }
+
+    #[test]
+    fn test_owned_send_sync() {
+        assert_send::<OwnedCuke>();
+        assert_sync::<OwnedCuke>();
+    }
+
+    #[test]
+    fn sends_owned_cukes_to_threads() {
+        let source = r#"
+Feature: Owned cukes
+  Background:
+    Given a customer
+
+  Scenario: Outside of rules
+    Given nothing special
+
+  Rule: Discounts
+    Background:
+      Given a loyal customer
+
+    Scenario Outline: Many discounts
+      When buying <count> items
+        | item   |
+        | <item> |
+
+      Examples:
+        | count | item  |
+        | 2     | cukes |
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = Arc::new(parser.parse_str(source).unwrap());
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let cukes = compiler.compile_owned(&gherkin_document).unwrap();
+        drop(gherkin_document);
+
+        let handles = cukes
+            .into_iter()
+            .map(|cuke| {
+                std::thread::spawn(move || {
+                    let rule = cuke.rule().map(|rule| rule.name.clone());
+                    let rule_background = cuke
+                        .rule_background()
+                        .map(|background| background.steps[0].text.clone());
+                    let steps = cuke
+                        .steps()
+                        .map(|step| step.text.clone())
+                        .collect::<Vec<StdString>>();
+                    assert_eq!(cuke.scenario().name, cuke.name);
+                    assert_eq!(
+                        cuke.feature_background().unwrap().steps[0].text,
+                        "a customer"
+                    );
+                    (rule, rule_background, steps, pickle::Pickle::from(cuke))
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(results[0].0, None);
+        assert_eq!(results[0].1, None);
+        assert_eq!(results[0].2, vec!["a customer", "nothing special"]);
+        assert_eq!(results[1].0, Some("Discounts".to_string()));
+        assert_eq!(results[1].1, Some("a loyal customer".to_string()));
+        assert_eq!(
+            results[1].2,
+            vec!["a customer", "a loyal customer", "buying 2 items"]
+        );
+
+        let pickle = &results[1].3;
+        assert_eq!(pickle.name, "Many discounts");
+        assert_eq!(pickle.steps.len(), 3);
+        match &pickle.steps[2].argument.as_ref().unwrap().message {
+            Some(pickle::ArgumentMessage::DataTable(table)) => {
+                assert_eq!(table.rows[1].cells[0].value, "cukes")
+            }
+            message => panic!("Expected a data table, got {:?}", message),
+        }
+    }
+
+    #[test]
+    fn groups_cukes_by_rule() {
+        let source = r#"
+@billing
+Feature: Grouped cukes
+  Background:
+    Given a customer
+
+  Scenario: Outside of rules
+    Given nothing special
+
+  Rule: Discounts
+    Only for loyal customers.
+
+    Background:
+      Given a loyal customer
+
+    Scenario: Single discount
+      When buying one item
+
+    Scenario Outline: Many discounts
+      When buying <count> items
+
+      Examples:
+        | count |
+        | 2     |
+        | 3     |
+
+  Rule: Refunds
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = parser.parse_str(source).unwrap();
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let feature = compiler
+            .compile_grouped(&gherkin_document)
+            .unwrap()
+            .unwrap();
+
+        let names = |cukes: &[Cuke<'_>]| {
+            cukes
+                .iter()
+                .map(|cuke| cuke.name.to_string())
+                .collect::<Vec<StdString>>()
+        };
+        assert_eq!(names(&feature.cukes), vec!["Outside of rules"]);
+        assert!(feature.cukes[0].rule.is_none());
+        assert_eq!(feature.rules.len(), 2);
+        assert_eq!(feature.all_cukes().count(), 4);
+
+        let discounts = &feature.rules[0];
+        assert_eq!(discounts.rule.keyword, "Rule");
+        assert_eq!(discounts.rule.name, "Discounts");
+        assert_eq!(
+            discounts.rule.description.trim(),
+            "Only for loyal customers."
+        );
+        assert_eq!(
+            discounts.rule.location,
+            Location {
+                line: 10,
+                column: 3
+            }
+        );
+        assert!(discounts.rule.background.is_some());
+        assert_eq!(discounts.rule.scenarios.len(), 2);
+        assert_eq!(discounts.rule.tags.len(), 1);
+        assert_eq!(discounts.rule.tags[0].name, "@billing");
+        assert_eq!(
+            names(&discounts.cukes),
+            vec!["Single discount", "Many discounts", "Many discounts"]
+        );
+        for cuke in &discounts.cukes {
+            assert!(Arc::ptr_eq(cuke.rule.as_ref().unwrap(), &discounts.rule));
+            assert_eq!(cuke.feature_background_steps.len(), 1);
+            assert_eq!(cuke.rule_background_steps.len(), 1);
+        }
+
+        let refunds = &feature.rules[1];
+        assert_eq!(refunds.rule.name, "Refunds");
+        assert!(refunds.rule.background.is_none());
+        assert!(refunds.cukes.is_empty());
+    }
+
+    #[test]
+    fn sets_rule_of_compiled_cukes() {
+        let source = r#"
+Feature: Rules
+  Scenario: Outside of rules
+    Given nothing special
+
+  Rule: A rule
+    Scenario: Inside of a rule
+      Given something special
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = parser.parse_str(source).unwrap();
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let cukes = compiler.compile(&gherkin_document).unwrap();
+
+        assert!(cukes[0].rule.is_none());
+        assert_eq!(cukes[1].rule.as_ref().unwrap().name, "A rule");
+    }
+
+    #[test]
+    fn converts_cukes_of_their_document_into_owned_cukes() {
+        let source = r#"
+Feature: Owned cukes
+  Rule: A rule
+    Background:
+      Given a background
+
+    Scenario: Inside of a rule
+      Given something special
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = Arc::new(parser.parse_str(source).unwrap());
+        let other_document = Arc::new(parser.parse_str(source).unwrap());
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let mut cukes = compiler.compile(&gherkin_document).unwrap();
+        let cuke = cukes.remove(0);
+        let other_cuke = compiler.compile(&other_document).unwrap().remove(0);
+
+        assert!(other_cuke.into_owned(&gherkin_document).is_none());
+        let owned_cuke = cuke.into_owned(&gherkin_document).unwrap();
+        assert_eq!(owned_cuke.rule().unwrap().name, "A rule");
+        assert_eq!(
+            owned_cuke.rule_background().unwrap().steps[0].text,
+            "a background"
+        );
+        assert_eq!(owned_cuke.scenario().name, "Inside of a rule");
+    }
+
+    #[test]
+    fn resolves_step_keyword_types() {
+        let source = r#"
+Feature: Keyword types
+  Background:
+    And a conjunction without a previous step
+
+  Scenario: All keyword types
+    Given a context
+    And another context
+    When an action
+    But not another action
+    Then an outcome
+    * an unknown step
+    And a conjunction after an unknown step
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = parser.parse_str(source).unwrap();
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let cukes = compiler.compile(&gherkin_document).unwrap();
+
+        let keyword_types = |steps: &[Step<'_>]| {
+            steps
+                .iter()
+                .map(|step| step.keyword_type)
+                .collect::<Vec<StepKeywordType>>()
+        };
+        assert_eq!(
+            keyword_types(&cukes[0].feature_background_steps),
+            vec![StepKeywordType::Unknown]
+        );
+        assert_eq!(
+            keyword_types(&cukes[0].scenario_steps),
+            vec![
+                StepKeywordType::Context,
+                StepKeywordType::Context,
+                StepKeywordType::Action,
+                StepKeywordType::Action,
+                StepKeywordType::Outcome,
+                StepKeywordType::Unknown,
+                StepKeywordType::Unknown,
+            ]
+        );
+    }
+
+    /// Provides a single dialect which is not built in.
+    struct PirateDialectProvider;
+
+    impl DialectProvider for PirateDialectProvider {
+        fn get_default_dialect(&self) -> crate::Result<Arc<Dialect>> {
+            let keywords = [
+                ("feature", "Ahoy matey!"),
+                ("scenario", "Heave to"),
+                ("given", "Gangway! "),
+                ("when", "Blimey! "),
+                ("then", "Let go and haul "),
+                ("and", "Aye "),
+            ]
+            .iter()
+            .map(|(group, keyword)| (StdString::from(*group), vec![StdString::from(*keyword)]))
+            .collect::<HashMap<StdString, Vec<StdString>>>();
+            let name = StdString::from("Pirate");
+            let dialect = Dialect::new(StdString::from("en-pirate"), name.clone(), name, keywords);
+            Ok(Arc::new(dialect))
+        }
+
+        fn get_dialect(
+            &self,
+            _language: &str,
+            _location: crate::Location,
+        ) -> crate::Result<Arc<Dialect>> {
+            self.get_default_dialect()
+        }
+
+        fn get_languages(&self) -> Vec<&str> {
+            vec!["en-pirate"]
+        }
+    }
+
+    #[test]
+    fn uses_step_keyword_types_of_custom_dialects() {
+        let source = r#"
+Ahoy matey!: Keyword types
+  Heave to: All keyword types
+    Gangway! a context
+    Aye another context
+    Blimey! an action
+    Let go and haul an outcome
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = ParserOptions::with_builder(builder)
+            .dialect_provider(PirateDialectProvider)
+            .create();
+        let gherkin_document = parser.parse_str(source).unwrap();
+        let step_keyword_types = parser.builder_mut().take_step_keyword_types();
+        assert_eq!(step_keyword_types.len(), 4);
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let cukes = compiler
+            .compile_with(&gherkin_document, &step_keyword_types)
+            .unwrap();
+
+        let keyword_types = cukes[0]
+            .scenario_steps
+            .iter()
+            .map(|step| step.keyword_type)
+            .collect::<Vec<StepKeywordType>>();
+        let expected_keyword_types = vec![
+            StepKeywordType::Context,
+            StepKeywordType::Context,
+            StepKeywordType::Action,
+            StepKeywordType::Outcome,
+        ];
+        assert_eq!(keyword_types, expected_keyword_types);
+
+        let step_keyword_types = cukes[0].step_keyword_types();
+        let pickle = pickle::Pickle::from(cukes.into_iter().next().unwrap());
+        let pickle_keyword_types = pickle
+            .steps
+            .iter()
+            .map(|step| step_keyword_types[&step.id])
+            .collect::<Vec<StepKeywordType>>();
+        assert_eq!(pickle_keyword_types, expected_keyword_types);
+    }
+
+    #[test]
+    fn reports_diagnostics_of_scenario_outlines() {
+        let source = r#"
+Feature: Diagnostics
+  Scenario Outline: Eating <count>
+    Given I have <count> <colour> cukes
+    When I eat <count>:
+      | <count> |
+
+    Examples:
+      | count | count | unused |
+      | 1     | 2     | x      |
+
+    Examples:
+      | count | colour |
+
+    Examples:
+
+    Examples:
+      | count |
+      | 3     |
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let mut gherkin_document = parser.parse_str(source).unwrap();
+        gherkin_document.uri = "diagnostics.feature".to_string();
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let cukes = compiler.compile(&gherkin_document).unwrap();
+        assert_eq!(cukes.len(), 2);
+        assert_eq!(cukes[0].scenario_steps[0].text, "I have 1 <colour> cukes");
+        assert_eq!(cukes[1].scenario_steps[0].text, "I have 3 <colour> cukes");
+
+        let diagnostic = |line, column, kind| Diagnostic {
+            uri: "diagnostics.feature".to_string(),
+            location: Location { line, column },
+            kind,
+        };
+        let name = |name: &str| name.to_string();
+        assert_eq!(
+            compiler.diagnostics(),
+            &[
+                diagnostic(
+                    9,
+                    17,
+                    DiagnosticKind::DuplicateColumn {
+                        name: name("count")
+                    }
+                ),
+                diagnostic(
+                    9,
+                    25,
+                    DiagnosticKind::UnusedColumn {
+                        name: name("unused")
+                    }
+                ),
+                diagnostic(
+                    4,
+                    26,
+                    DiagnosticKind::UnknownPlaceholder {
+                        name: name("colour"),
+                        examples: Location { line: 8, column: 5 },
+                    }
+                ),
+                diagnostic(12, 5, DiagnosticKind::EmptyExamples),
+                diagnostic(15, 5, DiagnosticKind::MissingExamplesHeader),
+                diagnostic(
+                    4,
+                    26,
+                    DiagnosticKind::UnknownPlaceholder {
+                        name: name("colour"),
+                        examples: Location {
+                            line: 17,
+                            column: 5
+                        },
+                    }
+                ),
+            ][..]
+        );
+        assert_eq!(compiler.diagnostics()[1].severity(), Severity::Warning);
+        assert_eq!(
+            compiler.diagnostics()[2].to_string(),
+            "diagnostics.feature:4:26: error: placeholder <colour> is not a column of the examples at line 8"
+        );
+
+        assert_eq!(compiler.take_diagnostics().len(), 6);
+        assert!(compiler.diagnostics().is_empty());
+    }
 }
@@ -0,0 +1,403 @@
+use std::sync::Arc;
+
+use cucumber_messages::ast;
+use cucumber_messages::pickle;
+use serde::de::{self, DeserializeSeed, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::cuke::serialize::{DeserializedCuke, SerializedCuke};
+use crate::cuke::{Argument, Cuke, Location, Step, Tag};
+use crate::{StepKeywordType, StepKeywordTypes};
+
+/// A `Cuke` which owns its data and shares the `GherkinDocument` it was compiled from.
+///
+/// It is `Send + Sync + 'static`, so it can be scheduled onto worker threads,
+/// and cloning it only copies the cuke itself.
+/// The feature, rule, backgrounds and scenario are looked up in the shared document.
+///
+/// It serializes to an object which references them by id, e.g.
+/// `{"id":"9","uri":"cukes.feature","name":"Eating","language":"en","featureBackgroundId":"1",`
+/// `"scenarioId":"4","featureBackgroundSteps":[…],"ruleBackgroundSteps":[],"scenarioSteps":[…],`
+/// `"tags":[],"locations":[{"line":6,"column":3}],"astNodeIds":["4"]}`,
+/// and is deserialized with an `OwnedCukeSeed` of the same document.
+#[derive(Debug, Clone)]
+pub struct OwnedCuke {
+    pub id: String,
+    pub name: String,
+    pub language: String,
+    pub feature_background_steps: Vec<OwnedStep>,
+    pub rule_background_steps: Vec<OwnedStep>,
+    pub scenario_steps: Vec<OwnedStep>,
+    pub tags: Vec<OwnedTag>,
+    pub locations: Vec<Location>,
+    pub ast_node_ids: Vec<String>,
+    gherkin_document: Arc<ast::GherkinDocument>,
+    /// Always points to nodes of the document: it is recorded while compiling the document
+    /// or resolved from the ids of a deserialized cuke, and the shared document can't change.
+    path: AstPath,
+}
+
+/// The indexes of the nodes of a cuke in the children of the feature and the rule.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct AstPath {
+    pub(crate) feature_background: Option<usize>,
+    pub(crate) rule: Option<usize>,
+    pub(crate) rule_background: Option<usize>,
+    /// The index in the children of the rule if there is one, else of the feature.
+    pub(crate) scenario: usize,
+}
+
+/// A `Step` which owns its data, with the same JSON representation.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedStep {
+    pub id: String,
+    pub keyword: String,
+    /// The keyword type with conjunctions (`And`, `But`) resolved to the type of the previous step.
+    pub keyword_type: StepKeywordType,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument: Option<Argument<'static>>,
+    pub locations: Vec<Location>,
+    pub ast_node_ids: Vec<String>,
+}
+
+/// A `Tag` which owns its data, with the same JSON representation.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedTag {
+    pub name: String,
+    pub location: Location,
+    pub ast_node_id: String,
+}
+
+impl<'d> Cuke<'d> {
+    /// Converts the cuke into an `OwnedCuke` which shares the document.
+    ///
+    /// Returns `None` if the cuke was not compiled from the given document.
+    pub fn into_owned(self, gherkin_document: &Arc<ast::GherkinDocument>) -> Option<OwnedCuke> {
+        let feature = gherkin_document.feature.as_ref()?;
+        if std::ptr::eq(feature, self.feature) {
+            Some(OwnedCuke::new(self, gherkin_document))
+        } else {
+            None
+        }
+    }
+}
+
+impl OwnedCuke {
+    /// Expects the cuke to be compiled from the document, so its path is valid.
+    pub(crate) fn new(cuke: Cuke<'_>, gherkin_document: &Arc<ast::GherkinDocument>) -> OwnedCuke {
+        OwnedCuke {
+            id: cuke.id,
+            name: cuke.name.into_owned(),
+            language: cuke.language.to_string(),
+            feature_background_steps: owned_steps(cuke.feature_background_steps),
+            rule_background_steps: owned_steps(cuke.rule_background_steps),
+            scenario_steps: owned_steps(cuke.scenario_steps),
+            tags: cuke.tags.into_iter().map(OwnedTag::from).collect(),
+            locations: cuke.locations,
+            ast_node_ids: cuke.ast_node_ids.into_iter().map(str::to_string).collect(),
+            gherkin_document: Arc::clone(gherkin_document),
+            path: cuke.path,
+        }
+    }
+}
+
+impl OwnedCuke {
+    pub fn gherkin_document(&self) -> &Arc<ast::GherkinDocument> {
+        &self.gherkin_document
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.gherkin_document.uri
+    }
+
+    pub fn feature(&self) -> &ast::Feature {
+        match &self.gherkin_document.feature {
+            Some(feature) => feature,
+            None => unreachable!(),
+        }
+    }
+
+    pub fn feature_background(&self) -> Option<&ast::Background> {
+        let index = self.path.feature_background?;
+        match &self.feature().children[index].value {
+            Some(ast::FeatureChildValue::Background(background)) => Some(background),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn rule(&self) -> Option<&ast::Rule> {
+        let index = self.path.rule?;
+        match &self.feature().children[index].value {
+            Some(ast::FeatureChildValue::Rule(rule)) => Some(rule),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn rule_background(&self) -> Option<&ast::Background> {
+        let index = self.path.rule_background?;
+        match &self.rule()?.children[index].value {
+            Some(ast::RuleChildValue::Background(background)) => Some(background),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn scenario(&self) -> &ast::Scenario {
+        let index = self.path.scenario;
+        match self.rule() {
+            Some(rule) => match &rule.children[index].value {
+                Some(ast::RuleChildValue::Scenario(scenario)) => scenario,
+                _ => unreachable!(),
+            },
+            None => match &self.feature().children[index].value {
+                Some(ast::FeatureChildValue::Scenario(scenario)) => scenario,
+                _ => unreachable!(),
+            },
+        }
+    }
+
+    /// Iterates over the background and scenario steps in the order of the pickle steps.
+    pub fn steps(&self) -> impl Iterator<Item = &OwnedStep> {
+        self.feature_background_steps
+            .iter()
+            .chain(&self.rule_background_steps)
+            .chain(&self.scenario_steps)
+    }
+
+    /// Returns the keyword types of the steps by the id of their pickle step,
+    /// which has no field for it.
+    pub fn step_keyword_types(&self) -> StepKeywordTypes {
+        self.steps()
+            .map(|step| (step.id.clone(), step.keyword_type))
+            .collect()
+    }
+}
+
+impl Serialize for OwnedCuke {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedCuke {
+            id: &self.id,
+            uri: self.uri(),
+            name: &self.name,
+            language: &self.language,
+            feature_background_id: self
+                .feature_background()
+                .map(|background| background.id.as_str()),
+            rule_id: self.rule().map(|rule| rule.id.as_str()),
+            rule_background_id: self
+                .rule_background()
+                .map(|background| background.id.as_str()),
+            scenario_id: &self.scenario().id,
+            feature_background_steps: &self.feature_background_steps,
+            rule_background_steps: &self.rule_background_steps,
+            scenario_steps: &self.scenario_steps,
+            tags: &self.tags,
+            locations: &self.locations,
+            ast_node_ids: &self.ast_node_ids,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes an `OwnedCuke` which shares the document it was compiled from.
+///
+/// Fails if the uri or the ids of the feature children don't match the document.
+#[derive(Debug, Clone)]
+pub struct OwnedCukeSeed<'a> {
+    gherkin_document: &'a Arc<ast::GherkinDocument>,
+}
+
+impl<'a> OwnedCukeSeed<'a> {
+    pub fn new(gherkin_document: &'a Arc<ast::GherkinDocument>) -> OwnedCukeSeed<'a> {
+        OwnedCukeSeed { gherkin_document }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for OwnedCukeSeed<'_> {
+    type Value = OwnedCuke;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<OwnedCuke, D::Error> {
+        let cuke = DeserializedCuke::deserialize(deserializer)?;
+        if cuke.uri != self.gherkin_document.uri {
+            return Err(de::Error::custom(format_args!(
+                "cuke of {} does not belong to the document {}",
+                cuke.uri, self.gherkin_document.uri
+            )));
+        }
+        let path = AstPath::resolve(&cuke, self.gherkin_document)
+            .map_err(|id| de::Error::custom(format_args!("unknown AST node id {}", id)))?;
+
+        Ok(OwnedCuke {
+            id: cuke.id,
+            name: cuke.name,
+            language: cuke.language,
+            feature_background_steps: cuke.feature_background_steps,
+            rule_background_steps: cuke.rule_background_steps,
+            scenario_steps: cuke.scenario_steps,
+            tags: cuke.tags,
+            locations: cuke.locations,
+            ast_node_ids: cuke.ast_node_ids,
+            gherkin_document: Arc::clone(self.gherkin_document),
+            path,
+        })
+    }
+}
+
+impl AstPath {
+    /// Looks up the ids of a deserialized cuke and returns the first one which can't be found.
+    fn resolve<'c>(
+        cuke: &'c DeserializedCuke,
+        gherkin_document: &ast::GherkinDocument,
+    ) -> Result<AstPath, &'c str> {
+        let scenario_id = cuke.scenario_id.as_str();
+        let children = match &gherkin_document.feature {
+            Some(feature) => &feature.children,
+            None => return Err(scenario_id),
+        };
+
+        let feature_background = match cuke.feature_background_id.as_deref() {
+            Some(id) => {
+                let is_background = |child: &ast::FeatureChild| match &child.value {
+                    Some(ast::FeatureChildValue::Background(background)) => background.id == id,
+                    _ => false,
+                };
+                Some(children.iter().position(is_background).ok_or(id)?)
+            }
+            None => None,
+        };
+
+        let rule = match cuke.rule_id.as_deref() {
+            Some(id) => {
+                let rule =
+                    children
+                        .iter()
+                        .enumerate()
+                        .find_map(|(index, child)| match &child.value {
+                            Some(ast::FeatureChildValue::Rule(rule)) if rule.id == id => {
+                                Some((index, rule))
+                            }
+                            _ => None,
+                        });
+                Some(rule.ok_or(id)?)
+            }
+            None => None,
+        };
+
+        let (rule, rule_background, scenario) = match rule {
+            Some((index, rule)) => {
+                let rule_background = match cuke.rule_background_id.as_deref() {
+                    Some(id) => {
+                        let is_background = |child: &ast::RuleChild| match &child.value {
+                            Some(ast::RuleChildValue::Background(background)) => {
+                                background.id == id
+                            }
+                            _ => false,
+                        };
+                        let position = rule.children.iter().position(is_background);
+                        Some(position.ok_or(id)?)
+                    }
+                    None => None,
+                };
+                let is_scenario = |child: &ast::RuleChild| match &child.value {
+                    Some(ast::RuleChildValue::Scenario(scenario)) => scenario.id == scenario_id,
+                    _ => false,
+                };
+                let scenario = rule.children.iter().position(is_scenario);
+                (Some(index), rule_background, scenario.ok_or(scenario_id)?)
+            }
+            None => {
+                if let Some(id) = cuke.rule_background_id.as_deref() {
+                    return Err(id);
+                }
+                let is_scenario = |child: &ast::FeatureChild| match &child.value {
+                    Some(ast::FeatureChildValue::Scenario(scenario)) => scenario.id == scenario_id,
+                    _ => false,
+                };
+                let scenario = children.iter().position(is_scenario);
+                (None, None, scenario.ok_or(scenario_id)?)
+            }
+        };
+
+        Ok(AstPath {
+            feature_background,
+            rule,
+            rule_background,
+            scenario,
+        })
+    }
+}
+
+/// Converts the cuke into a pickle, dropping the keyword types of the steps,
+/// see `OwnedCuke::step_keyword_types`.
+impl From<OwnedCuke> for pickle::Pickle {
+    fn from(cuke: OwnedCuke) -> Self {
+        let uri = cuke.uri().to_string();
+        let steps = cuke
+            .feature_background_steps
+            .into_iter()
+            .chain(cuke.rule_background_steps)
+            .chain(cuke.scenario_steps)
+            .map(pickle::Step::from)
+            .collect();
+
+        pickle::Pickle {
+            id: cuke.id,
+            uri,
+            name: cuke.name,
+            language: cuke.language,
+            steps,
+            tags: cuke.tags.into_iter().map(pickle::Tag::from).collect(),
+            ast_node_ids: cuke.ast_node_ids,
+        }
+    }
+}
+
+fn owned_steps(steps: Vec<Step<'_>>) -> Vec<OwnedStep> {
+    steps.into_iter().map(OwnedStep::from).collect()
+}
+
+impl<'d> From<Step<'d>> for OwnedStep {
+    fn from(step: Step<'d>) -> Self {
+        OwnedStep {
+            id: step.id,
+            keyword: step.keyword.to_string(),
+            keyword_type: step.keyword_type,
+            text: step.text.into_owned(),
+            argument: step.argument.map(Argument::into_owned),
+            locations: step.locations,
+            ast_node_ids: step.ast_node_ids.into_iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+impl From<OwnedStep> for pickle::Step {
+    fn from(step: OwnedStep) -> Self {
+        pickle::Step {
+            id: step.id,
+            text: step.text,
+            argument: step.argument.map(pickle::Argument::from),
+            ast_node_ids: step.ast_node_ids,
+        }
+    }
+}
+
+impl<'d> From<Tag<'d>> for OwnedTag {
+    fn from(tag: Tag<'d>) -> Self {
+        OwnedTag {
+            name: tag.name.to_string(),
+            location: tag.location,
+            ast_node_id: tag.ast_node_id.to_string(),
+        }
+    }
+}
+
+impl From<OwnedTag> for pickle::Tag {
+    fn from(tag: OwnedTag) -> Self {
+        pickle::Tag {
+            name: tag.name,
+            ast_node_id: tag.ast_node_id,
+        }
+    }
+}
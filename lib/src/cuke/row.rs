@@ -1,12 +1,21 @@
 use cucumber_messages::pickle;
+use serde::{Deserialize, Serialize};
 
 use crate::cuke::Cell;
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Row<'d> {
     pub cells: Vec<Cell<'d>>,
 }
 
+impl<'d> Row<'d> {
+    pub fn into_owned(self) -> Row<'static> {
+        Row {
+            cells: self.cells.into_iter().map(Cell::into_owned).collect(),
+        }
+    }
+}
+
 impl<'d> From<Row<'d>> for pickle::TableRow {
     fn from(row: Row<'d>) -> pickle::TableRow {
         pickle::TableRow {
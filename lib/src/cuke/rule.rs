@@ -1,12 +1,17 @@
-use crate::cuke::{Argument, Location};
-use std::borrow::Cow;
+use cucumber_messages::ast;
 
-#[derive(Debug, Clone)]
+use crate::cuke::{Location, Tag};
+
+#[derive(Debug)]
 pub struct Rule<'d> {
+    pub ast_node_id: &'d str,
     pub location: Location,
     pub keyword: &'d str,
     pub name: &'d str,
-    pub description: std::string::String,
+    pub description: &'d str,
     pub background: Option<&'d ast::Background>,
     pub scenarios: Vec<&'d ast::Scenario>,
+    /// Rules can not be tagged themselves, so these are the feature tags
+    /// which are inherited by the cukes of the rule.
+    pub tags: Vec<Tag<'d>>,
 }
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::cuke::{Cuke, Location, OwnedStep, OwnedTag};
+
+/// The JSON representation shared by `Cuke` and `OwnedCuke`.
+///
+/// The feature, rule, backgrounds and scenario are referenced by their ids,
+/// so that an `OwnedCuke` can be deserialized against the document it was compiled from.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SerializedCuke<'a, S, T, I> {
+    pub id: &'a str,
+    pub uri: &'a str,
+    pub name: &'a str,
+    pub language: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature_background_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_background_id: Option<&'a str>,
+    pub scenario_id: &'a str,
+    pub feature_background_steps: &'a [S],
+    pub rule_background_steps: &'a [S],
+    pub scenario_steps: &'a [S],
+    pub tags: &'a [T],
+    pub locations: &'a [Location],
+    pub ast_node_ids: &'a [I],
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct DeserializedCuke {
+    pub id: String,
+    pub uri: String,
+    pub name: String,
+    pub language: String,
+    pub feature_background_id: Option<String>,
+    pub rule_id: Option<String>,
+    pub rule_background_id: Option<String>,
+    pub scenario_id: String,
+    pub feature_background_steps: Vec<OwnedStep>,
+    pub rule_background_steps: Vec<OwnedStep>,
+    pub scenario_steps: Vec<OwnedStep>,
+    pub tags: Vec<OwnedTag>,
+    pub locations: Vec<Location>,
+    pub ast_node_ids: Vec<String>,
+}
+
+impl Serialize for Cuke<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedCuke {
+            id: &self.id,
+            uri: self.uri,
+            name: &self.name,
+            language: self.language,
+            feature_background_id: self
+                .feature_background
+                .map(|background| background.id.as_str()),
+            rule_id: self.rule.as_ref().map(|rule| rule.ast_node_id),
+            rule_background_id: self
+                .rule_background
+                .map(|background| background.id.as_str()),
+            scenario_id: &self.scenario.id,
+            feature_background_steps: &self.feature_background_steps,
+            rule_background_steps: &self.rule_background_steps,
+            scenario_steps: &self.scenario_steps,
+            tags: &self.tags,
+            locations: &self.locations,
+            ast_node_ids: &self.ast_node_ids,
+        }
+        .serialize(serializer)
+    }
+}
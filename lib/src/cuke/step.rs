@@ -2,14 +2,20 @@ use std::borrow::Cow;
 use std::string::String as StdString;
 
 use cucumber_messages::pickle;
+use serde::Serialize;
 
 use crate::cuke::{Argument, Location};
+use crate::StepKeywordType;
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Step<'d> {
     pub id: StdString,
     pub keyword: &'d str,
+    /// The keyword type with conjunctions (`And`, `But`) resolved to the type of the previous step.
+    pub keyword_type: StepKeywordType,
     pub text: Cow<'d, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub argument: Option<Argument<'d>>,
     pub locations: Vec<Location>,
     pub ast_node_ids: Vec<&'d str>,
@@ -1,10 +1,12 @@
 use std::borrow::Cow;
 
 use cucumber_messages::pickle;
+use serde::{Deserialize, Serialize};
 
 use crate::cuke::Location;
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct String<'d> {
     pub location: Location,
     pub content: Cow<'d, str>,
@@ -15,6 +17,14 @@ impl<'d> String<'d> {
     pub fn get_location(&self) -> Location {
         self.location
     }
+
+    pub fn into_owned(self) -> String<'static> {
+        String {
+            location: self.location,
+            content: Cow::Owned(self.content.into_owned()),
+            media_type: Cow::Owned(self.media_type.into_owned()),
+        }
+    }
 }
 
 impl<'d> AsRef<str> for String<'d> {
@@ -1,15 +1,222 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::result;
+
+use cucumber_messages::ast;
 use cucumber_messages::pickle;
+use failure::Fail;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::cuke::table_de::TableDeserializer;
+use crate::cuke::{Cell, Location, Row, TableDiff};
+
+/// A type alias for `Result<T, TableError>`.
+pub type TableResult<T> = result::Result<T, TableError>;
 
-use crate::cuke::{Location, Row};
+/// An error of reading a `Table` in a step definition,
+/// e.g. a row with another number of cells than the header or a cell which can't be deserialized.
+#[derive(Fail, Debug, Clone, Eq, PartialEq)]
+pub struct TableError {
+    pub location: Location,
+    pub message: String,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}:{}): {}",
+            self.location.line, self.location.column, self.message
+        )
+    }
+}
 
-#[derive(Debug, Clone)]
+/// A data table of a step.
+///
+/// The first row is treated as the header by the methods which need one.
+/// `ast::DataTable`s can be converted into a `Table` to use the same methods,
+/// cells without a location are placed at line 0.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Table<'d> {
     pub rows: Vec<Row<'d>>,
 }
 
 impl<'d> Table<'d> {
+    /// Returns the location of the first cell, or line 0 if the table has none.
     pub fn get_location(&self) -> Location {
-        self.rows[0].cells[0].location
+        self.rows
+            .iter()
+            .flat_map(|row| row.cells.first())
+            .map(|cell| cell.location)
+            .next()
+            .unwrap_or(Location { line: 0, column: 0 })
+    }
+
+    pub fn into_owned(self) -> Table<'static> {
+        Table {
+            rows: self.rows.into_iter().map(Row::into_owned).collect(),
+        }
+    }
+
+    /// Returns the first row.
+    pub fn header(&self) -> Option<&Row<'d>> {
+        self.rows.first()
+    }
+
+    /// Returns all rows except the header.
+    pub fn body(&self) -> &[Row<'d>] {
+        if self.rows.is_empty() {
+            &self.rows
+        } else {
+            &self.rows[1..]
+        }
+    }
+
+    /// Returns the index of the header cell with the given value.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.header()?
+            .cells
+            .iter()
+            .position(|cell| cell.value == name)
+    }
+
+    /// Iterates over the cells of the column with the given index, including the header cell.
+    pub fn column(&self, index: usize) -> impl Iterator<Item = &Cell<'d>> {
+        self.rows.iter().filter_map(move |row| row.cells.get(index))
+    }
+
+    /// Iterates over the body cells of the column with the given header.
+    pub fn column_by_name(&self, name: &str) -> Option<impl Iterator<Item = &Cell<'d>>> {
+        let index = self.column_index(name)?;
+        Some(self.column(index).skip(1))
+    }
+
+    /// Iterates over all columns.
+    ///
+    /// Fails like `rows_as_maps` if a row has a different number of cells than the header.
+    pub fn columns(&self) -> TableResult<impl Iterator<Item = Vec<&Cell<'d>>>> {
+        self.check_row_lengths()?;
+        let column_count = self.header().map_or(0, |header| header.cells.len());
+        Ok((0..column_count).map(move |index| self.column(index).collect()))
+    }
+
+    /// Returns a map from the header values to the cell values for each row of the body.
+    ///
+    /// Fails with the location of the first row which has a different number of cells
+    /// than the header.
+    pub fn rows_as_maps(&self) -> TableResult<Vec<HashMap<&str, &str>>> {
+        self.check_row_lengths()?;
+        let header = match self.header() {
+            Some(header) => header,
+            None => return Ok(Vec::new()),
+        };
+
+        let maps = self
+            .body()
+            .iter()
+            .map(|row| {
+                header
+                    .cells
+                    .iter()
+                    .zip(&row.cells)
+                    .map(|(header_cell, cell)| (header_cell.as_ref(), cell.as_ref()))
+                    .collect()
+            })
+            .collect();
+        Ok(maps)
+    }
+
+    /// Swaps the rows and the columns, keeping the location of every cell.
+    ///
+    /// Fails like `rows_as_maps` if a row has a different number of cells than the header.
+    pub fn transpose(&self) -> TableResult<Table<'d>> {
+        let rows = self
+            .columns()?
+            .map(|cells| Row {
+                cells: cells.into_iter().cloned().collect(),
+            })
+            .collect();
+
+        Ok(Table { rows })
+    }
+
+    /// Fails with the location of the first row which has a different number of cells
+    /// than the header.
+    fn check_row_lengths(&self) -> TableResult<()> {
+        let header = match self.header() {
+            Some(header) => header,
+            None => return Ok(()),
+        };
+
+        match self
+            .body()
+            .iter()
+            .find(|row| row.cells.len() != header.cells.len())
+        {
+            Some(row) => Err(TableError {
+                location: row
+                    .cells
+                    .first()
+                    .map_or_else(|| self.get_location(), |cell| cell.location),
+                message: format!(
+                    "expected a row with {} cells like the header, but found {} cells",
+                    header.cells.len(),
+                    row.cells.len()
+                ),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Compares the table row by row with an expected table.
+    ///
+    /// `self` is treated as the actual table, so its rows that are not expected
+    /// are reported as surplus and the expected rows that are not found as missing.
+    pub fn diff(&self, expected: &Table<'_>) -> TableDiff {
+        TableDiff::new(self, expected)
+    }
+
+    /// Deserializes the table with serde.
+    ///
+    /// Sequences like `Vec<T>` get one item per body row, which is deserialized
+    /// as a map from the header values to the cell values.
+    /// Maps and structs are deserialized vertically from a table with two columns,
+    /// where the first column contains the keys and the second one the values.
+    ///
+    /// Errors contain the location of the cell that could not be deserialized.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> TableResult<T> {
+        T::deserialize(TableDeserializer::new(self)).map_err(|error| TableError {
+            location: error.location.unwrap_or_else(|| self.get_location()),
+            message: error.message,
+        })
+    }
+}
+
+impl<'d> From<&'d ast::DataTable> for Table<'d> {
+    fn from(data_table: &'d ast::DataTable) -> Table<'d> {
+        let rows = data_table
+            .rows
+            .iter()
+            .map(|row| {
+                let cells = row
+                    .cells
+                    .iter()
+                    .map(|cell| Cell {
+                        location: cell
+                            .location
+                            .map(Location::from)
+                            .unwrap_or(Location { line: 0, column: 0 }),
+                        value: Cow::Borrowed(cell.value.as_str()),
+                    })
+                    .collect();
+
+                Row { cells }
+            })
+            .collect();
+
+        Table { rows }
     }
 }
 
@@ -20,3 +227,250 @@ impl<'d> From<Table<'d>> for pickle::Table {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+    use serde::Deserialize;
+
+    use crate::{DocumentBuilder, Parser};
+
+    use super::*;
+
+    fn parse_data_table(rows: &str) -> ast::DataTable {
+        let source = format!(
+            "Feature: Tables\n  Scenario: A table\n    Given a table\n{}",
+            rows
+        );
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = parser.parse_str(&source).unwrap();
+
+        let feature_child = gherkin_document.feature.unwrap().children.remove(0);
+        match feature_child.value {
+            Some(ast::FeatureChildValue::Scenario(mut scenario)) => {
+                match scenario.steps.remove(0).argument {
+                    Some(ast::Argument::DataTable(data_table)) => data_table,
+                    argument => panic!("unexpected argument: {:?}", argument),
+                }
+            }
+            value => panic!("unexpected feature child: {:?}", value),
+        }
+    }
+
+    fn values<'t>(cells: impl Iterator<Item = &'t Cell<'t>>) -> Vec<&'t str> {
+        cells.map(|cell| cell.as_ref()).collect()
+    }
+
+    const USERS: &str = concat!(
+        "      | name  | age | admin |\n",
+        "      | Alice | 42  | true  |\n",
+        "      | Bob   | 7   | false |\n",
+    );
+
+    #[test]
+    fn looks_up_columns_by_header() {
+        let data_table = parse_data_table(USERS);
+        let table = Table::from(&data_table);
+
+        assert_eq!(
+            values(table.header().unwrap().cells.iter()),
+            vec!["name", "age", "admin"]
+        );
+        assert_eq!(table.body().len(), 2);
+        assert_eq!(table.column_index("age"), Some(1));
+        assert_eq!(table.column_index("email"), None);
+        assert_eq!(
+            values(table.column_by_name("name").unwrap()),
+            vec!["Alice", "Bob"]
+        );
+        assert!(table.column_by_name("email").is_none());
+        assert_eq!(
+            table
+                .columns()
+                .unwrap()
+                .map(|column| column.len())
+                .collect::<Vec<_>>(),
+            vec![3, 3, 3]
+        );
+
+        let maps = table.rows_as_maps().unwrap();
+        assert_eq!(maps.len(), 2);
+        assert_eq!(maps[0]["name"], "Alice");
+        assert_eq!(maps[1]["admin"], "false");
+    }
+
+    #[test]
+    fn rejects_ragged_rows_as_maps() {
+        let data_table = parse_data_table(USERS);
+        let mut table = Table::from(&data_table);
+        table.rows[2].cells.pop();
+
+        assert_eq!(
+            table.rows_as_maps().unwrap_err().to_string(),
+            "(6:9): expected a row with 3 cells like the header, but found 2 cells"
+        );
+    }
+
+    #[test]
+    fn rejects_ragged_rows_as_columns() {
+        let data_table = parse_data_table(USERS);
+        let mut table = Table::from(&data_table);
+        let cell = table.rows[1].cells[0].clone();
+        table.rows[1].cells.push(cell);
+
+        let message = "(5:9): expected a row with 3 cells like the header, but found 4 cells";
+        assert_eq!(table.columns().err().unwrap().to_string(), message);
+        assert_eq!(table.transpose().unwrap_err().to_string(), message);
+    }
+
+    #[test]
+    fn transposes_tables() {
+        let data_table = parse_data_table(USERS);
+        let table = Table::from(&data_table).transpose().unwrap();
+
+        assert_eq!(table.rows.len(), 3);
+        assert_eq!(values(table.rows[1].cells.iter()), vec!["age", "42", "7"]);
+        assert_eq!(
+            table.rows[1].cells[1].location,
+            Location {
+                line: 5,
+                column: 17
+            }
+        );
+        assert_eq!(
+            values(table.transpose().unwrap().rows[2].cells.iter()),
+            vec!["Bob", "7", "false"]
+        );
+    }
+
+    #[test]
+    fn diffs_tables() {
+        let data_table = parse_data_table(USERS);
+        let actual = Table::from(&data_table);
+
+        assert!(actual.diff(&actual).is_equal());
+
+        let expected_data_table = parse_data_table(concat!(
+            "      | name    | age | admin |\n",
+            "      | Alice   | 42  | true  |\n",
+            "      | Charlie | 99  | false |\n",
+        ));
+        let diff = actual.diff(&Table::from(&expected_data_table));
+
+        assert!(!diff.is_equal());
+        assert_eq!(
+            diff.to_string(),
+            concat!(
+                "  | name    | age | admin |\n",
+                "  | Alice   | 42  | true  |\n",
+                "- | Charlie | 99  | false |\n",
+                "+ | Bob     | 7   | false |\n",
+            )
+        );
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct User {
+        name: String,
+        age: u32,
+        admin: bool,
+    }
+
+    #[test]
+    fn deserializes_rows() {
+        let data_table = parse_data_table(USERS);
+        let users: Vec<User> = Table::from(&data_table).deserialize().unwrap();
+
+        assert_eq!(
+            users,
+            vec![
+                User {
+                    name: String::from("Alice"),
+                    age: 42,
+                    admin: true,
+                },
+                User {
+                    name: String::from("Bob"),
+                    age: 7,
+                    admin: false,
+                },
+            ]
+        );
+
+        let rows: Vec<(String, u8, bool)> = Table::from(&data_table).deserialize().unwrap();
+        assert_eq!(rows[1], (String::from("Bob"), 7, false));
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin,
+        Guest,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Account {
+        login: String,
+        role: Role,
+        email: Option<String>,
+    }
+
+    #[test]
+    fn deserializes_key_value_tables() {
+        let data_table = parse_data_table(concat!(
+            "      | login | alice |\n",
+            "      | role  | guest |\n",
+            "      | email |       |\n",
+        ));
+        let account: Account = Table::from(&data_table).deserialize().unwrap();
+
+        assert_eq!(
+            account,
+            Account {
+                login: String::from("alice"),
+                role: Role::Guest,
+                email: None,
+            }
+        );
+    }
+
+    #[test]
+    fn reports_locations_of_invalid_cells() {
+        let error_location = |rows: &str| -> (Location, String) {
+            let data_table = parse_data_table(rows);
+            match Table::from(&data_table).deserialize::<Vec<User>>() {
+                Err(TableError { location, message }) => (location, message),
+                result => panic!("unexpected result: {:?}", result.map(|_| ())),
+            }
+        };
+
+        let (location, message) = error_location(concat!(
+            "      | name  | age | admin |\n",
+            "      | Alice | 42  | true  |\n",
+            "      | Bob   | old | false |\n",
+        ));
+        assert_eq!((location.line, location.column), (6, 17));
+        assert_eq!(
+            message,
+            "cannot parse 'old' as u32: invalid digit found in string"
+        );
+
+        let (location, message) = error_location(concat!(
+            "      | name  | admin |\n",
+            "      | Alice | true  |\n",
+        ));
+        assert_eq!((location.line, location.column), (5, 9));
+        assert_eq!(message, "missing field `age`");
+
+        let data_table = parse_data_table("      | login | alice | admin |\n");
+        let error = Table::from(&data_table)
+            .deserialize::<Account>()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "(4:9): expected a row with a key and a value, but found 3 cells"
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use std::error;
+use std::fmt;
+use std::slice;
+
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::cuke::{Cell, Location, Row, Table};
+
+/// An error of the table deserializer with the location of the cell or row that caused it.
+#[derive(Debug)]
+pub(crate) struct DeError {
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl DeError {
+    fn new<T: fmt::Display>(message: T, location: Location) -> DeError {
+        DeError {
+            message: message.to_string(),
+            location: Some(location),
+        }
+    }
+
+    /// Sets the location if the error does not have a more specific one yet.
+    fn at(mut self, location: Location) -> DeError {
+        if self.location.is_none() {
+            self.location = Some(location);
+        }
+        self
+    }
+}
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(message: T) -> DeError {
+        DeError {
+            message: message.to_string(),
+            location: None,
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, DeError>;
+
+pub(crate) struct TableDeserializer<'t, 'd> {
+    table: &'t Table<'d>,
+}
+
+impl<'t, 'd> TableDeserializer<'t, 'd> {
+    pub(crate) fn new(table: &'t Table<'d>) -> TableDeserializer<'t, 'd> {
+        TableDeserializer { table }
+    }
+}
+
+impl<'de, 't, 'd> de::Deserializer<'de> for TableDeserializer<'t, 'd> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let header = match self.table.header() {
+            Some(header) => &header.cells[..],
+            None => &[],
+        };
+
+        visitor.visit_seq(RowsAccess {
+            header,
+            rows: self.table.body().iter(),
+        })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let mut entries = Vec::with_capacity(self.table.rows.len());
+        for row in &self.table.rows {
+            match row.cells.as_slice() {
+                [key, value] => entries.push((key, value)),
+                cells => {
+                    return Err(DeError::new(
+                        format!(
+                            "expected a row with a key and a value, but found {} cells",
+                            cells.len()
+                        ),
+                        row_location(row),
+                    ));
+                }
+            }
+        }
+
+        visitor.visit_map(CellsAccess {
+            entries: entries.into_iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct tuple
+        tuple_struct enum identifier ignored_any
+    }
+}
+
+struct RowsAccess<'t, 'd> {
+    header: &'t [Cell<'d>],
+    rows: slice::Iter<'t, Row<'d>>,
+}
+
+impl<'de, 't, 'd> SeqAccess<'de> for RowsAccess<'t, 'd> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.rows.next() {
+            Some(row) => {
+                let row_deserializer = RowDeserializer {
+                    header: self.header,
+                    row,
+                };
+                seed.deserialize(row_deserializer).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.rows.len())
+    }
+}
+
+/// Deserializes a row as a map from the header values to the cell values,
+/// or as a sequence of the cell values.
+struct RowDeserializer<'t, 'd> {
+    header: &'t [Cell<'d>],
+    row: &'t Row<'d>,
+}
+
+impl<'de, 't, 'd> de::Deserializer<'de> for RowDeserializer<'t, 'd> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let location = row_location(self.row);
+        if self.row.cells.len() != self.header.len() {
+            return Err(DeError::new(
+                format!(
+                    "expected {} cells like the header, but found {}",
+                    self.header.len(),
+                    self.row.cells.len()
+                ),
+                location,
+            ));
+        }
+
+        let entries = self.header.iter().zip(self.row.cells.iter());
+        visitor
+            .visit_map(CellsAccess {
+                entries,
+                value: None,
+            })
+            .map_err(|error| error.at(location))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let location = row_location(self.row);
+        visitor
+            .visit_seq(CellsSeqAccess {
+                cells: self.row.cells.iter(),
+            })
+            .map_err(|error| error.at(location))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct map struct
+        enum identifier ignored_any
+    }
+}
+
+struct CellsAccess<'t, 'd, I>
+where
+    I: Iterator<Item = (&'t Cell<'d>, &'t Cell<'d>)>,
+    'd: 't,
+{
+    entries: I,
+    value: Option<&'t Cell<'d>>,
+}
+
+impl<'de, 't, 'd, I> MapAccess<'de> for CellsAccess<'t, 'd, I>
+where
+    I: Iterator<Item = (&'t Cell<'d>, &'t Cell<'d>)>,
+    'd: 't,
+{
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(CellDeserializer { cell: key }).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        match self.value.take() {
+            Some(cell) => seed.deserialize(CellDeserializer { cell }),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+}
+
+struct CellsSeqAccess<'t, 'd> {
+    cells: slice::Iter<'t, Cell<'d>>,
+}
+
+impl<'de, 't, 'd> SeqAccess<'de> for CellsSeqAccess<'t, 'd> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.cells.next() {
+            Some(cell) => seed.deserialize(CellDeserializer { cell }).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.cells.len())
+    }
+}
+
+/// Deserializes the value of a cell, parsing it for primitive types.
+struct CellDeserializer<'t, 'd> {
+    cell: &'t Cell<'d>,
+}
+
+impl<'t, 'd> CellDeserializer<'t, 'd> {
+    fn value(&self) -> &'t str {
+        self.cell.as_ref()
+    }
+
+    fn located<T>(&self, result: Result<T>) -> Result<T> {
+        result.map_err(|error| error.at(self.cell.location))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident($value_type:ty)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                match self.value().parse::<$value_type>() {
+                    Ok(value) => self.located(visitor.$visit(value)),
+                    Err(error) => Err(DeError::new(
+                        format!(
+                            "cannot parse '{}' as {}: {}",
+                            self.value(),
+                            stringify!($value_type),
+                            error
+                        ),
+                        self.cell.location,
+                    )),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, 't, 'd> de::Deserializer<'de> for CellDeserializer<'t, 'd> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.located(visitor.visit_str(self.value()))
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool(bool),
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+        deserialize_char => visit_char(char),
+    }
+
+    /// Empty cells are `None`.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.value().is_empty() {
+            self.located(visitor.visit_none())
+        } else {
+            let location = self.cell.location;
+            visitor.visit_some(self).map_err(|error| error.at(location))
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.value().is_empty() {
+            self.located(visitor.visit_unit())
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        let location = self.cell.location;
+        visitor
+            .visit_newtype_struct(self)
+            .map_err(|error| error.at(location))
+    }
+
+    /// Only unit variants can be deserialized from a cell.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.located(visitor.visit_enum(self.value().into_deserializer()))
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+fn row_location(row: &Row<'_>) -> Location {
+    match row.cells.first() {
+        Some(cell) => cell.location,
+        None => Location { line: 0, column: 0 },
+    }
+}
@@ -0,0 +1,128 @@
+use std::cmp;
+use std::fmt;
+
+use crate::cuke::{Row, Table};
+
+/// The result of comparing an actual with an expected `Table`.
+///
+/// The `Display` implementation renders the rows as an aligned table,
+/// prefixing missing rows with `-` and surplus rows with `+`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TableDiff {
+    pub rows: Vec<DiffRow>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DiffRow {
+    pub kind: DiffKind,
+    pub cells: Vec<String>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DiffKind {
+    /// The row is in both tables.
+    Unchanged,
+    /// The row is expected, but not in the actual table.
+    Missing,
+    /// The row is in the actual table, but not expected.
+    Surplus,
+}
+
+impl TableDiff {
+    pub(crate) fn new(actual: &Table<'_>, expected: &Table<'_>) -> TableDiff {
+        let actual = row_values(actual);
+        let expected = row_values(expected);
+
+        // longest common subsequence of the rows, lengths[i][j] covers expected[i..] and actual[j..]
+        let mut lengths = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+        for i in (0..expected.len()).rev() {
+            for j in (0..actual.len()).rev() {
+                lengths[i][j] = if expected[i] == actual[j] {
+                    lengths[i + 1][j + 1] + 1
+                } else {
+                    cmp::max(lengths[i + 1][j], lengths[i][j + 1])
+                };
+            }
+        }
+
+        let mut rows = Vec::with_capacity(cmp::max(actual.len(), expected.len()));
+        let (mut i, mut j) = (0, 0);
+        while i < expected.len() || j < actual.len() {
+            let kind = if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+                DiffKind::Unchanged
+            } else if i < expected.len()
+                && (j == actual.len() || lengths[i + 1][j] >= lengths[i][j + 1])
+            {
+                DiffKind::Missing
+            } else {
+                DiffKind::Surplus
+            };
+
+            let cells = match kind {
+                DiffKind::Unchanged => {
+                    i += 1;
+                    j += 1;
+                    &actual[j - 1]
+                }
+                DiffKind::Surplus => {
+                    j += 1;
+                    &actual[j - 1]
+                }
+                DiffKind::Missing => {
+                    i += 1;
+                    &expected[i - 1]
+                }
+            };
+            rows.push(DiffRow {
+                kind,
+                cells: cells.iter().map(|cell| String::from(*cell)).collect(),
+            });
+        }
+
+        TableDiff { rows }
+    }
+
+    /// Returns `true` if both tables have the same rows.
+    pub fn is_equal(&self) -> bool {
+        self.rows.iter().all(|row| row.kind == DiffKind::Unchanged)
+    }
+}
+
+impl fmt::Display for TableDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut widths = Vec::new();
+        for row in &self.rows {
+            for (index, cell) in row.cells.iter().enumerate() {
+                let width = cell.chars().count();
+                if index == widths.len() {
+                    widths.push(width);
+                } else {
+                    widths[index] = cmp::max(widths[index], width);
+                }
+            }
+        }
+
+        for row in &self.rows {
+            let prefix = match row.kind {
+                DiffKind::Unchanged => ' ',
+                DiffKind::Missing => '-',
+                DiffKind::Surplus => '+',
+            };
+            write!(f, "{} |", prefix)?;
+            for (cell, width) in row.cells.iter().zip(&widths) {
+                write!(f, " {:width$} |", cell, width = *width)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn row_values<'t>(table: &'t Table<'_>) -> Vec<Vec<&'t str>> {
+    table.rows.iter().map(cell_values).collect()
+}
+
+fn cell_values<'t>(row: &'t Row<'_>) -> Vec<&'t str> {
+    row.cells.iter().map(|cell| cell.as_ref()).collect()
+}
@@ -1,9 +1,11 @@
 use cucumber_messages::ast;
 use cucumber_messages::pickle;
+use serde::Serialize;
 
 use crate::cuke::Location;
 
-#[derive(Debug)]
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct Tag<'d> {
     pub name: &'d str,
     pub location: Location,
@@ -0,0 +1,82 @@
+use cucumber_messages::ast;
+
+use crate::visit::{self, Context, Visitor};
+use crate::{Error, Result};
+
+/// Checks that the examples rows of a `GherkinDocument` have as many cells as their header:
+/// documents built by the parser always have, but deserialized or hand-written ones may not.
+///
+/// Missing locations are reported by the compiler where it needs them.
+pub(crate) fn validate(gherkin_document: &ast::GherkinDocument) -> Result<()> {
+    let mut validator = Validator { error: None };
+    validator.visit_gherkin_document(gherkin_document);
+
+    match validator.error {
+        Some(message) => Err(Error::InvalidDocument {
+            uri: gherkin_document.uri.clone(),
+            message,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Remembers the first problem of the document.
+struct Validator {
+    error: Option<String>,
+}
+
+impl<'a> Visitor<'a> for Validator {
+    fn visit_examples(&mut self, examples: &'a ast::Examples, context: &Context<'a>) {
+        if let Some(table_header) = &examples.table_header {
+            let columns = table_header.cells.len();
+            let row = examples
+                .table_body
+                .iter()
+                .find(|row| row.cells.len() != columns);
+            if let (Some(row), None) = (row, &self.error) {
+                self.error = Some(format!(
+                    "examples row {:?} has {} cells, but the header has {}",
+                    row.id,
+                    row.cells.len(),
+                    columns
+                ));
+            }
+        }
+        visit::walk_examples(self, examples, context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::parse_document;
+
+    use super::*;
+
+    #[test]
+    fn rejects_examples_rows_with_missing_cells() {
+        let gherkin_document = parse_document(
+            "invalid.feature",
+            r#"
+Feature: Invalid
+  Scenario Outline: eating
+    Given I have <count> cukes
+
+    Examples:
+      | count |
+      | 5     |
+"#,
+        );
+        assert!(validate(&gherkin_document).is_ok());
+
+        let mut missing_cell = gherkin_document;
+        if let Some(ast::FeatureChildValue::Scenario(scenario)) =
+            &mut missing_cell.feature.as_mut().unwrap().children[0].value
+        {
+            scenario.examples[0].table_body[0].cells.clear();
+        }
+        assert_eq!(
+            validate(&missing_cell).unwrap_err().to_string(),
+            "invalid.feature: examples row \"2\" has 0 cells, but the header has 1"
+        );
+    }
+}
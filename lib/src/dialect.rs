@@ -1,7 +1,26 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+/// The type of a step keyword, derived from the keyword group it is defined in.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StepKeywordType {
+    /// The keyword is defined in more than one group (e.g. `* `).
+    Unknown,
+    /// A `given` keyword.
+    Context,
+    /// A `when` keyword.
+    Action,
+    /// A `then` keyword.
+    Outcome,
+    /// An `and` or `but` keyword that continues the previous step.
+    Conjunction,
+}
+
+/// The keyword types of steps by the id of the step.
+pub type StepKeywordTypes = HashMap<String, StepKeywordType>;
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Dialect {
     #[serde(skip)]
@@ -96,11 +115,21 @@ impl Dialect {
             step_keywords.is_empty(),
             "step keywords must only be initialized once"
         );
-        step_keywords.extend_from_slice(&self.given_keywords);
-        step_keywords.extend_from_slice(&self.when_keywords);
-        step_keywords.extend_from_slice(&self.then_keywords);
-        step_keywords.extend_from_slice(&self.and_keywords);
-        step_keywords.extend_from_slice(&self.but_keywords);
+        let keywords = self
+            .given_keywords
+            .iter()
+            .chain(&self.when_keywords)
+            .chain(&self.then_keywords)
+            .chain(&self.and_keywords)
+            .chain(&self.but_keywords);
+        for keyword in keywords {
+            if !step_keywords.contains(keyword) {
+                step_keywords.push(keyword.to_owned());
+            }
+        }
+        // a keyword may be a prefix of another one (e.g. "A " and "A tiež "),
+        // so the longest keyword has to be tried first
+        step_keywords.sort_by_key(|keyword| Reverse(keyword.len()));
     }
 
     pub fn get_language(&self) -> &String {
@@ -119,6 +148,7 @@ impl Dialect {
         &self.scenario_keywords
     }
 
+    /// Returns the distinct step keywords of all step types, longest keyword first.
     pub fn get_step_keywords(&self) -> &Vec<String> {
         &self.step_keywords
     }
@@ -154,4 +184,28 @@ impl Dialect {
     pub fn get_but_keywords(&self) -> &Vec<String> {
         &self.but_keywords
     }
+
+    pub fn get_step_keyword_type(&self, keyword: &str) -> StepKeywordType {
+        let keyword_types = [
+            (&self.given_keywords, StepKeywordType::Context),
+            (&self.when_keywords, StepKeywordType::Action),
+            (&self.then_keywords, StepKeywordType::Outcome),
+            (&self.and_keywords, StepKeywordType::Conjunction),
+            (&self.but_keywords, StepKeywordType::Conjunction),
+        ];
+
+        let mut step_keyword_type = None;
+        for (keywords, keyword_type) in keyword_types.iter() {
+            if !keywords.iter().any(|k| k == keyword) {
+                continue;
+            }
+            match step_keyword_type {
+                None => step_keyword_type = Some(*keyword_type),
+                Some(previous_type) if previous_type == *keyword_type => {}
+                Some(_) => return StepKeywordType::Unknown,
+            }
+        }
+
+        step_keyword_type.unwrap_or(StepKeywordType::Unknown)
+    }
 }
@@ -72,8 +72,25 @@ impl DialectProvider for BuiltInDialectProvider {
     }
 }
 
+/// Shares a dialect provider between token matchers.
+impl<DP: DialectProvider + ?Sized> DialectProvider for Arc<DP> {
+    fn get_default_dialect(&self) -> Result<Arc<Dialect>> {
+        (**self).get_default_dialect()
+    }
+
+    fn get_dialect(&self, language: &str, location: Location) -> Result<Arc<Dialect>> {
+        (**self).get_dialect(language, location)
+    }
+
+    fn get_languages(&self) -> Vec<&str> {
+        (**self).get_languages()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::dialect::StepKeywordType;
+
     use super::*;
 
     #[test]
@@ -89,10 +106,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn provides_step_keyword_types() {
+        let en_dialect = get_dialect("en");
+        assert_eq!(
+            en_dialect.get_step_keyword_type("Given "),
+            StepKeywordType::Context
+        );
+        assert_eq!(
+            en_dialect.get_step_keyword_type("When "),
+            StepKeywordType::Action
+        );
+        assert_eq!(
+            en_dialect.get_step_keyword_type("Then "),
+            StepKeywordType::Outcome
+        );
+        assert_eq!(
+            en_dialect.get_step_keyword_type("But "),
+            StepKeywordType::Conjunction
+        );
+        assert_eq!(
+            en_dialect.get_step_keyword_type("* "),
+            StepKeywordType::Unknown
+        );
+    }
+
     fn get_dialect(language: &str) -> Arc<Dialect> {
         let dialect_provider = BuiltInDialectProvider::default();
         let location = Location::new(0, 0);
-        let dialect = dialect_provider.get_dialect(language, location).unwrap();
-        dialect
+        dialect_provider.get_dialect(language, location).unwrap()
     }
 }
@@ -0,0 +1,863 @@
+//! Semantic differences between two versions of a `GherkinDocument`.
+//!
+//! The documents are compared node by node instead of line by line, so formatting-only changes
+//! like realigned tables, indentation or comments are no differences. Scenarios and backgrounds
+//! are matched by their name within their rule, and scenarios which were renamed are recognized
+//! by their steps.
+
+use std::fmt;
+
+use cucumber_messages::ast;
+use serde::Serialize;
+
+use crate::duplicates::similarity;
+
+/// The minimum similarity of the steps of two scenarios to treat them as renamed.
+const RENAME_SIMILARITY: f64 = 0.5;
+
+/// A scenario or background, identified by its rule and name.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioRef {
+    pub rule: Option<String>,
+    pub keyword: String,
+    pub name: String,
+    pub line: u32,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepRef {
+    pub keyword: String,
+    pub text: String,
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_string: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_table: Option<Vec<Vec<String>>>,
+}
+
+/// A change from the old to the new document.
+///
+/// The lines of added nodes refer to the new document, the lines of removed nodes to the old one.
+/// Changes within a scenario reference the scenario of the new document, except for the changes
+/// of removed nodes.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Change {
+    #[serde(rename_all = "camelCase")]
+    FeatureRenamed {
+        old_name: String,
+        new_name: String,
+    },
+    /// The tags of the feature, a scenario or examples changed.
+    #[serde(rename_all = "camelCase")]
+    TagsChanged {
+        scenario: Option<ScenarioRef>,
+        examples: Option<String>,
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+    RuleAdded {
+        name: String,
+        line: u32,
+    },
+    RuleRemoved {
+        name: String,
+        line: u32,
+    },
+    ScenarioAdded {
+        scenario: ScenarioRef,
+    },
+    ScenarioRemoved {
+        scenario: ScenarioRef,
+    },
+    ScenarioRenamed {
+        old: ScenarioRef,
+        new: ScenarioRef,
+    },
+    StepAdded {
+        scenario: ScenarioRef,
+        step: StepRef,
+    },
+    StepRemoved {
+        scenario: ScenarioRef,
+        step: StepRef,
+    },
+    StepChanged {
+        scenario: ScenarioRef,
+        old: Box<StepRef>,
+        new: Box<StepRef>,
+    },
+    ExamplesAdded {
+        scenario: ScenarioRef,
+        name: String,
+        line: u32,
+    },
+    ExamplesRemoved {
+        scenario: ScenarioRef,
+        name: String,
+        line: u32,
+    },
+    ExamplesHeaderChanged {
+        scenario: ScenarioRef,
+        examples: String,
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+    ExamplesRowAdded {
+        scenario: ScenarioRef,
+        examples: String,
+        cells: Vec<String>,
+        line: u32,
+    },
+    ExamplesRowRemoved {
+        scenario: ScenarioRef,
+        examples: String,
+        cells: Vec<String>,
+        line: u32,
+    },
+}
+
+/// Returns the changes from the old to the new document, in the order of the new document.
+pub fn diff_documents(old: &ast::GherkinDocument, new: &ast::GherkinDocument) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let empty_feature = ast::Feature::default();
+    let old_feature = old.feature.as_ref().unwrap_or(&empty_feature);
+    let new_feature = new.feature.as_ref().unwrap_or(&empty_feature);
+
+    if old_feature.name != new_feature.name {
+        changes.push(Change::FeatureRenamed {
+            old_name: old_feature.name.clone(),
+            new_name: new_feature.name.clone(),
+        });
+    }
+    diff_tags(
+        &mut changes,
+        &old_feature.tags,
+        &new_feature.tags,
+        None,
+        None,
+    );
+
+    let old_container = Container::of_feature(old_feature);
+    let new_container = Container::of_feature(new_feature);
+    diff_containers(&mut changes, &old_container, &new_container);
+    changes
+}
+
+/// The backgrounds and scenarios of a feature or rule, and the rules of a feature.
+struct Container<'a> {
+    units: Vec<Unit<'a>>,
+    rules: Vec<(&'a ast::Rule, Container<'a>)>,
+}
+
+/// A background or scenario.
+struct Unit<'a> {
+    reference: ScenarioRef,
+    is_background: bool,
+    tags: &'a [ast::Tag],
+    steps: &'a [ast::Step],
+    examples: &'a [ast::Examples],
+}
+
+impl<'a> Container<'a> {
+    fn of_feature(feature: &'a ast::Feature) -> Container<'a> {
+        let mut container = Container {
+            units: Vec::new(),
+            rules: Vec::new(),
+        };
+        for child in &feature.children {
+            match &child.value {
+                Some(ast::FeatureChildValue::Background(background)) => {
+                    container.units.push(Unit::of_background(background, None))
+                }
+                Some(ast::FeatureChildValue::Scenario(scenario)) => {
+                    container.units.push(Unit::of_scenario(scenario, None))
+                }
+                Some(ast::FeatureChildValue::Rule(rule)) => {
+                    container.rules.push((rule, Container::of_rule(rule)))
+                }
+                None => {}
+            }
+        }
+        container
+    }
+
+    fn of_rule(rule: &'a ast::Rule) -> Container<'a> {
+        let units = rule
+            .children
+            .iter()
+            .filter_map(|child| match &child.value {
+                Some(ast::RuleChildValue::Background(background)) => {
+                    Some(Unit::of_background(background, Some(&rule.name)))
+                }
+                Some(ast::RuleChildValue::Scenario(scenario)) => {
+                    Some(Unit::of_scenario(scenario, Some(&rule.name)))
+                }
+                None => None,
+            })
+            .collect();
+        Container {
+            units,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Unit<'a> {
+    fn of_background(background: &'a ast::Background, rule: Option<&str>) -> Unit<'a> {
+        Unit {
+            reference: ScenarioRef {
+                rule: rule.map(str::to_string),
+                keyword: background.keyword.clone(),
+                name: background.name.clone(),
+                line: line(background.location),
+            },
+            is_background: true,
+            tags: &[],
+            steps: &background.steps,
+            examples: &[],
+        }
+    }
+
+    fn of_scenario(scenario: &'a ast::Scenario, rule: Option<&str>) -> Unit<'a> {
+        Unit {
+            reference: ScenarioRef {
+                rule: rule.map(str::to_string),
+                keyword: scenario.keyword.clone(),
+                name: scenario.name.clone(),
+                line: line(scenario.location),
+            },
+            is_background: false,
+            tags: &scenario.tags,
+            steps: &scenario.steps,
+            examples: &scenario.examples,
+        }
+    }
+
+    /// Returns whether two units are the same background or equally named scenarios.
+    fn is_named_like(&self, other: &Unit<'_>) -> bool {
+        self.is_background == other.is_background
+            && (self.is_background || self.reference.name == other.reference.name)
+    }
+}
+
+fn diff_containers(changes: &mut Vec<Change>, old: &Container<'_>, new: &Container<'_>) {
+    let mut old_matched = vec![false; old.units.len()];
+    let mut new_matches = vec![None; new.units.len()];
+
+    for (new_index, new_unit) in new.units.iter().enumerate() {
+        let old_index = (0..old.units.len())
+            .find(|&index| !old_matched[index] && old.units[index].is_named_like(new_unit));
+        if let Some(old_index) = old_index {
+            old_matched[old_index] = true;
+            new_matches[new_index] = Some(old_index);
+        }
+    }
+
+    // the remaining scenarios with the most similar steps were renamed
+    let mut candidates = Vec::new();
+    for (new_index, new_unit) in new.units.iter().enumerate() {
+        for (old_index, old_unit) in old.units.iter().enumerate() {
+            if new_matches[new_index].is_none()
+                && !old_matched[old_index]
+                && old_unit.is_background == new_unit.is_background
+            {
+                let similarity = similarity(&step_keys(old_unit.steps), &step_keys(new_unit.steps));
+                if similarity >= RENAME_SIMILARITY {
+                    candidates.push((similarity, old_index, new_index));
+                }
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+    for (_similarity, old_index, new_index) in candidates {
+        if new_matches[new_index].is_none() && !old_matched[old_index] {
+            old_matched[old_index] = true;
+            new_matches[new_index] = Some(old_index);
+        }
+    }
+
+    for (old_index, old_unit) in old.units.iter().enumerate() {
+        if !old_matched[old_index] {
+            changes.push(Change::ScenarioRemoved {
+                scenario: old_unit.reference.clone(),
+            });
+        }
+    }
+    for (new_index, new_unit) in new.units.iter().enumerate() {
+        match new_matches[new_index] {
+            Some(old_index) => diff_units(changes, &old.units[old_index], new_unit),
+            None => changes.push(Change::ScenarioAdded {
+                scenario: new_unit.reference.clone(),
+            }),
+        }
+    }
+
+    for (old_rule, _old_container) in &old.rules {
+        if !new.rules.iter().any(|(rule, _)| rule.name == old_rule.name) {
+            changes.push(Change::RuleRemoved {
+                name: old_rule.name.clone(),
+                line: line(old_rule.location),
+            });
+        }
+    }
+    for (new_rule, new_container) in &new.rules {
+        match old
+            .rules
+            .iter()
+            .find(|(rule, _)| rule.name == new_rule.name)
+        {
+            Some((_old_rule, old_container)) => {
+                diff_containers(changes, old_container, new_container)
+            }
+            None => changes.push(Change::RuleAdded {
+                name: new_rule.name.clone(),
+                line: line(new_rule.location),
+            }),
+        }
+    }
+}
+
+fn diff_units(changes: &mut Vec<Change>, old: &Unit<'_>, new: &Unit<'_>) {
+    let scenario = &new.reference;
+    if old.reference.name != new.reference.name {
+        changes.push(Change::ScenarioRenamed {
+            old: old.reference.clone(),
+            new: new.reference.clone(),
+        });
+    }
+    diff_tags(changes, old.tags, new.tags, Some(scenario), None);
+
+    for edit in align(&step_keys(old.steps), &step_keys(new.steps)) {
+        match edit {
+            Edit::Same(..) => {}
+            Edit::Changed(old_index, new_index) => changes.push(Change::StepChanged {
+                scenario: scenario.clone(),
+                old: Box::new(step_ref(&old.steps[old_index])),
+                new: Box::new(step_ref(&new.steps[new_index])),
+            }),
+            Edit::Removed(old_index) => changes.push(Change::StepRemoved {
+                scenario: scenario.clone(),
+                step: step_ref(&old.steps[old_index]),
+            }),
+            Edit::Added(new_index) => changes.push(Change::StepAdded {
+                scenario: scenario.clone(),
+                step: step_ref(&new.steps[new_index]),
+            }),
+        }
+    }
+
+    let mut old_matched = vec![false; old.examples.len()];
+    for new_examples in new.examples {
+        let old_index = (0..old.examples.len())
+            .find(|&index| !old_matched[index] && old.examples[index].name == new_examples.name);
+        match old_index {
+            Some(old_index) => {
+                old_matched[old_index] = true;
+                diff_examples(changes, scenario, &old.examples[old_index], new_examples);
+            }
+            None => changes.push(Change::ExamplesAdded {
+                scenario: scenario.clone(),
+                name: new_examples.name.clone(),
+                line: line(new_examples.location),
+            }),
+        }
+    }
+    for (old_index, old_examples) in old.examples.iter().enumerate() {
+        if !old_matched[old_index] {
+            changes.push(Change::ExamplesRemoved {
+                scenario: old.reference.clone(),
+                name: old_examples.name.clone(),
+                line: line(old_examples.location),
+            });
+        }
+    }
+}
+
+fn diff_examples(
+    changes: &mut Vec<Change>,
+    scenario: &ScenarioRef,
+    old: &ast::Examples,
+    new: &ast::Examples,
+) {
+    let name = &new.name;
+    diff_tags(changes, &old.tags, &new.tags, Some(scenario), Some(name));
+
+    let old_header = old.table_header.as_ref().map(cells).unwrap_or_default();
+    let new_header = new.table_header.as_ref().map(cells).unwrap_or_default();
+    if old_header != new_header {
+        changes.push(Change::ExamplesHeaderChanged {
+            scenario: scenario.clone(),
+            examples: name.clone(),
+            old: old_header,
+            new: new_header,
+        });
+    }
+
+    let old_rows = old
+        .table_body
+        .iter()
+        .map(cells)
+        .collect::<Vec<Vec<String>>>();
+    let new_rows = new
+        .table_body
+        .iter()
+        .map(cells)
+        .collect::<Vec<Vec<String>>>();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for edit in align(&old_rows, &new_rows) {
+        match edit {
+            Edit::Same(..) => {}
+            Edit::Changed(old_index, new_index) => {
+                removed.push(old_index);
+                added.push(new_index);
+            }
+            Edit::Removed(old_index) => removed.push(old_index),
+            Edit::Added(new_index) => added.push(new_index),
+        }
+    }
+    for old_index in removed {
+        changes.push(Change::ExamplesRowRemoved {
+            scenario: scenario.clone(),
+            examples: name.clone(),
+            cells: old_rows[old_index].clone(),
+            line: line(old.table_body[old_index].location),
+        });
+    }
+    for new_index in added {
+        changes.push(Change::ExamplesRowAdded {
+            scenario: scenario.clone(),
+            examples: name.clone(),
+            cells: new_rows[new_index].clone(),
+            line: line(new.table_body[new_index].location),
+        });
+    }
+}
+
+fn diff_tags(
+    changes: &mut Vec<Change>,
+    old: &[ast::Tag],
+    new: &[ast::Tag],
+    scenario: Option<&ScenarioRef>,
+    examples: Option<&String>,
+) {
+    let has_tag = |tags: &[ast::Tag], name: &str| tags.iter().any(|tag| tag.name == name);
+    let added = new
+        .iter()
+        .filter(|tag| !has_tag(old, &tag.name))
+        .map(|tag| tag.name.clone())
+        .collect::<Vec<String>>();
+    let removed = old
+        .iter()
+        .filter(|tag| !has_tag(new, &tag.name))
+        .map(|tag| tag.name.clone())
+        .collect::<Vec<String>>();
+
+    if !added.is_empty() || !removed.is_empty() {
+        changes.push(Change::TagsChanged {
+            scenario: scenario.cloned(),
+            examples: examples.cloned(),
+            added,
+            removed,
+        });
+    }
+}
+
+/// The parts of a step which are compared, without its location and formatting.
+#[derive(Debug, PartialEq)]
+pub(crate) struct StepKey<'a> {
+    keyword: &'a str,
+    text: &'a str,
+    doc_string: Option<(&'a str, &'a str)>,
+    data_table: Option<Vec<Vec<String>>>,
+}
+
+pub(crate) fn step_keys(steps: &[ast::Step]) -> Vec<StepKey<'_>> {
+    steps
+        .iter()
+        .map(|step| {
+            let (doc_string, data_table) = match &step.argument {
+                Some(ast::Argument::DocString(doc_string)) => {
+                    let doc_string = (doc_string.media_type.as_str(), doc_string.content.as_str());
+                    (Some(doc_string), None)
+                }
+                Some(ast::Argument::DataTable(data_table)) => {
+                    (None, Some(data_table.rows.iter().map(cells).collect()))
+                }
+                None => (None, None),
+            };
+            StepKey {
+                keyword: step.keyword.trim(),
+                text: &step.text,
+                doc_string,
+                data_table,
+            }
+        })
+        .collect()
+}
+
+fn step_ref(step: &ast::Step) -> StepRef {
+    let (doc_string, data_table) = match &step.argument {
+        Some(ast::Argument::DocString(doc_string)) => (Some(doc_string.content.clone()), None),
+        Some(ast::Argument::DataTable(data_table)) => {
+            (None, Some(data_table.rows.iter().map(cells).collect()))
+        }
+        None => (None, None),
+    };
+    StepRef {
+        keyword: step.keyword.clone(),
+        text: step.text.clone(),
+        line: line(step.location),
+        doc_string,
+        data_table,
+    }
+}
+
+pub(crate) fn cells(table_row: &ast::TableRow) -> Vec<String> {
+    table_row
+        .cells
+        .iter()
+        .map(|cell| cell.value.clone())
+        .collect()
+}
+
+fn line(location: Option<ast::Location>) -> u32 {
+    location.unwrap_or_default().line
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Edit {
+    Same(usize, usize),
+    /// An old item replaced by a new one at the same position.
+    Changed(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Aligns two sequences along their longest common subsequence.
+///
+/// Removed and added items between two common items are paired as changed items.
+fn align<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let lengths = lcs_lengths(old, new);
+
+    let mut edits = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let (mut old_index, mut new_index) = (0, 0);
+    while old_index < old.len() || new_index < new.len() {
+        if old_index < old.len() && new_index < new.len() && old[old_index] == new[new_index] {
+            flush_edits(&mut edits, &mut removed, &mut added);
+            edits.push(Edit::Same(old_index, new_index));
+            old_index += 1;
+            new_index += 1;
+        } else if new_index == new.len()
+            || (old_index < old.len()
+                && lengths[old_index + 1][new_index] >= lengths[old_index][new_index + 1])
+        {
+            removed.push(old_index);
+            old_index += 1;
+        } else {
+            added.push(new_index);
+            new_index += 1;
+        }
+    }
+    flush_edits(&mut edits, &mut removed, &mut added);
+    edits
+}
+
+fn flush_edits(edits: &mut Vec<Edit>, removed: &mut Vec<usize>, added: &mut Vec<usize>) {
+    let paired = removed.len().min(added.len());
+    for (&old_index, &new_index) in removed.iter().zip(added.iter()) {
+        edits.push(Edit::Changed(old_index, new_index));
+    }
+    edits.extend(removed.drain(..).skip(paired).map(Edit::Removed));
+    edits.extend(added.drain(..).skip(paired).map(Edit::Added));
+}
+
+/// Returns the lengths of the longest common subsequences of all suffixes of two sequences.
+fn lcs_lengths<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Vec<usize>> {
+    let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for old_index in (0..old.len()).rev() {
+        for new_index in (0..new.len()).rev() {
+            lengths[old_index][new_index] = if old[old_index] == new[new_index] {
+                lengths[old_index + 1][new_index + 1] + 1
+            } else {
+                lengths[old_index + 1][new_index].max(lengths[old_index][new_index + 1])
+            };
+        }
+    }
+    lengths
+}
+
+impl fmt::Display for ScenarioRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(rule) = &self.rule {
+            write!(f, "rule {:?} > ", rule)?;
+        }
+        write!(f, "{} {:?} (line {})", self.keyword, self.name, self.line)
+    }
+}
+
+impl fmt::Display for StepRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} (line {})",
+            format!("{}{}", self.keyword, self.text),
+            self.line
+        )
+    }
+}
+
+/// Displays a change as one line, starting with `+` for additions, `-` for removals
+/// and `~` for modifications.
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::FeatureRenamed { old_name, new_name } => {
+                write!(f, "~ feature renamed from {:?} to {:?}", old_name, new_name)
+            }
+            Change::TagsChanged {
+                scenario,
+                examples,
+                added,
+                removed,
+            } => {
+                f.write_str("~ tags of ")?;
+                match (scenario, examples) {
+                    (Some(scenario), Some(examples)) => {
+                        write!(f, "{} examples {:?}", scenario, examples)?
+                    }
+                    (Some(scenario), None) => write!(f, "{}", scenario)?,
+                    (None, _) => f.write_str("feature")?,
+                }
+                f.write_str(":")?;
+                for tag in added {
+                    write!(f, " +{}", tag)?;
+                }
+                for tag in removed {
+                    write!(f, " -{}", tag)?;
+                }
+                Ok(())
+            }
+            Change::RuleAdded { name, line } => write!(f, "+ rule {:?} (line {})", name, line),
+            Change::RuleRemoved { name, line } => write!(f, "- rule {:?} (line {})", name, line),
+            Change::ScenarioAdded { scenario } => write!(f, "+ {}", scenario),
+            Change::ScenarioRemoved { scenario } => write!(f, "- {}", scenario),
+            Change::ScenarioRenamed { old, new } => {
+                write!(f, "~ {} renamed to {:?}", old, new.name)
+            }
+            Change::StepAdded { scenario, step } => write!(f, "+ {}: step {}", scenario, step),
+            Change::StepRemoved { scenario, step } => write!(f, "- {}: step {}", scenario, step),
+            Change::StepChanged { scenario, old, new } => {
+                write!(f, "~ {}: step {} changed to {}", scenario, old, new)
+            }
+            Change::ExamplesAdded {
+                scenario,
+                name,
+                line,
+            } => write!(f, "+ {}: examples {:?} (line {})", scenario, name, line),
+            Change::ExamplesRemoved {
+                scenario,
+                name,
+                line,
+            } => write!(f, "- {}: examples {:?} (line {})", scenario, name, line),
+            Change::ExamplesHeaderChanged {
+                scenario,
+                examples,
+                old,
+                new,
+            } => write!(
+                f,
+                "~ {}: examples {:?} header | {} | changed to | {} |",
+                scenario,
+                examples,
+                old.join(" | "),
+                new.join(" | ")
+            ),
+            Change::ExamplesRowAdded {
+                scenario,
+                examples,
+                cells,
+                line,
+            } => write!(
+                f,
+                "+ {}: examples {:?} row | {} | (line {})",
+                scenario,
+                examples,
+                cells.join(" | "),
+                line
+            ),
+            Change::ExamplesRowRemoved {
+                scenario,
+                examples,
+                cells,
+                line,
+            } => write!(
+                f,
+                "- {}: examples {:?} row | {} | (line {})",
+                scenario,
+                examples,
+                cells.join(" | "),
+                line
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::parse_document;
+
+    use super::*;
+
+    #[test]
+    fn ignores_formatting() {
+        let old = parse_document(
+            "cukes.feature",
+            r#"
+Feature: Cukes
+  # a comment
+  Scenario Outline: eating
+    Given I have <count> cukes
+
+    Examples:
+      | count |
+      | 5 |
+"#,
+        );
+        let new = parse_document(
+            "cukes.feature",
+            r#"
+Feature:   Cukes
+
+
+    Scenario Outline: eating
+        Given   I have <count> cukes
+        Examples:
+          | count |
+          | 5     |
+"#,
+        );
+
+        assert_eq!(diff_documents(&old, &new), vec![]);
+    }
+
+    #[test]
+    fn reports_changes() {
+        let old = parse_document(
+            "cukes.feature",
+            r#"
+@food
+Feature: Cukes
+  Scenario: eating
+    Given I have 5 cukes
+    When I eat 2 cukes
+    Then I have 3 cukes
+
+  Scenario: removed
+    Given nothing
+
+  Scenario Outline: outline
+    Given I have <count> cukes
+
+    Examples: amounts
+      | count |
+      | 1     |
+      | 2     |
+"#,
+        );
+        let new = parse_document(
+            "cukes.feature",
+            r#"
+@food @slow
+Feature: Cukes
+  Scenario: eating some cukes
+    Given I have 5 cukes
+    When I eat 3 cukes
+    Then I have 3 cukes
+    And I am full
+
+  Scenario Outline: outline
+    Given I have <count> cukes
+
+    Examples: amounts
+      | count |
+      | 2     |
+      | 3     |
+
+  Rule: Added
+    Scenario: in rule
+      Given something
+"#,
+        );
+
+        let changes = diff_documents(&old, &new)
+            .iter()
+            .map(Change::to_string)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            changes,
+            vec![
+                "~ tags of feature: +@slow",
+                "- Scenario \"removed\" (line 9)",
+                "~ Scenario \"eating\" (line 4) renamed to \"eating some cukes\"",
+                "~ Scenario \"eating some cukes\" (line 4): step \"When I eat 2 cukes\" (line 6) \
+                 changed to \"When I eat 3 cukes\" (line 6)",
+                "+ Scenario \"eating some cukes\" (line 4): step \"And I am full\" (line 8)",
+                "- Scenario Outline \"outline\" (line 10): examples \"amounts\" row | 1 | (line 17)",
+                "+ Scenario Outline \"outline\" (line 10): examples \"amounts\" row | 3 | (line 16)",
+                "+ rule \"Added\" (line 18)",
+            ]
+        );
+
+        let json = serde_json::to_value(&diff_documents(&old, &new)[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "tagsChanged",
+                "scenario": null,
+                "examples": null,
+                "added": ["@slow"],
+                "removed": [],
+            })
+        );
+    }
+
+    #[test]
+    fn does_not_rename_backgrounds_to_scenarios() {
+        let old = parse_document(
+            "cukes.feature",
+            r#"
+Feature: Cukes
+  Background:
+    Given a basket
+    And some cukes
+"#,
+        );
+        let new = parse_document(
+            "cukes.feature",
+            r#"
+Feature: Cukes
+  Scenario: filling the basket
+    Given a basket
+    And some cukes
+"#,
+        );
+
+        let changes = diff_documents(&old, &new)
+            .iter()
+            .map(Change::to_string)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            changes,
+            vec![
+                "- Background \"\" (line 3)",
+                "+ Scenario \"filling the basket\" (line 3)",
+            ]
+        );
+    }
+}
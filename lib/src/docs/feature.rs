@@ -0,0 +1,238 @@
+use std::fmt::{self, Write};
+
+use cucumber_messages::ast;
+
+use crate::docs::html::{escape, escape_placeholders};
+use crate::docs::{node_anchor, page_head, page_tail, tag_anchor};
+
+/// Renders the page of a feature, which is placed one directory below the site root.
+pub fn render_feature_page(
+    gherkin_document: &ast::GherkinDocument,
+    feature: &ast::Feature,
+) -> String {
+    let mut html = String::new();
+    write_feature_page(&mut html, gherkin_document, feature).expect("write to string");
+    html
+}
+
+fn write_feature_page(
+    html: &mut String,
+    gherkin_document: &ast::GherkinDocument,
+    feature: &ast::Feature,
+) -> fmt::Result {
+    page_head(html, &feature.name, &feature.language, "../")?;
+    write!(
+        html,
+        "<nav><a href=\"../index.html\">Index</a></nav>\n\
+         <article class=\"feature\">\n\
+         <p class=\"uri\">{}</p>\n\
+         <h1><span class=\"keyword\">{}:</span> {}</h1>\n",
+        escape(&gherkin_document.uri),
+        escape(&feature.keyword),
+        escape(&feature.name),
+    )?;
+    write_tags(html, &feature.tags)?;
+    write_description(html, &feature.description)?;
+
+    for feature_child in &feature.children {
+        match &feature_child.value {
+            Some(ast::FeatureChildValue::Rule(rule)) => write_rule(html, rule)?,
+            Some(ast::FeatureChildValue::Background(background)) => {
+                write_background(html, background, 2)?
+            }
+            Some(ast::FeatureChildValue::Scenario(scenario)) => write_scenario(html, scenario, 2)?,
+            None => {}
+        }
+    }
+
+    html.push_str("</article>\n");
+    page_tail(html)
+}
+
+fn write_rule(html: &mut String, rule: &ast::Rule) -> fmt::Result {
+    write!(
+        html,
+        "<section class=\"rule\" id=\"{}\">\n\
+         <h2><span class=\"keyword\">{}:</span> {}</h2>\n",
+        node_anchor(&rule.id),
+        escape(&rule.keyword),
+        escape(&rule.name),
+    )?;
+    write_description(html, &rule.description)?;
+
+    for rule_child in &rule.children {
+        match &rule_child.value {
+            Some(ast::RuleChildValue::Background(background)) => {
+                write_background(html, background, 3)?
+            }
+            Some(ast::RuleChildValue::Scenario(scenario)) => write_scenario(html, scenario, 3)?,
+            None => {}
+        }
+    }
+
+    html.push_str("</section>\n");
+    Ok(())
+}
+
+fn write_background(html: &mut String, background: &ast::Background, level: u8) -> fmt::Result {
+    write!(
+        html,
+        "<section class=\"background\" id=\"{}\">\n\
+         <h{level}><span class=\"keyword\">{}:</span> {}</h{level}>\n",
+        node_anchor(&background.id),
+        escape(&background.keyword),
+        escape(&background.name),
+        level = level,
+    )?;
+    write_description(html, &background.description)?;
+    write_steps(html, &background.steps, false)?;
+    html.push_str("</section>\n");
+    Ok(())
+}
+
+fn write_scenario(html: &mut String, scenario: &ast::Scenario, level: u8) -> fmt::Result {
+    write!(
+        html,
+        "<section class=\"scenario\" id=\"{}\">\n\
+         <h{level}><span class=\"keyword\">{}:</span> {}</h{level}>\n",
+        node_anchor(&scenario.id),
+        escape(&scenario.keyword),
+        escape(&scenario.name),
+        level = level,
+    )?;
+    write_tags(html, &scenario.tags)?;
+    write_description(html, &scenario.description)?;
+
+    let is_outline = !scenario.examples.is_empty();
+    write_steps(html, &scenario.steps, is_outline)?;
+    for examples in &scenario.examples {
+        write_examples(html, examples, level + 1)?;
+    }
+
+    html.push_str("</section>\n");
+    Ok(())
+}
+
+fn write_examples(html: &mut String, examples: &ast::Examples, level: u8) -> fmt::Result {
+    write!(
+        html,
+        "<section class=\"examples\" id=\"{}\">\n\
+         <h{level}><span class=\"keyword\">{}:</span> {}</h{level}>\n",
+        node_anchor(&examples.id),
+        escape(&examples.keyword),
+        escape(&examples.name),
+        level = level,
+    )?;
+    write_tags(html, &examples.tags)?;
+    write_description(html, &examples.description)?;
+
+    if let Some(table_header) = &examples.table_header {
+        html.push_str("<table class=\"examples-table\">\n<thead>\n");
+        write_table_row(html, table_header, "th")?;
+        html.push_str("</thead>\n<tbody>\n");
+        for table_row in &examples.table_body {
+            write_table_row(html, table_row, "td")?;
+        }
+        html.push_str("</tbody>\n</table>\n");
+    }
+
+    html.push_str("</section>\n");
+    Ok(())
+}
+
+fn write_steps(html: &mut String, steps: &[ast::Step], is_outline: bool) -> fmt::Result {
+    if steps.is_empty() {
+        return Ok(());
+    }
+
+    html.push_str("<ol class=\"steps\">\n");
+    for step in steps {
+        let text = if is_outline {
+            escape_placeholders(&step.text)
+        } else {
+            escape(&step.text).into_owned()
+        };
+        write!(
+            html,
+            "<li><span class=\"keyword\">{}</span>{}",
+            escape(&step.keyword),
+            text,
+        )?;
+
+        match &step.argument {
+            Some(ast::Argument::DocString(doc_string)) => write_doc_string(html, doc_string)?,
+            Some(ast::Argument::DataTable(data_table)) => {
+                html.push_str("\n<table class=\"data-table\">\n<tbody>\n");
+                for table_row in &data_table.rows {
+                    write_table_row(html, table_row, "td")?;
+                }
+                html.push_str("</tbody>\n</table>\n");
+            }
+            None => {}
+        }
+
+        html.push_str("</li>\n");
+    }
+    html.push_str("</ol>\n");
+    Ok(())
+}
+
+fn write_doc_string(html: &mut String, doc_string: &ast::DocString) -> fmt::Result {
+    html.push_str("\n<figure class=\"doc-string\">\n");
+    if doc_string.media_type.is_empty() {
+        html.push_str("<pre><code>");
+    } else {
+        let media_type = escape(&doc_string.media_type);
+        write!(
+            html,
+            "<figcaption class=\"media-type\">{}</figcaption>\n\
+             <pre><code data-media-type=\"{}\">",
+            media_type, media_type,
+        )?;
+    }
+    write!(
+        html,
+        "{}</code></pre>\n</figure>\n",
+        escape(&doc_string.content)
+    )
+}
+
+fn write_table_row(html: &mut String, table_row: &ast::TableRow, cell_tag: &str) -> fmt::Result {
+    html.push_str("<tr>");
+    for table_cell in &table_row.cells {
+        write!(
+            html,
+            "<{tag}>{}</{tag}>",
+            escape(&table_cell.value),
+            tag = cell_tag,
+        )?;
+    }
+    html.push_str("</tr>\n");
+    Ok(())
+}
+
+fn write_tags(html: &mut String, tags: &[ast::Tag]) -> fmt::Result {
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    html.push_str("<ul class=\"tags\">");
+    for tag in tags {
+        write!(
+            html,
+            "<li><a class=\"tag\" href=\"../index.html#{}\">{}</a></li>",
+            tag_anchor(&tag.name),
+            escape(&tag.name),
+        )?;
+    }
+    html.push_str("</ul>\n");
+    Ok(())
+}
+
+fn write_description(html: &mut String, description: &str) -> fmt::Result {
+    let description = description.trim();
+    if description.is_empty() {
+        return Ok(());
+    }
+    writeln!(html, "<p class=\"description\">{}</p>", escape(description))
+}
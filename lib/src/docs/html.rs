@@ -0,0 +1,82 @@
+use std::borrow::Cow;
+
+/// Escapes the characters of a text which are special in HTML text and attribute values.
+pub fn escape(text: &str) -> Cow<'_, str> {
+    if !text.contains(|c| c == '&' || c == '<' || c == '>' || c == '"' || c == '\'') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Escapes a step text and highlights the `<placeholder>`s of a scenario outline.
+pub fn escape_placeholders(text: &str) -> String {
+    let mut html = String::with_capacity(text.len() + 16);
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        let end = match rest[start..].find('>') {
+            Some(len) => start + len,
+            None => break,
+        };
+        html.push_str(&escape(&rest[..start]));
+        html.push_str("<var class=\"placeholder\">");
+        html.push_str(&escape(&rest[start..=end]));
+        html.push_str("</var>");
+        rest = &rest[end + 1..];
+    }
+
+    html.push_str(&escape(rest));
+    html
+}
+
+/// Returns a file name or fragment identifier for a text, which only consists of
+/// lowercase ASCII letters, digits and hyphens.
+pub fn slug(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_placeholders() {
+        assert_eq!(
+            escape_placeholders("I eat <eat> & \"<left>\" > 0"),
+            "I eat <var class=\"placeholder\">&lt;eat&gt;</var> &amp; \
+             &quot;<var class=\"placeholder\">&lt;left&gt;</var>&quot; &gt; 0"
+        );
+    }
+
+    #[test]
+    fn creates_slugs() {
+        assert_eq!(
+            slug("features/Eating cukes.feature"),
+            "features-eating-cukes-feature"
+        );
+        assert_eq!(slug("--Grüße!--"), "gr-e");
+    }
+}
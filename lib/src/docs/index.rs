@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use cucumber_messages::ast;
+
+use crate::docs::node_anchor;
+use crate::visit::{self, Context, Visitor};
+
+/// An entry of the client-side search index.
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchEntry {
+    pub keyword: String,
+    pub title: String,
+    pub feature: String,
+    /// The url relative to the site root.
+    pub url: String,
+    /// The searchable text besides the title, like the tags and step texts.
+    pub text: String,
+}
+
+/// A link from the index of a tag to a tagged node.
+#[derive(Debug, Clone)]
+pub struct TagLink {
+    pub url: String,
+    pub title: String,
+}
+
+/// Collects the search entries and tag links of the feature on a page.
+pub struct IndexBuilder<'p> {
+    page: &'p str,
+    pub search_entries: Vec<SearchEntry>,
+    pub tag_links: BTreeMap<String, Vec<TagLink>>,
+}
+
+impl<'p> IndexBuilder<'p> {
+    pub fn new(page: &'p str) -> IndexBuilder<'p> {
+        IndexBuilder {
+            page,
+            search_entries: Vec::new(),
+            tag_links: BTreeMap::new(),
+        }
+    }
+
+    fn url(&self, id: Option<&str>) -> String {
+        match id {
+            Some(id) => format!("{}#{}", self.page, node_anchor(id)),
+            None => self.page.to_string(),
+        }
+    }
+
+    fn add_search_entry(
+        &mut self,
+        keyword: &str,
+        title: &str,
+        id: Option<&str>,
+        context: &Context<'_>,
+        text: String,
+    ) {
+        let feature = context
+            .feature
+            .map_or(title, |feature| feature.name.as_str());
+        self.search_entries.push(SearchEntry {
+            keyword: keyword.to_string(),
+            title: title.to_string(),
+            feature: feature.to_string(),
+            url: self.url(id),
+            text,
+        });
+    }
+}
+
+impl<'a> Visitor<'a> for IndexBuilder<'_> {
+    fn visit_feature(&mut self, feature: &'a ast::Feature, context: &Context<'a>) {
+        let text = searchable_text(&feature.tags, &feature.description, &[]);
+        self.add_search_entry(&feature.keyword, &feature.name, None, context, text);
+        visit::walk_feature(self, feature, context);
+    }
+
+    fn visit_rule(&mut self, rule: &'a ast::Rule, context: &Context<'a>) {
+        let text = searchable_text(&[], &rule.description, &[]);
+        self.add_search_entry(&rule.keyword, &rule.name, Some(&rule.id), context, text);
+        visit::walk_rule(self, rule, context);
+    }
+
+    fn visit_background(&mut self, background: &'a ast::Background, context: &Context<'a>) {
+        let text = searchable_text(&[], &background.description, &background.steps);
+        let (keyword, title, id) = (&background.keyword, &background.name, &background.id);
+        self.add_search_entry(keyword, title, Some(id), context, text);
+    }
+
+    fn visit_scenario(&mut self, scenario: &'a ast::Scenario, context: &Context<'a>) {
+        let text = searchable_text(&scenario.tags, &scenario.description, &scenario.steps);
+        let (keyword, title, id) = (&scenario.keyword, &scenario.name, &scenario.id);
+        self.add_search_entry(keyword, title, Some(id), context, text);
+        visit::walk_scenario(self, scenario, context);
+    }
+
+    fn visit_tag(&mut self, tag: &'a ast::Tag, context: &Context<'a>) {
+        let feature_name = context.feature.map_or("", |feature| feature.name.as_str());
+        let link = match (context.scenario, context.examples) {
+            (Some(scenario), examples) => {
+                let id = examples.map_or(&scenario.id, |examples| &examples.id);
+                TagLink {
+                    url: self.url(Some(id)),
+                    title: format!("{} › {}", feature_name, scenario.name),
+                }
+            }
+            (None, _) => TagLink {
+                url: self.url(None),
+                title: feature_name.to_string(),
+            },
+        };
+
+        self.tag_links
+            .entry(tag.name.clone())
+            .or_insert_with(Vec::new)
+            .push(link);
+    }
+}
+
+fn searchable_text(tags: &[ast::Tag], description: &str, steps: &[ast::Step]) -> String {
+    let tags = tags.iter().map(|tag| tag.name.as_str());
+    let description = Some(description.trim()).filter(|description| !description.is_empty());
+    let steps = steps.iter().map(|step| step.text.as_str());
+
+    tags.chain(description)
+        .chain(steps)
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
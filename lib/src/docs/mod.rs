@@ -0,0 +1,380 @@
+//! Rendering of `GherkinDocument`s into a static HTML site.
+//!
+//! The site consists of an index grouping the features by folder and tag, one page per feature
+//! and a client-side search index. It references no external assets, so it can be browsed
+//! offline straight from the file system.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use cucumber_messages::ast;
+
+use crate::constant;
+use crate::docs::feature::render_feature_page;
+use crate::docs::html::{escape, slug};
+use crate::docs::index::{IndexBuilder, SearchEntry, TagLink};
+use crate::uri::folder;
+use crate::visit::Visitor;
+
+mod feature;
+mod html;
+mod index;
+
+const STYLE: &str = include_str!("style.css");
+const SEARCH: &str = include_str!("search.js");
+const FEATURES_DIRECTORY: &str = "features";
+
+/// A file of the site.
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// The path relative to the site root, with `/` as separator.
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Site {
+    pages: Vec<Page>,
+}
+
+/// A feature listed in the index.
+struct FeatureSummary<'d> {
+    folder: String,
+    feature: &'d ast::Feature,
+    page: String,
+}
+
+impl Site {
+    /// Renders the documents with a feature, ordered by their uri.
+    pub fn render<'d, D>(gherkin_documents: D) -> Site
+    where
+        D: IntoIterator<Item = &'d ast::GherkinDocument>,
+    {
+        let mut gherkin_documents = gherkin_documents
+            .into_iter()
+            .filter(|gherkin_document| gherkin_document.feature.is_some())
+            .collect::<Vec<&ast::GherkinDocument>>();
+        gherkin_documents.sort_by(|a, b| a.uri.cmp(&b.uri));
+
+        let mut pages = Vec::new();
+        let mut page_paths = HashSet::new();
+        let mut summaries = Vec::new();
+        let mut search_entries = Vec::new();
+        let mut tag_links = BTreeMap::<String, Vec<TagLink>>::new();
+
+        for gherkin_document in gherkin_documents {
+            let feature = gherkin_document.feature.as_ref().unwrap();
+            let page = unique_page_path(&gherkin_document.uri, &mut page_paths);
+
+            let mut index_builder = IndexBuilder::new(&page);
+            index_builder.visit_gherkin_document(gherkin_document);
+            search_entries.extend(index_builder.search_entries);
+            for (tag, links) in index_builder.tag_links {
+                tag_links.entry(tag).or_insert_with(Vec::new).extend(links);
+            }
+
+            pages.push(Page {
+                path: page.clone(),
+                content: render_feature_page(gherkin_document, feature),
+            });
+            summaries.push(FeatureSummary {
+                folder: folder(&gherkin_document.uri),
+                feature,
+                page,
+            });
+        }
+
+        pages.push(Page {
+            path: String::from("index.html"),
+            content: render_index_page(&summaries, &tag_links),
+        });
+        pages.push(Page {
+            path: String::from("search-index.js"),
+            content: render_search_index(&search_entries),
+        });
+        pages.push(Page {
+            path: String::from("search.js"),
+            content: SEARCH.to_string(),
+        });
+        pages.push(Page {
+            path: String::from("style.css"),
+            content: STYLE.to_string(),
+        });
+
+        Site { pages }
+    }
+
+    pub fn pages(&self) -> &[Page] {
+        &self.pages
+    }
+
+    pub fn page(&self, path: &str) -> Option<&Page> {
+        self.pages.iter().find(|page| page.path == path)
+    }
+
+    /// Writes the pages into a directory, which is created if it does not exist.
+    pub fn write_to<P: AsRef<Path>>(&self, directory: P) -> io::Result<()> {
+        let directory = directory.as_ref();
+        fs::create_dir_all(directory.join(FEATURES_DIRECTORY))?;
+
+        for page in &self.pages {
+            fs::write(directory.join(&page.path), &page.content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the anchor of an AST node on the page of its feature.
+fn node_anchor(id: &str) -> String {
+    format!("node-{}", slug(id))
+}
+
+/// Returns the anchor of a tag on the index page.
+///
+/// Unlike a slug, it is distinct for every tag: ASCII letters, digits and `-` are kept
+/// and every other char is escaped as `_<hex code>_`.
+fn tag_anchor(name: &str) -> String {
+    let name = if name.starts_with(constant::TAG_PREFIX) {
+        &name[constant::TAG_PREFIX.len()..]
+    } else {
+        name
+    };
+
+    let mut anchor = String::from("tag-");
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            anchor.push(c);
+        } else {
+            write!(anchor, "_{:x}_", c as u32).expect("write to string");
+        }
+    }
+    anchor
+}
+
+fn unique_page_path(uri: &str, page_paths: &mut HashSet<String>) -> String {
+    let mut name = slug(uri);
+    if name.is_empty() {
+        name = String::from("feature");
+    }
+
+    let mut page = format!("{}/{}.html", FEATURES_DIRECTORY, name);
+    let mut suffix = 1;
+    while !page_paths.insert(page.clone()) {
+        suffix += 1;
+        page = format!("{}/{}-{}.html", FEATURES_DIRECTORY, name, suffix);
+    }
+    page
+}
+
+fn render_index_page(
+    summaries: &[FeatureSummary<'_>],
+    tag_links: &BTreeMap<String, Vec<TagLink>>,
+) -> String {
+    let mut html = String::new();
+    write_index_page(&mut html, summaries, tag_links).expect("write to string");
+    html
+}
+
+fn write_index_page(
+    html: &mut String,
+    summaries: &[FeatureSummary<'_>],
+    tag_links: &BTreeMap<String, Vec<TagLink>>,
+) -> fmt::Result {
+    page_head(html, "Features", "en", "")?;
+    html.push_str(
+        "<h1>Features</h1>\n\
+         <input id=\"search\" type=\"search\" placeholder=\"Search features and scenarios\">\n\
+         <ul id=\"search-results\"></ul>\n",
+    );
+
+    let mut folders = BTreeMap::<&str, Vec<&FeatureSummary<'_>>>::new();
+    for summary in summaries {
+        folders
+            .entry(summary.folder.as_str())
+            .or_insert_with(Vec::new)
+            .push(summary);
+    }
+
+    html.push_str("<section class=\"folders\">\n<h2>Folders</h2>\n");
+    for (folder, summaries) in folders {
+        writeln!(html, "<h3 class=\"folder\">{}</h3>\n<ul>", escape(folder))?;
+        for summary in summaries {
+            writeln!(
+                html,
+                "<li><a href=\"{}\">{}</a></li>",
+                escape(&summary.page),
+                escape(&summary.feature.name),
+            )?;
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</section>\n");
+
+    if !tag_links.is_empty() {
+        html.push_str("<section class=\"tags\">\n<h2>Tags</h2>\n");
+        for (tag, links) in tag_links {
+            writeln!(
+                html,
+                "<h3 class=\"tag\" id=\"{}\">{}</h3>\n<ul>",
+                tag_anchor(tag),
+                escape(tag),
+            )?;
+            for link in links {
+                writeln!(
+                    html,
+                    "<li><a href=\"{}\">{}</a></li>",
+                    escape(&link.url),
+                    escape(&link.title),
+                )?;
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</section>\n");
+    }
+
+    html.push_str(
+        "<script src=\"search-index.js\"></script>\n<script src=\"search.js\"></script>\n",
+    );
+    page_tail(html)
+}
+
+/// Renders the search index as script, as browsers do not load JSON from the file system.
+fn render_search_index(search_entries: &[SearchEntry]) -> String {
+    let json = serde_json::to_string(search_entries).expect("serialize search index");
+    // line and paragraph separators are valid in JSON strings, but not in older JavaScript
+    let json = json
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029");
+    format!("var SEARCH_INDEX = {};\n", json)
+}
+
+fn page_head(html: &mut String, title: &str, language: &str, root: &str) -> fmt::Result {
+    write!(
+        html,
+        "<!DOCTYPE html>\n\
+         <html lang=\"{}\">\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n\
+         <link rel=\"stylesheet\" href=\"{}style.css\">\n\
+         </head>\n\
+         <body>\n",
+        escape(language),
+        escape(title),
+        root,
+    )
+}
+
+fn page_tail(html: &mut String) -> fmt::Result {
+    html.push_str("</body>\n</html>\n");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::parse_document;
+
+    use super::*;
+
+    #[test]
+    fn renders_index_grouped_by_folder_and_tag() {
+        let gherkin_documents = vec![
+            parse_document(
+                "features/eating/cukes.feature",
+                "@food\nFeature: Eating\n  @slow\n  Scenario: many cukes\n    Given a cuke\n",
+            ),
+            parse_document(
+                "features/basket.feature",
+                "Feature: Basket <b>\n  Scenario: empty\n    Given a basket\n",
+            ),
+        ];
+
+        let site = Site::render(&gherkin_documents);
+
+        let paths = site
+            .pages()
+            .iter()
+            .map(|page| page.path.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            paths,
+            vec![
+                "features/features-basket-feature.html",
+                "features/features-eating-cukes-feature.html",
+                "index.html",
+                "search-index.js",
+                "search.js",
+                "style.css",
+            ]
+        );
+
+        let index = &site.page("index.html").unwrap().content;
+        assert!(index.contains("<h3 class=\"folder\">features</h3>"));
+        assert!(index.contains("<h3 class=\"folder\">features/eating</h3>"));
+        assert!(index.contains("Basket &lt;b&gt;</a>"));
+        assert!(index.contains("<h3 class=\"tag\" id=\"tag-slow\">@slow</h3>"));
+        assert!(index.contains(
+            "<a href=\"features/features-eating-cukes-feature.html#node-2\">Eating › many cukes</a>"
+        ));
+
+        let search_index = &site.page("search-index.js").unwrap().content;
+        assert!(search_index.starts_with("var SEARCH_INDEX = [{"));
+        assert!(search_index.contains("\"text\":\"@slow\\na cuke\""));
+    }
+
+    #[test]
+    fn escapes_tag_anchors() {
+        let tags = ["@a_b", "@a-b", "@A-b", "@日本", "@中文", "@a b"];
+        let anchors = tags.iter().map(|tag| tag_anchor(tag)).collect::<Vec<_>>();
+
+        assert_eq!(
+            anchors,
+            vec![
+                "tag-a_5f_b",
+                "tag-a-b",
+                "tag-A-b",
+                "tag-_65e5__672c_",
+                "tag-_4e2d__6587_",
+                "tag-a_20_b",
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_feature_page() {
+        let source = r#"
+Feature: Cukes
+  Rule: Eating
+    Scenario Outline: eating <count> cukes
+      Given I eat <count> cukes
+      And the note:
+        ```markdown
+        *yummy*
+        ```
+
+      Examples: amounts
+        | count |
+        | 5     |
+"#;
+        let gherkin_document = parse_document("cukes.feature", source);
+
+        let site = Site::render(vec![&gherkin_document]);
+        let page = &site.page("features/cukes-feature.html").unwrap().content;
+
+        assert!(page.contains("<link rel=\"stylesheet\" href=\"../style.css\">"));
+        assert!(page.contains("<h2><span class=\"keyword\">Rule:</span> Eating</h2>"));
+        assert!(page.contains(
+            "<li><span class=\"keyword\">Given </span>I eat <var class=\"placeholder\">&lt;count&gt;</var> cukes</li>"
+        ));
+        assert!(page.contains(
+            "<figcaption class=\"media-type\">markdown</figcaption>\n<pre><code data-media-type=\"markdown\">*yummy*</code></pre>"
+        ));
+        assert!(page.contains("<h4><span class=\"keyword\">Examples:</span> amounts</h4>"));
+        assert!(page
+            .contains("<thead>\n<tr><th>count</th></tr>\n</thead>\n<tbody>\n<tr><td>5</td></tr>"));
+    }
+}
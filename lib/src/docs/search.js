@@ -0,0 +1,61 @@
+(function () {
+  "use strict";
+
+  var input = document.getElementById("search");
+  var results = document.getElementById("search-results");
+  if (!input || !results || typeof SEARCH_INDEX === "undefined") {
+    return;
+  }
+
+  var entries = SEARCH_INDEX.map(function (entry) {
+    return {
+      entry: entry,
+      text: (entry.title + "\n" + entry.text).toLowerCase()
+    };
+  });
+
+  function clearResults() {
+    while (results.firstChild) {
+      results.removeChild(results.firstChild);
+    }
+  }
+
+  function addResult(entry) {
+    var item = document.createElement("li");
+    var link = document.createElement("a");
+    link.href = entry.url;
+    link.textContent = entry.keyword + ": " + entry.title;
+    item.appendChild(link);
+
+    if (entry.feature !== entry.title) {
+      var feature = document.createElement("span");
+      feature.className = "search-feature";
+      feature.textContent = " — " + entry.feature;
+      item.appendChild(feature);
+    }
+
+    results.appendChild(item);
+  }
+
+  input.addEventListener("input", function () {
+    var words = input.value.toLowerCase().split(/\s+/).filter(function (word) {
+      return word.length > 0;
+    });
+
+    clearResults();
+    if (words.length === 0) {
+      return;
+    }
+
+    entries
+      .filter(function (candidate) {
+        return words.every(function (word) {
+          return candidate.text.indexOf(word) !== -1;
+        });
+      })
+      .slice(0, 50)
+      .forEach(function (candidate) {
+        addResult(candidate.entry);
+      });
+  });
+})();
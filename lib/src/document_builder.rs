@@ -1,10 +1,10 @@
-use std::any::Any;
 use std::mem;
 
 use cucumber_messages::ast::*;
 use cucumber_messages::id_generator::IdGenerator;
 
-use crate::ast_node::AstNode;
+use crate::ast_node::{AstItem, AstNode};
+use crate::dialect::StepKeywordTypes;
 use crate::error::{Error, Result};
 use crate::parser::{self, Builder, RuleType, TokenType};
 use crate::token::Token;
@@ -13,6 +13,9 @@ pub struct DocumentBuilder<'id_gen> {
     id_generator: &'id_gen mut dyn IdGenerator,
     stack: Vec<AstNode>,
     comments: Vec<Comment>,
+    step_keyword_types: StepKeywordTypes,
+    /// The location of the last token, where errors of missing items are reported.
+    location: crate::Location,
 }
 
 impl<'id_gen> DocumentBuilder<'id_gen> {
@@ -23,6 +26,8 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
             id_generator,
             stack: Vec::new(),
             comments: Vec::new(),
+            step_keyword_types: StepKeywordTypes::new(),
+            location: crate::Location::new(0, 0),
         };
         builder.reset();
         builder
@@ -31,26 +36,36 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
     pub fn id_generator_mut(&mut self) -> &mut dyn IdGenerator {
         self.id_generator
     }
+
+    /// Returns the keyword types of the steps of the last built document by the step id.
+    ///
+    /// `ast::Step` has no field for the keyword type, so it is recorded here from the token
+    /// of the step line and needs to be passed to `Compiler::compile_with`.
+    pub fn step_keyword_types(&self) -> &StepKeywordTypes {
+        &self.step_keyword_types
+    }
+
+    pub fn take_step_keyword_types(&mut self) -> StepKeywordTypes {
+        mem::replace(&mut self.step_keyword_types, StepKeywordTypes::new())
+    }
 }
 
 impl<'id_gen> parser::Builder for DocumentBuilder<'id_gen> {
     type BuilderResult = GherkinDocument;
 
-    fn build(&mut self, token: Token) -> Result<()> {
-        let (rule_type, is_comment) = {
-            let token_type = token.matched_type.unwrap();
-            let rule_type = RuleType::from(token_type);
-            let is_comment = token_type == TokenType::Comment;
-            (rule_type, is_comment)
-        };
+    fn build(&mut self, token: Token<'_>) -> Result<()> {
+        if let Some(location) = token.location {
+            self.location = location;
+        }
 
-        if is_comment {
+        if token.matched_type == Some(TokenType::Comment) {
             let location = self.get_location(&token, 0);
-            let text = token.matched_text.as_ref().unwrap().clone();
+            let text = token.matched_text.unwrap_or_default().into_owned();
             let comment = Comment { location, text };
             self.comments.push(comment);
         } else {
-            self.current_node().add(rule_type, Box::new(token));
+            let item = AstItem::Token(token.into_owned());
+            self.current_node().add(item);
         }
 
         Ok(())
@@ -62,17 +77,17 @@ impl<'id_gen> parser::Builder for DocumentBuilder<'id_gen> {
     }
 
     fn end_rule(&mut self, _rule_type: RuleType) -> Result<()> {
-        let node = self.stack.pop().unwrap();
-        let rule_type = node.rule_type();
+        let node = self.stack.pop().expect("current node on AstBuilder stack");
 
         let transformed_node = self.get_transformed_node(node)?;
-        self.current_node().add(rule_type, transformed_node);
+        self.current_node().add(transformed_node);
 
         Ok(())
     }
 
-    fn get_result(&mut self) -> GherkinDocument {
-        self.current_node().remove(RuleType::GherkinDocument)
+    fn get_result(&mut self) -> Result<GherkinDocument> {
+        let gherkin_document = self.current_node().remove_opt();
+        self.missing(gherkin_document, "GherkinDocument")
     }
 
     fn reset(&mut self) {
@@ -80,6 +95,8 @@ impl<'id_gen> parser::Builder for DocumentBuilder<'id_gen> {
         self.stack.push(AstNode::new(RuleType::None));
 
         self.comments.clear();
+        self.step_keyword_types.clear();
+        self.location = crate::Location::new(0, 0);
     }
 }
 
@@ -90,8 +107,8 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
             .expect("current node on AstBuilder stack")
     }
 
-    fn get_location(&self, token: &Token, column: u32) -> Option<Location> {
-        let token_location = token.location.expect("token location");
+    fn get_location(&self, token: &Token<'_>, column: u32) -> Option<Location> {
+        let token_location = token.location?;
 
         let location = if column == 0 {
             Location {
@@ -108,18 +125,17 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
         Some(location)
     }
 
-    fn get_transformed_node(&mut self, mut node: AstNode) -> Result<Box<dyn Any>> {
+    fn get_transformed_node(&mut self, mut node: AstNode) -> Result<AstItem> {
         match node.rule_type() {
             RuleType::Step => {
-                let step_line: Token = node.remove_token(TokenType::StepLine);
+                let step_line = self.remove_token(&mut node, TokenType::StepLine)?;
 
                 let argument: Option<Argument> = {
-                    let data_table: Option<DataTable> = node.remove_opt(RuleType::DataTable);
+                    let data_table: Option<DataTable> = node.remove_opt();
                     match data_table {
                         Some(data_table) => Some(Argument::DataTable(data_table)),
                         None => {
-                            let doc_string: Option<DocString> =
-                                node.remove_opt(RuleType::DocString);
+                            let doc_string: Option<DocString> = node.remove_opt();
                             match doc_string {
                                 Some(doc_string) => Some(Argument::DocString(doc_string)),
                                 None => None,
@@ -129,9 +145,11 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                 };
 
                 let id = self.id_generator.new_id();
+                if let Some(keyword_type) = step_line.matched_keyword_type {
+                    self.step_keyword_types.insert(id.clone(), keyword_type);
+                }
                 let location = self.get_location(&step_line, 0);
-                let keyword = step_line.matched_keyword.as_ref().unwrap().to_owned();
-                let text = step_line.matched_text.as_ref().unwrap().to_owned();
+                let (keyword, text) = self.get_keyword_and_text(step_line)?;
 
                 let step = Step {
                     id,
@@ -140,12 +158,12 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     text,
                     argument,
                 };
-                Ok(Box::new(step))
+                Ok(AstItem::Step(step))
             }
             RuleType::DocString => {
-                let mut separator_tokens = node.remove_tokens(TokenType::DocStringSeparator);
-                let separator_token = separator_tokens.remove(0);
-                let separator_token_text = separator_token.matched_text.as_ref().unwrap();
+                let separator_token =
+                    self.remove_token(&mut node, TokenType::DocStringSeparator)?;
+                let separator_token_text = separator_token.matched_text.as_deref().unwrap_or("");
                 let media_type = if separator_token_text.chars().count() > 0 {
                     separator_token_text.to_owned()
                 } else {
@@ -154,11 +172,14 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                 let content = node
                     .remove_tokens(TokenType::Other)
                     .into_iter()
-                    .map(|line_token| line_token.matched_text.as_ref().unwrap().to_owned())
-                    .collect::<Vec<String>>()
+                    .map(|line_token| line_token.matched_text.unwrap_or_default())
+                    .collect::<Vec<_>>()
                     .join("\n");
                 let location = self.get_location(&separator_token, 0);
-                let delimiter = separator_token.matched_keyword.unwrap_or_default();
+                let delimiter = separator_token
+                    .matched_keyword
+                    .unwrap_or_default()
+                    .into_owned();
 
                 let doc_string = DocString {
                     location,
@@ -166,22 +187,21 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     content,
                     delimiter,
                 };
-                Ok(Box::new(doc_string))
+                Ok(AstItem::DocString(doc_string))
             }
             RuleType::DataTable => {
                 let rows = self.get_table_rows(node)?;
-                let location = rows[0].location;
+                let location = rows.first().and_then(|row| row.location);
 
-                Ok(Box::new(DataTable { location, rows }))
+                Ok(AstItem::DataTable(DataTable { location, rows }))
             }
             RuleType::Background => {
-                let background_line: Token = node.remove_token(TokenType::BackgroundLine);
+                let background_line = self.remove_token(&mut node, TokenType::BackgroundLine)?;
 
                 let description = self.get_description(&mut node);
                 let steps = self.get_steps(&mut node);
                 let location = self.get_location(&background_line, 0);
-                let keyword = background_line.matched_keyword.as_ref().unwrap().to_owned();
-                let name = background_line.matched_text.as_ref().unwrap().to_owned();
+                let (keyword, name) = self.get_keyword_and_text(background_line)?;
                 let id = self.id_generator.new_id();
 
                 let background = Background {
@@ -192,20 +212,20 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     description,
                     steps,
                 };
-                Ok(Box::new(background))
+                Ok(AstItem::Background(background))
             }
             RuleType::ScenarioDefinition => {
                 let tags = self.get_tags(&mut node);
-                let mut scenario_node = node.remove::<AstNode>(RuleType::Scenario);
-                let scenario_line = scenario_node.remove_token(TokenType::ScenarioLine);
+                let mut scenario_node = self.remove_node(&mut node, RuleType::Scenario)?;
+                let scenario_line =
+                    self.remove_token(&mut scenario_node, TokenType::ScenarioLine)?;
 
                 let id = self.id_generator.new_id();
                 let location = self.get_location(&scenario_line, 0);
-                let keyword = scenario_line.matched_keyword.as_ref().unwrap().to_owned();
-                let name = scenario_line.matched_text.as_ref().unwrap().to_owned();
+                let (keyword, name) = self.get_keyword_and_text(scenario_line)?;
                 let description = self.get_description(&mut scenario_node);
                 let steps = self.get_steps(&mut scenario_node);
-                let examples = scenario_node.remove_items(RuleType::ExamplesDefinition);
+                let examples = scenario_node.remove_items::<Examples>();
 
                 let scenario = Scenario {
                     id,
@@ -218,14 +238,15 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     examples,
                 };
 
-                Ok(Box::new(scenario))
+                Ok(AstItem::Scenario(scenario))
             }
             RuleType::ExamplesDefinition => {
                 let tags = self.get_tags(&mut node);
-                let mut examples_node: AstNode = node.remove(RuleType::Examples);
-                let examples_line = examples_node.remove_token(TokenType::ExamplesLine);
+                let mut examples_node = self.remove_node(&mut node, RuleType::Examples)?;
+                let examples_line =
+                    self.remove_token(&mut examples_node, TokenType::ExamplesLine)?;
                 let description = self.get_description(&mut examples_node);
-                let rows: Option<Vec<TableRow>> = examples_node.remove_opt(RuleType::ExamplesTable);
+                let rows: Option<Vec<TableRow>> = examples_node.remove_opt();
                 let (table_header, table_body) = match rows {
                     Some(mut rows) => {
                         if rows.is_empty() {
@@ -238,8 +259,7 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     None => (None, Vec::new()),
                 };
                 let location = self.get_location(&examples_line, 0);
-                let keyword = examples_line.matched_keyword.as_ref().unwrap().to_owned();
-                let name = examples_line.matched_text.as_ref().unwrap().to_owned();
+                let (keyword, name) = self.get_keyword_and_text(examples_line)?;
                 let id = self.id_generator.new_id();
 
                 let examples = Examples {
@@ -252,11 +272,11 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     table_header,
                     table_body,
                 };
-                Ok(Box::new(examples))
+                Ok(AstItem::Examples(examples))
             }
             RuleType::ExamplesTable => {
                 let rows = self.get_table_rows(node)?;
-                Ok(Box::new(rows))
+                Ok(AstItem::TableRows(rows))
             }
             RuleType::Description => {
                 let line_tokens = node.remove_tokens(TokenType::Other);
@@ -265,8 +285,8 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                 while end > 0
                     && line_tokens[end - 1]
                         .matched_text
-                        .as_ref()
-                        .unwrap()
+                        .as_deref()
+                        .unwrap_or("")
                         .chars()
                         .all(|c| c.is_whitespace())
                 {
@@ -277,23 +297,23 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
 
                 let description = line_tokens
                     .iter()
-                    .map(|token| token.matched_text.as_ref().unwrap().to_owned())
-                    .collect::<Vec<String>>()
+                    .map(|token| token.matched_text.as_deref().unwrap_or(""))
+                    .collect::<Vec<&str>>()
                     .join("\n");
 
-                Ok(Box::new(description))
+                Ok(AstItem::Description(description))
             }
             RuleType::Rule => {
-                let mut header_node =
-                    node.remove_or(RuleType::RuleHeader, AstNode::new(RuleType::RuleHeader));
-                let rule_line = header_node.remove_token(TokenType::RuleLine);
+                let mut header_node = node
+                    .remove_node_opt(RuleType::RuleHeader)
+                    .unwrap_or_else(|| AstNode::new(RuleType::RuleHeader));
+                let rule_line = self.remove_token(&mut header_node, TokenType::RuleLine)?;
 
                 let location = self.get_location(&rule_line, 0);
-                let keyword = rule_line.matched_keyword.as_ref().unwrap().to_owned();
-                let name = rule_line.matched_text.as_ref().unwrap().to_owned();
+                let (keyword, name) = self.get_keyword_and_text(rule_line)?;
                 let description = self.get_description(&mut header_node);
-                let background = node.remove_opt::<Background>(RuleType::Background);
-                let scenarios = node.remove_items::<Scenario>(RuleType::ScenarioDefinition);
+                let background = node.remove_opt::<Background>();
+                let scenarios = node.remove_items::<Scenario>();
 
                 let children_capacity = if background.is_some() { 1 } else { 0 } + scenarios.len();
                 let mut children = Vec::with_capacity(children_capacity);
@@ -318,16 +338,17 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     description,
                     children,
                 };
-                Ok(Box::new(rule))
+                Ok(AstItem::Rule(rule))
             }
             RuleType::Feature => {
-                let mut feature_header = node.remove(RuleType::FeatureHeader);
+                let mut feature_header = self.remove_node(&mut node, RuleType::FeatureHeader)?;
                 let tags = self.get_tags(&mut feature_header);
-                let feature_line = feature_header.remove_token(TokenType::FeatureLine);
+                let feature_line =
+                    self.remove_token(&mut feature_header, TokenType::FeatureLine)?;
 
-                let background = node.remove_opt::<Background>(RuleType::Background);
-                let scenarios = node.remove_items::<Scenario>(RuleType::ScenarioDefinition);
-                let rules = node.remove_items::<Rule>(RuleType::Rule);
+                let background = node.remove_opt::<Background>();
+                let scenarios = node.remove_items::<Scenario>();
+                let rules = node.remove_items::<Rule>();
 
                 let children_capacity =
                     if background.is_some() { 1 } else { 0 } + scenarios.len() + rules.len();
@@ -350,14 +371,9 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                 }
 
                 let location = self.get_location(&feature_line, 0);
-                let language = feature_line
-                    .matched_dialect
-                    .as_ref()
-                    .unwrap()
-                    .get_language()
-                    .to_owned();
-                let keyword = feature_line.matched_keyword.as_ref().unwrap().to_owned();
-                let name = feature_line.matched_text.as_ref().unwrap().to_owned();
+                let dialect = self.missing(feature_line.matched_dialect.as_ref(), "dialect")?;
+                let language = dialect.get_language().to_owned();
+                let (keyword, name) = self.get_keyword_and_text(feature_line)?;
                 let description = self.get_description(&mut feature_header);
 
                 let feature = Feature {
@@ -369,11 +385,11 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     description,
                     children,
                 };
-                Ok(Box::new(feature))
+                Ok(AstItem::Feature(feature))
             }
             RuleType::GherkinDocument => {
                 let uri = String::new();
-                let feature: Option<Feature> = node.remove_opt(RuleType::Feature);
+                let feature: Option<Feature> = node.remove_opt();
                 let comments = mem::replace(&mut self.comments, Vec::new());
 
                 let gherkin_document = GherkinDocument {
@@ -381,9 +397,9 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
                     feature,
                     comments,
                 };
-                Ok(Box::new(gherkin_document))
+                Ok(AstItem::GherkinDocument(gherkin_document))
             }
-            _ => Ok(Box::new(node)),
+            _ => Ok(AstItem::Node(node)),
         }
     }
 
@@ -391,10 +407,10 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
         let rows: Vec<TableRow> = node
             .remove_tokens(TokenType::TableRow)
             .into_iter()
-            .map(|token| {
+            .map(|mut token| {
                 let id = self.id_generator.new_id();
                 let location = self.get_location(&token, 0);
-                let cells = self.get_cells(&token);
+                let cells = self.get_cells(&mut token);
                 TableRow {
                     id,
                     location,
@@ -436,31 +452,56 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
         Ok(())
     }
 
-    fn get_cells(&self, token: &Token) -> Vec<TableCell> {
-        token
-            .matched_items
-            .iter()
+    fn get_cells(&self, token: &mut Token<'_>) -> Vec<TableCell> {
+        let cell_items = mem::replace(&mut token.matched_items, Vec::new());
+        cell_items
+            .into_iter()
             .map(|cell_item| {
-                let location = self.get_location(&token, cell_item.column);
-                let value = cell_item.text.to_owned();
+                let location = self.get_location(token, cell_item.column);
+                let value = cell_item.text.into_owned();
                 TableCell { location, value }
             })
             .collect()
     }
 
+    /// Returns an error for an item which the parser did not add,
+    /// e.g. because a `TokenMatch` did not match all parts of a line.
+    fn missing<T>(&self, item: Option<T>, name: &str) -> Result<T> {
+        item.ok_or_else(|| Error::DocumentBuilder {
+            location: self.location,
+            message: format!("could not find item for {}", name),
+        })
+    }
+
+    fn remove_node(&self, node: &mut AstNode, rule_type: RuleType) -> Result<AstNode> {
+        let child_node = node.remove_node_opt(rule_type);
+        self.missing(child_node, &format!("RuleType::{}", rule_type))
+    }
+
+    fn remove_token(&self, node: &mut AstNode, token_type: TokenType) -> Result<Token<'static>> {
+        let token = node.remove_token_opt(token_type);
+        self.missing(token, &format!("TokenType::{}", token_type))
+    }
+
+    fn get_keyword_and_text(&self, token: Token<'_>) -> Result<(String, String)> {
+        let keyword = self.missing(token.matched_keyword, "keyword")?;
+        let text = self.missing(token.matched_text, "text")?;
+        Ok((keyword.into_owned(), text.into_owned()))
+    }
+
     fn get_steps(&self, node: &mut AstNode) -> Vec<Step> {
-        node.remove_items(RuleType::Step)
+        node.remove_items()
     }
 
     fn get_description(&self, node: &mut AstNode) -> String {
-        node.remove_or(RuleType::Description, String::new())
+        node.remove_opt().unwrap_or_default()
     }
 
     fn get_tags(&mut self, node: &mut AstNode) -> Vec<Tag> {
-        let default_tags_node = AstNode::new(RuleType::None);
-        let mut tags_node = node.remove_or(RuleType::Tags, default_tags_node);
-
-        let mut tokens = tags_node.remove_tokens(TokenType::TagLine);
+        let mut tokens = match node.remove_node_opt(RuleType::Tags) {
+            Some(mut tags_node) => tags_node.remove_tokens(TokenType::TagLine),
+            None => Vec::new(),
+        };
 
         let mut tags = Vec::new();
         for token in tokens.iter_mut() {
@@ -468,7 +509,7 @@ impl<'id_gen> DocumentBuilder<'id_gen> {
             for tag_item in tag_items {
                 let id = self.id_generator.new_id();
                 let location = self.get_location(&token, tag_item.column);
-                let name = tag_item.text;
+                let name = tag_item.text.into_owned();
                 tags.push(Tag { id, location, name });
             }
         }
@@ -0,0 +1,440 @@
+//! Detection of duplicated steps and scenarios in compiled cukes.
+//!
+//! Step texts are normalized, so steps which only differ in numbers, quoted strings or
+//! placeholders are considered the same. Scenarios are compared by the normalized texts of their
+//! own steps, backgrounds are left out as they are shared on purpose.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::cuke::{Cuke, Location, Step};
+
+lazy_static! {
+    static ref QUOTED_PATTERN: Regex = Regex::new(r#""[^"]*"|\B'[^']*'\B"#).unwrap();
+    static ref PLACEHOLDER_PATTERN: Regex = Regex::new(r"<[^<>]+>").unwrap();
+    static ref NUMBER_PATTERN: Regex = Regex::new(r"-?\b\d+(?:\.\d+)?\b").unwrap();
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct DuplicateOptions {
+    /// The minimum similarity of the step sequences of two scenarios to be near-duplicates,
+    /// between 0 (anything) and 1 (only identical sequences).
+    pub threshold: f64,
+    /// Scenarios with fewer steps are not compared.
+    pub min_steps: usize,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        DuplicateOptions {
+            threshold: 0.8,
+            min_steps: 2,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Duplicates {
+    /// Steps with different texts which are the same after normalization.
+    pub steps: Vec<StepCluster>,
+    /// Scenarios with identical or similar step sequences.
+    pub scenarios: Vec<ScenarioCluster>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepCluster {
+    pub normalized_text: String,
+    pub occurrences: Vec<StepOccurrence>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepOccurrence {
+    pub uri: String,
+    pub location: Location,
+    pub text: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioCluster {
+    /// Whether the normalized step sequences of all scenarios are equal.
+    pub identical: bool,
+    /// The lowest similarity of two scenarios of the cluster.
+    pub similarity: f64,
+    pub scenarios: Vec<ScenarioOccurrence>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioOccurrence {
+    pub uri: String,
+    pub location: Location,
+    pub name: String,
+    pub steps: Vec<StepOccurrence>,
+}
+
+/// Returns the text of a step with quoted strings, placeholders and numbers replaced
+/// by `{string}`, `{placeholder}` and `{number}`, whitespace collapsed and in lowercase.
+pub fn normalize_step_text(text: &str) -> String {
+    let text = QUOTED_PATTERN.replace_all(text, "{string}");
+    let text = PLACEHOLDER_PATTERN.replace_all(&text, "{placeholder}");
+    let text = NUMBER_PATTERN.replace_all(&text, "{number}");
+    text.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Finds the duplicated steps and scenarios of cukes, which may be compiled from many documents.
+///
+/// The cukes of a scenario outline are compared as one scenario, using the first example.
+pub fn find_duplicates(cukes: &[Cuke<'_>], options: &DuplicateOptions) -> Duplicates {
+    Duplicates {
+        steps: find_step_clusters(cukes),
+        scenarios: find_scenario_clusters(cukes, options),
+    }
+}
+
+fn find_step_clusters(cukes: &[Cuke<'_>]) -> Vec<StepCluster> {
+    let mut seen_steps = HashSet::new();
+    let mut occurrences = HashMap::<String, Vec<StepOccurrence>>::new();
+
+    for cuke in cukes {
+        for step in cuke.steps() {
+            let occurrence = step_occurrence(cuke, step);
+            // the steps of backgrounds and outlines are part of many cukes
+            if !seen_steps.insert((occurrence.uri.clone(), occurrence.location)) {
+                continue;
+            }
+            occurrences
+                .entry(normalize_step_text(&step.text))
+                .or_insert_with(Vec::new)
+                .push(occurrence);
+        }
+    }
+
+    let mut clusters = occurrences
+        .into_iter()
+        .filter(|(_normalized_text, occurrences)| {
+            let texts = occurrences
+                .iter()
+                .map(|occurrence| occurrence.text.as_str())
+                .collect::<HashSet<&str>>();
+            texts.len() > 1
+        })
+        .map(|(normalized_text, occurrences)| StepCluster {
+            normalized_text,
+            occurrences,
+        })
+        .collect::<Vec<StepCluster>>();
+    clusters.sort_by(|a, b| {
+        b.occurrences
+            .len()
+            .cmp(&a.occurrences.len())
+            .then_with(|| a.normalized_text.cmp(&b.normalized_text))
+    });
+    clusters
+}
+
+fn find_scenario_clusters(cukes: &[Cuke<'_>], options: &DuplicateOptions) -> Vec<ScenarioCluster> {
+    let mut seen_scenarios = HashSet::new();
+    let scenarios = cukes
+        .iter()
+        .filter(|cuke| seen_scenarios.insert((cuke.uri, cuke.scenario.id.as_str())))
+        .filter(|cuke| cuke.scenario_steps.len() >= options.min_steps)
+        .collect::<Vec<&Cuke<'_>>>();
+    let sequences = scenarios
+        .iter()
+        .map(|cuke| {
+            cuke.scenario_steps
+                .iter()
+                .map(|step| normalize_step_text(&step.text))
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    // single-linkage clustering of the scenarios which are similar enough
+    let mut parents = (0..scenarios.len()).collect::<Vec<usize>>();
+    for a in 0..scenarios.len() {
+        for b in a + 1..scenarios.len() {
+            if similarity(&sequences[a], &sequences[b]) >= options.threshold {
+                let (root_a, root_b) = (find_root(&mut parents, a), find_root(&mut parents, b));
+                parents[root_b] = root_a;
+            }
+        }
+    }
+
+    let mut members = HashMap::<usize, Vec<usize>>::new();
+    for index in 0..scenarios.len() {
+        let root = find_root(&mut parents, index);
+        members.entry(root).or_insert_with(Vec::new).push(index);
+    }
+
+    let mut clusters = members
+        .into_iter()
+        .map(|(_root, members)| members)
+        .filter(|members| members.len() > 1)
+        .map(|members| {
+            let mut lowest_similarity = 1.0f64;
+            for (position, &a) in members.iter().enumerate() {
+                for &b in &members[position + 1..] {
+                    lowest_similarity =
+                        lowest_similarity.min(similarity(&sequences[a], &sequences[b]));
+                }
+            }
+            let first = &sequences[members[0]];
+            ScenarioCluster {
+                identical: members.iter().all(|&index| &sequences[index] == first),
+                similarity: lowest_similarity,
+                scenarios: members
+                    .iter()
+                    .map(|&index| scenario_occurrence(scenarios[index]))
+                    .collect(),
+            }
+        })
+        .collect::<Vec<ScenarioCluster>>();
+    clusters.sort_by(|a, b| {
+        b.scenarios.len().cmp(&a.scenarios.len()).then_with(|| {
+            let first = |cluster: &ScenarioCluster| {
+                let scenario = &cluster.scenarios[0];
+                (scenario.uri.clone(), scenario.location.line)
+            };
+            first(a).cmp(&first(b))
+        })
+    });
+    clusters
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Returns the Dice coefficient of the longest common subsequence of two sequences.
+pub(crate) fn similarity<T: PartialEq>(a: &[T], b: &[T]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let mut lengths = vec![0usize; b.len() + 1];
+    for a_step in a {
+        let mut diagonal = 0;
+        for (index, b_step) in b.iter().enumerate() {
+            let above = lengths[index + 1];
+            lengths[index + 1] = if a_step == b_step {
+                diagonal + 1
+            } else {
+                above.max(lengths[index])
+            };
+            diagonal = above;
+        }
+    }
+
+    2.0 * lengths[b.len()] as f64 / (a.len() + b.len()) as f64
+}
+
+fn step_occurrence(cuke: &Cuke<'_>, step: &Step<'_>) -> StepOccurrence {
+    // the locations of outline steps start with the location of the examples row
+    let location = step
+        .locations
+        .last()
+        .copied()
+        .unwrap_or(Location { line: 0, column: 0 });
+    StepOccurrence {
+        uri: cuke.uri.to_string(),
+        location,
+        text: step.text.to_string(),
+    }
+}
+
+fn scenario_occurrence(cuke: &Cuke<'_>) -> ScenarioOccurrence {
+    ScenarioOccurrence {
+        uri: cuke.uri.to_string(),
+        location: Location::from(cuke.scenario.location.unwrap_or_default()),
+        name: cuke.scenario.name.clone(),
+        steps: cuke
+            .scenario_steps
+            .iter()
+            .map(|step| step_occurrence(cuke, step))
+            .collect(),
+    }
+}
+
+/// Displays the duplicates as plain text, one cluster per paragraph.
+impl fmt::Display for Duplicates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} near-duplicate steps, {} duplicate scenario clusters",
+            self.steps.len(),
+            self.scenarios.len()
+        )?;
+
+        for cluster in &self.steps {
+            writeln!(f, "\nstep: {}", cluster.normalized_text)?;
+            for occurrence in &cluster.occurrences {
+                let location = occurrence.location;
+                writeln!(
+                    f,
+                    "  {}:{}:{}: {}",
+                    occurrence.uri, location.line, location.column, occurrence.text
+                )?;
+            }
+        }
+
+        for cluster in &self.scenarios {
+            if cluster.identical {
+                writeln!(f, "\nidentical scenarios:")?;
+            } else {
+                writeln!(f, "\nsimilar scenarios ({:.2}):", cluster.similarity)?;
+            }
+            for scenario in &cluster.scenarios {
+                let location = scenario.location;
+                writeln!(
+                    f,
+                    "  {}:{}:{}: {}",
+                    scenario.uri, location.line, location.column, scenario.name
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::cuke::Compiler;
+    use crate::test_util::parse_document;
+
+    use super::*;
+
+    #[test]
+    fn normalizes_step_texts() {
+        assert_eq!(
+            normalize_step_text("I  pay 3.50 for \"Cukes\" and <count> 'items' that don't matter"),
+            "i pay {number} for {string} and {placeholder} {string} that don't matter"
+        );
+        assert_eq!(
+            normalize_step_text("user1 has -5 cukes"),
+            "user1 has {number} cukes"
+        );
+    }
+
+    #[test]
+    fn finds_duplicate_steps_and_scenarios() {
+        let eating = parse_document(
+            "eating.feature",
+            r#"
+Feature: Eating
+  Background:
+    Given a basket with 5 cukes
+
+  Scenario: eating some
+    When I eat 2 cukes
+    Then 3 cukes are left
+
+  Scenario Outline: eating many
+    When I eat <eaten> cukes
+    Then <left> cukes are left
+
+    Examples:
+      | eaten | left |
+      | 1     | 4    |
+      | 5     | 0    |
+
+  Scenario: eating and complaining
+    When I eat 4 cukes
+    And I say "yuck"
+    Then 1 cukes are left
+"#,
+        );
+        let basket = parse_document(
+            "basket.feature",
+            r#"
+Feature: Basket
+  Scenario: emptying
+    Given a basket with 3 cukes
+    When I eat 3 cukes
+    Then 0 cukes are left
+
+  Scenario: unrelated
+    Given nothing
+    Then nothing happens
+"#,
+        );
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let mut cukes = compiler.compile(&eating).unwrap();
+        cukes.extend(compiler.compile(&basket).unwrap());
+
+        let duplicates = find_duplicates(&cukes, &DuplicateOptions::default());
+
+        let step_texts = |cluster: &StepCluster| {
+            cluster
+                .occurrences
+                .iter()
+                .map(|occurrence| occurrence.text.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(duplicates.steps.len(), 3);
+        assert_eq!(duplicates.steps[0].normalized_text, "i eat {number} cukes");
+        assert_eq!(
+            step_texts(&duplicates.steps[0]),
+            vec![
+                "I eat 2 cukes",
+                "I eat 1 cukes",
+                "I eat 4 cukes",
+                "I eat 3 cukes"
+            ]
+        );
+        assert_eq!(
+            duplicates.steps[2].occurrences[1].location,
+            Location {
+                line: 4,
+                column: 11
+            }
+        );
+
+        let names = |cluster: &ScenarioCluster| {
+            cluster
+                .scenarios
+                .iter()
+                .map(|scenario| scenario.name.clone())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(duplicates.scenarios.len(), 1);
+        let cluster = &duplicates.scenarios[0];
+        assert_eq!(
+            names(cluster),
+            vec![
+                "eating some",
+                "eating many",
+                "eating and complaining",
+                "emptying"
+            ]
+        );
+        assert!(!cluster.identical);
+        assert!((cluster.similarity - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            cluster.scenarios[3].location,
+            Location { line: 3, column: 3 }
+        );
+
+        let text = duplicates.to_string();
+        assert!(text.starts_with("3 near-duplicate steps, 1 duplicate scenario clusters\n"));
+        assert!(text.contains("\nsimilar scenarios (0.67):\n  eating.feature:6:3: eating some\n"));
+    }
+}
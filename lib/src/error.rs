@@ -26,7 +26,7 @@ pub enum Error {
     UnexpectedToken {
         location: Location,
         state_comment: String,
-        received_token: Box<Token>,
+        received_token: Box<Token<'static>>,
         expected_tokens: &'static [&'static str],
     },
     UnexpectedEof {
@@ -35,6 +35,12 @@ pub enum Error {
         expected_tokens: &'static [&'static str],
     },
     Composite(Vec<Error>),
+    /// A `GherkinDocument` which was not built by the parser can not be compiled,
+    /// e.g. because a node has no location.
+    InvalidDocument {
+        uri: String,
+        message: String,
+    },
     /// Hints that destructuring should not be exhaustive.
     ///
     /// This enum may grow additional variants, so this makes sure clients
@@ -98,6 +104,10 @@ impl fmt::Display for Error {
 
                 Ok(())
             }
+            Error::InvalidDocument {
+                ref uri,
+                ref message,
+            } => write!(f, "{}: {}", uri, message),
             Error::__Nonexhaustive => unreachable!(),
         }
     }
@@ -112,6 +122,7 @@ impl Error {
             Error::UnexpectedToken { location, .. } => Some(location),
             Error::UnexpectedEof { location, .. } => Some(location),
             Error::Composite(ref _errors) => None,
+            Error::InvalidDocument { .. } => None,
             Error::__Nonexhaustive => unreachable!(),
         }
     }
@@ -0,0 +1,539 @@
+//! A JSON representation of a `GherkinDocument` with a stable shape, described by `schema()`.
+//!
+//! Unlike the `cucumber_messages` envelopes, all properties are always present (`null` if absent),
+//! property names are camelCase and the children of features and rules and the arguments of steps
+//! are unions discriminated by a `type` property, so the shape maps directly onto TypeScript types.
+
+use cucumber_messages::ast;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    pub uri: String,
+    pub feature: Option<Feature>,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub location: Location,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Feature {
+    pub location: Location,
+    pub tags: Vec<Tag>,
+    pub language: String,
+    pub keyword: String,
+    pub name: String,
+    pub description: String,
+    pub children: Vec<FeatureChild>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FeatureChild {
+    Background(Background),
+    Scenario(Scenario),
+    Rule(Rule),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub location: Location,
+    pub keyword: String,
+    pub name: String,
+    pub description: String,
+    pub children: Vec<RuleChild>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleChild {
+    Background(Background),
+    Scenario(Scenario),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Background {
+    pub id: String,
+    pub location: Location,
+    pub keyword: String,
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<Step>,
+}
+
+/// A scenario, or a scenario outline if it has examples.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Scenario {
+    pub id: String,
+    pub location: Location,
+    pub tags: Vec<Tag>,
+    pub keyword: String,
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<Step>,
+    pub examples: Vec<Examples>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Examples {
+    pub id: String,
+    pub location: Location,
+    pub tags: Vec<Tag>,
+    pub keyword: String,
+    pub name: String,
+    pub description: String,
+    pub table_header: Option<TableRow>,
+    pub table_body: Vec<TableRow>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Step {
+    pub id: String,
+    pub location: Location,
+    pub keyword: String,
+    pub text: String,
+    pub argument: Option<StepArgument>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StepArgument {
+    DocString(DocString),
+    DataTable(DataTable),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DocString {
+    pub location: Location,
+    /// The media type after the opening delimiter, empty if there is none.
+    pub media_type: String,
+    pub content: String,
+    pub delimiter: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataTable {
+    pub location: Location,
+    pub rows: Vec<TableRow>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRow {
+    pub id: String,
+    pub location: Location,
+    pub cells: Vec<TableCell>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableCell {
+    pub location: Location,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: String,
+    pub location: Location,
+    pub name: String,
+}
+
+/// Converts a document into its JSON representation.
+pub fn to_json(gherkin_document: &ast::GherkinDocument) -> Document {
+    Document {
+        uri: gherkin_document.uri.clone(),
+        feature: gherkin_document.feature.as_ref().map(Feature::from),
+        comments: gherkin_document
+            .comments
+            .iter()
+            .map(Comment::from)
+            .collect(),
+    }
+}
+
+impl From<Option<ast::Location>> for Location {
+    fn from(location: Option<ast::Location>) -> Self {
+        let location = location.unwrap_or_default();
+        Location {
+            line: location.line,
+            column: location.column,
+        }
+    }
+}
+
+impl From<&ast::Comment> for Comment {
+    fn from(comment: &ast::Comment) -> Self {
+        Comment {
+            location: Location::from(comment.location),
+            text: comment.text.clone(),
+        }
+    }
+}
+
+impl From<&ast::Feature> for Feature {
+    fn from(feature: &ast::Feature) -> Self {
+        let children = feature
+            .children
+            .iter()
+            .filter_map(|child| match &child.value {
+                Some(ast::FeatureChildValue::Background(background)) => {
+                    Some(FeatureChild::Background(Background::from(background)))
+                }
+                Some(ast::FeatureChildValue::Scenario(scenario)) => {
+                    Some(FeatureChild::Scenario(Scenario::from(scenario)))
+                }
+                Some(ast::FeatureChildValue::Rule(rule)) => {
+                    Some(FeatureChild::Rule(Rule::from(rule)))
+                }
+                None => None,
+            })
+            .collect();
+
+        Feature {
+            location: Location::from(feature.location),
+            tags: feature.tags.iter().map(Tag::from).collect(),
+            language: feature.language.clone(),
+            keyword: feature.keyword.clone(),
+            name: feature.name.clone(),
+            description: feature.description.clone(),
+            children,
+        }
+    }
+}
+
+impl From<&ast::Rule> for Rule {
+    fn from(rule: &ast::Rule) -> Self {
+        let children = rule
+            .children
+            .iter()
+            .filter_map(|child| match &child.value {
+                Some(ast::RuleChildValue::Background(background)) => {
+                    Some(RuleChild::Background(Background::from(background)))
+                }
+                Some(ast::RuleChildValue::Scenario(scenario)) => {
+                    Some(RuleChild::Scenario(Scenario::from(scenario)))
+                }
+                None => None,
+            })
+            .collect();
+
+        Rule {
+            id: rule.id.clone(),
+            location: Location::from(rule.location),
+            keyword: rule.keyword.clone(),
+            name: rule.name.clone(),
+            description: rule.description.clone(),
+            children,
+        }
+    }
+}
+
+impl From<&ast::Background> for Background {
+    fn from(background: &ast::Background) -> Self {
+        Background {
+            id: background.id.clone(),
+            location: Location::from(background.location),
+            keyword: background.keyword.clone(),
+            name: background.name.clone(),
+            description: background.description.clone(),
+            steps: background.steps.iter().map(Step::from).collect(),
+        }
+    }
+}
+
+impl From<&ast::Scenario> for Scenario {
+    fn from(scenario: &ast::Scenario) -> Self {
+        Scenario {
+            id: scenario.id.clone(),
+            location: Location::from(scenario.location),
+            tags: scenario.tags.iter().map(Tag::from).collect(),
+            keyword: scenario.keyword.clone(),
+            name: scenario.name.clone(),
+            description: scenario.description.clone(),
+            steps: scenario.steps.iter().map(Step::from).collect(),
+            examples: scenario.examples.iter().map(Examples::from).collect(),
+        }
+    }
+}
+
+impl From<&ast::Examples> for Examples {
+    fn from(examples: &ast::Examples) -> Self {
+        Examples {
+            id: examples.id.clone(),
+            location: Location::from(examples.location),
+            tags: examples.tags.iter().map(Tag::from).collect(),
+            keyword: examples.keyword.clone(),
+            name: examples.name.clone(),
+            description: examples.description.clone(),
+            table_header: examples.table_header.as_ref().map(TableRow::from),
+            table_body: examples.table_body.iter().map(TableRow::from).collect(),
+        }
+    }
+}
+
+impl From<&ast::Step> for Step {
+    fn from(step: &ast::Step) -> Self {
+        let argument = match &step.argument {
+            Some(ast::Argument::DocString(doc_string)) => {
+                Some(StepArgument::DocString(DocString {
+                    location: Location::from(doc_string.location),
+                    media_type: doc_string.media_type.clone(),
+                    content: doc_string.content.clone(),
+                    delimiter: doc_string.delimiter.clone(),
+                }))
+            }
+            Some(ast::Argument::DataTable(data_table)) => {
+                Some(StepArgument::DataTable(DataTable {
+                    location: Location::from(data_table.location),
+                    rows: data_table.rows.iter().map(TableRow::from).collect(),
+                }))
+            }
+            None => None,
+        };
+
+        Step {
+            id: step.id.clone(),
+            location: Location::from(step.location),
+            keyword: step.keyword.clone(),
+            text: step.text.clone(),
+            argument,
+        }
+    }
+}
+
+impl From<&ast::TableRow> for TableRow {
+    fn from(table_row: &ast::TableRow) -> Self {
+        TableRow {
+            id: table_row.id.clone(),
+            location: Location::from(table_row.location),
+            cells: table_row
+                .cells
+                .iter()
+                .map(|table_cell| TableCell {
+                    location: Location::from(table_cell.location),
+                    value: table_cell.value.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&ast::Tag> for Tag {
+    fn from(tag: &ast::Tag) -> Self {
+        Tag {
+            id: tag.id.clone(),
+            location: Location::from(tag.location),
+            name: tag.name.clone(),
+        }
+    }
+}
+
+/// Returns the JSON Schema (draft-07) of a `Document`.
+pub fn schema() -> Value {
+    let string = || json!({ "type": "string" });
+    let array = |name: &str| json!({ "type": "array", "items": reference(name) });
+
+    let definitions = json!({
+        "location": object(None, json!({
+            "line": { "type": "integer", "minimum": 0 },
+            "column": { "type": "integer", "minimum": 0 },
+        })),
+        "comment": object(None, json!({
+            "location": reference("location"),
+            "text": string(),
+        })),
+        "feature": object(None, json!({
+            "location": reference("location"),
+            "tags": array("tag"),
+            "language": string(),
+            "keyword": string(),
+            "name": string(),
+            "description": string(),
+            "children": {
+                "type": "array",
+                "items": {
+                    "oneOf": [reference("background"), reference("scenario"), reference("rule")],
+                },
+            },
+        })),
+        "rule": object(Some("rule"), json!({
+            "id": string(),
+            "location": reference("location"),
+            "keyword": string(),
+            "name": string(),
+            "description": string(),
+            "children": {
+                "type": "array",
+                "items": { "oneOf": [reference("background"), reference("scenario")] },
+            },
+        })),
+        "background": object(Some("background"), json!({
+            "id": string(),
+            "location": reference("location"),
+            "keyword": string(),
+            "name": string(),
+            "description": string(),
+            "steps": array("step"),
+        })),
+        "scenario": object(Some("scenario"), json!({
+            "id": string(),
+            "location": reference("location"),
+            "tags": array("tag"),
+            "keyword": string(),
+            "name": string(),
+            "description": string(),
+            "steps": array("step"),
+            "examples": array("examples"),
+        })),
+        "examples": object(None, json!({
+            "id": string(),
+            "location": reference("location"),
+            "tags": array("tag"),
+            "keyword": string(),
+            "name": string(),
+            "description": string(),
+            "tableHeader": nullable(reference("tableRow")),
+            "tableBody": array("tableRow"),
+        })),
+        "step": object(None, json!({
+            "id": string(),
+            "location": reference("location"),
+            "keyword": string(),
+            "text": string(),
+            "argument": {
+                "oneOf": [reference("docString"), reference("dataTable"), { "type": "null" }],
+            },
+        })),
+        "docString": object(Some("docString"), json!({
+            "location": reference("location"),
+            "mediaType": string(),
+            "content": string(),
+            "delimiter": string(),
+        })),
+        "dataTable": object(Some("dataTable"), json!({
+            "location": reference("location"),
+            "rows": array("tableRow"),
+        })),
+        "tableRow": object(None, json!({
+            "id": string(),
+            "location": reference("location"),
+            "cells": array("tableCell"),
+        })),
+        "tableCell": object(None, json!({
+            "location": reference("location"),
+            "value": string(),
+        })),
+        "tag": object(None, json!({
+            "id": string(),
+            "location": reference("location"),
+            "name": string(),
+        })),
+    });
+
+    let mut schema = object(
+        None,
+        json!({
+            "uri": string(),
+            "feature": nullable(reference("feature")),
+            "comments": array("comment"),
+        }),
+    );
+    let root = schema.as_object_mut().unwrap();
+    root.insert(
+        String::from("$schema"),
+        json!("http://json-schema.org/draft-07/schema#"),
+    );
+    root.insert(String::from("title"), json!("GherkinDocument"));
+    root.insert(String::from("definitions"), definitions);
+    schema
+}
+
+/// Returns the schema of an object with all of the given properties and no others,
+/// which is discriminated by a `type` property if there is a type name.
+fn object(type_name: Option<&str>, properties: Value) -> Value {
+    let mut properties = match properties {
+        Value::Object(properties) => properties,
+        _ => unreachable!("properties need to be an object"),
+    };
+    if let Some(type_name) = type_name {
+        properties.insert(String::from("type"), json!({ "const": type_name }));
+    }
+    let required = properties
+        .keys()
+        .cloned()
+        .map(Value::from)
+        .collect::<Vec<Value>>();
+
+    let mut object = Map::new();
+    object.insert(String::from("type"), json!("object"));
+    object.insert(String::from("properties"), Value::Object(properties));
+    object.insert(String::from("required"), Value::Array(required));
+    object.insert(String::from("additionalProperties"), json!(false));
+    Value::Object(object)
+}
+
+fn reference(definition: &str) -> Value {
+    json!({ "$ref": format!("#/definitions/{}", definition) })
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({ "oneOf": [schema, { "type": "null" }] })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::parse_document;
+
+    use super::*;
+
+    #[test]
+    fn discriminates_children_and_arguments_by_type() {
+        let source = r#"
+Feature: Cukes
+  Rule: Eating
+    Scenario: eating cukes
+      Given a note:
+        """
+        yummy
+        """
+"#;
+        let gherkin_document = parse_document("cukes.feature", source);
+
+        let json = serde_json::to_value(to_json(&gherkin_document)).unwrap();
+        let rule = &json["feature"]["children"][0];
+        assert_eq!(rule["type"], "rule");
+        let scenario = &rule["children"][0];
+        assert_eq!(scenario["type"], "scenario");
+        assert_eq!(scenario["examples"], json!([]));
+        assert_eq!(scenario["steps"][0]["argument"]["type"], "docString");
+        assert_eq!(scenario["steps"][0]["argument"]["mediaType"], "");
+
+        let document = serde_json::from_value::<Document>(json).unwrap();
+        assert_eq!(document, to_json(&gherkin_document));
+    }
+}
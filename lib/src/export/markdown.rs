@@ -0,0 +1,299 @@
+use std::fmt::{self, Write};
+
+use cucumber_messages::ast;
+
+const MIN_FENCE_LEN: usize = 3;
+const LIST_ITEM_INDENT: &str = "  ";
+
+/// Renders a document as GitHub-flavored Markdown.
+///
+/// The feature, rules, backgrounds, scenarios and examples are headings with bold keywords,
+/// steps are list items, data tables and examples tables are tables, doc strings are fenced
+/// code blocks with the media type as language and tags are code spans.
+/// As Markdown tables need a header, the first row of a data table is rendered as header.
+/// Comments are not rendered.
+pub fn to_markdown(gherkin_document: &ast::GherkinDocument) -> String {
+    let mut markdown = String::new();
+    if let Some(feature) = &gherkin_document.feature {
+        write_feature(&mut markdown, feature).expect("write to string");
+    }
+    markdown
+}
+
+fn write_feature(markdown: &mut String, feature: &ast::Feature) -> fmt::Result {
+    write_heading(markdown, 1, &feature.keyword, &feature.name)?;
+    write_tags(markdown, &feature.tags)?;
+    write_description(markdown, &feature.description)?;
+
+    for feature_child in &feature.children {
+        match &feature_child.value {
+            Some(ast::FeatureChildValue::Rule(rule)) => write_rule(markdown, rule)?,
+            Some(ast::FeatureChildValue::Background(background)) => {
+                write_background(markdown, background, 2)?
+            }
+            Some(ast::FeatureChildValue::Scenario(scenario)) => {
+                write_scenario(markdown, scenario, 2)?
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn write_rule(markdown: &mut String, rule: &ast::Rule) -> fmt::Result {
+    write_heading(markdown, 2, &rule.keyword, &rule.name)?;
+    write_description(markdown, &rule.description)?;
+
+    for rule_child in &rule.children {
+        match &rule_child.value {
+            Some(ast::RuleChildValue::Background(background)) => {
+                write_background(markdown, background, 3)?
+            }
+            Some(ast::RuleChildValue::Scenario(scenario)) => write_scenario(markdown, scenario, 3)?,
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn write_background(
+    markdown: &mut String,
+    background: &ast::Background,
+    level: usize,
+) -> fmt::Result {
+    write_heading(markdown, level, &background.keyword, &background.name)?;
+    write_description(markdown, &background.description)?;
+    write_steps(markdown, &background.steps)
+}
+
+fn write_scenario(markdown: &mut String, scenario: &ast::Scenario, level: usize) -> fmt::Result {
+    write_heading(markdown, level, &scenario.keyword, &scenario.name)?;
+    write_tags(markdown, &scenario.tags)?;
+    write_description(markdown, &scenario.description)?;
+    write_steps(markdown, &scenario.steps)?;
+
+    for examples in &scenario.examples {
+        write_heading(markdown, level + 1, &examples.keyword, &examples.name)?;
+        write_tags(markdown, &examples.tags)?;
+        write_description(markdown, &examples.description)?;
+
+        if let Some(table_header) = &examples.table_header {
+            write_table(markdown, table_header, &examples.table_body, "")?;
+            markdown.push('\n');
+        }
+    }
+    Ok(())
+}
+
+fn write_steps(markdown: &mut String, steps: &[ast::Step]) -> fmt::Result {
+    if steps.is_empty() {
+        return Ok(());
+    }
+
+    for step in steps {
+        writeln!(
+            markdown,
+            "- **{}** {}",
+            escape(step.keyword.trim()),
+            escape(&step.text)
+        )?;
+
+        match &step.argument {
+            Some(ast::Argument::DocString(doc_string)) => {
+                write_doc_string(markdown, doc_string)?;
+            }
+            Some(ast::Argument::DataTable(data_table)) => {
+                if let Some((header, body)) = data_table.rows.split_first() {
+                    markdown.push('\n');
+                    write_table(markdown, header, body, LIST_ITEM_INDENT)?;
+                }
+            }
+            None => {}
+        }
+    }
+    markdown.push('\n');
+    Ok(())
+}
+
+fn write_heading(markdown: &mut String, level: usize, keyword: &str, name: &str) -> fmt::Result {
+    write!(markdown, "{} **{}:**", "#".repeat(level), escape(keyword))?;
+    if !name.is_empty() {
+        write!(markdown, " {}", escape(name))?;
+    }
+    markdown.push_str("\n\n");
+    Ok(())
+}
+
+fn write_tags(markdown: &mut String, tags: &[ast::Tag]) -> fmt::Result {
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let tags = tags
+        .iter()
+        .map(|tag| format!("`{}`", tag.name))
+        .collect::<Vec<String>>();
+    writeln!(markdown, "{}\n", tags.join(" "))
+}
+
+fn write_description(markdown: &mut String, description: &str) -> fmt::Result {
+    let lines = description
+        .trim()
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<&str>>();
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    for line in lines {
+        writeln!(markdown, "{}", escape(line))?;
+    }
+    markdown.push('\n');
+    Ok(())
+}
+
+fn write_doc_string(markdown: &mut String, doc_string: &ast::DocString) -> fmt::Result {
+    // the fence has to be longer than any backtick sequence of the content
+    let longest_backticks = doc_string
+        .content
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(MIN_FENCE_LEN.max(longest_backticks + 1));
+
+    markdown.push('\n');
+    writeln!(
+        markdown,
+        "{}{}{}",
+        LIST_ITEM_INDENT, fence, doc_string.media_type
+    )?;
+    for line in doc_string.content.lines() {
+        if line.is_empty() {
+            markdown.push('\n');
+        } else {
+            writeln!(markdown, "{}{}", LIST_ITEM_INDENT, line)?;
+        }
+    }
+    writeln!(markdown, "{}{}", LIST_ITEM_INDENT, fence)
+}
+
+fn write_table(
+    markdown: &mut String,
+    header: &ast::TableRow,
+    body: &[ast::TableRow],
+    indent: &str,
+) -> fmt::Result {
+    write_table_row(markdown, header, indent)?;
+    write!(markdown, "{}|", indent)?;
+    for _ in &header.cells {
+        markdown.push_str(" --- |");
+    }
+    markdown.push('\n');
+    for table_row in body {
+        write_table_row(markdown, table_row, indent)?;
+    }
+    Ok(())
+}
+
+fn write_table_row(markdown: &mut String, table_row: &ast::TableRow, indent: &str) -> fmt::Result {
+    write!(markdown, "{}|", indent)?;
+    for table_cell in &table_row.cells {
+        let value = escape(&table_cell.value).replace('\n', "<br>");
+        write!(markdown, " {} |", value)?;
+    }
+    markdown.push('\n');
+    Ok(())
+}
+
+/// Escapes the characters of a text which would otherwise be formatting or inline HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (index, c) in text.char_indices() {
+        let is_special = match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' => true,
+            // a heading or list item at the start of a line
+            '#' | '-' | '+' => index == 0,
+            _ => false,
+        };
+        if is_special {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::parse_document;
+
+    use super::*;
+
+    #[test]
+    fn renders_gherkin_document_as_markdown() {
+        let source = r#"
+@food
+Feature: Cukes
+  Eating them
+  is healthy
+
+  Rule: Eating
+    Background:
+      Given a basket:
+        | name    | color |
+        | gherkin | green |
+
+    Scenario Outline: eating <eat> cukes
+      When I eat <eat> cukes
+      Then the note reads:
+        """markdown
+        ```yummy``` *cukes*
+        """
+
+      @slow
+      Examples: amounts
+        | eat |
+        | 5   |
+"#;
+        let gherkin_document = parse_document("cukes.feature", source);
+
+        let expected = r#"# **Feature:** Cukes
+
+`@food`
+
+Eating them
+is healthy
+
+## **Rule:** Eating
+
+### **Background:**
+
+- **Given** a basket:
+
+  | name | color |
+  | --- | --- |
+  | gherkin | green |
+
+### **Scenario Outline:** eating \<eat\> cukes
+
+- **When** I eat \<eat\> cukes
+- **Then** the note reads:
+
+  ````markdown
+  ```yummy``` *cukes*
+  ````
+
+#### **Examples:** amounts
+
+`@slow`
+
+| eat |
+| --- |
+| 5 |
+
+"#;
+        assert_eq!(to_markdown(&gherkin_document), expected);
+    }
+}
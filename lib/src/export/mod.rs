@@ -0,0 +1,6 @@
+//! Exporters rendering a `GherkinDocument` into other formats.
+
+pub use crate::export::markdown::to_markdown;
+
+pub mod json;
+mod markdown;
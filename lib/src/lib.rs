@@ -10,26 +10,56 @@ use cucumber_messages::pickle::Pickle;
 use cucumber_messages::source::{Source, SourceReference, SourceReferenceType};
 use cucumber_messages::{Envelope, Message, ParseError};
 
+use crate::source_parser::SourceParser;
+
+pub use crate::dialect::{StepKeywordType, StepKeywordTypes};
 pub use crate::document_builder::DocumentBuilder;
 pub use crate::error::{Error, Result};
+pub use crate::line::{Line, LineSpan};
 pub use crate::location::Location;
-pub use crate::parser::{DialectProvider, Parser, ParserOptions};
+pub use crate::parser::{DialectProvider, Parser, ParserOptions, TokenScan, TokenType};
+pub use crate::query::{AstNode, GherkinQuery};
+pub use crate::token::Token;
 pub use crate::token_formatter_builder::TokenFormatterBuilder;
+pub use crate::token_scanner::{StrTokenScanner, TokenScanner};
+pub use crate::tokens_builder::TokensBuilder;
+
+#[cfg(feature = "async")]
+pub use crate::async_parser::{parse_paths_async, MAX_CONCURRENT_READS};
 
 mod ast_node;
+#[cfg(feature = "async")]
+mod async_parser;
+pub mod changed;
 mod constant;
+pub mod cucumber_expressions;
 pub mod cuke;
 mod dialect;
 mod dialect_provider;
+pub mod diff;
+pub mod docs;
 mod document_builder;
+pub mod duplicates;
 mod error;
+pub mod export;
 mod line;
 mod location;
+mod markdown_token_matcher;
 mod parser;
+mod query;
+mod source_parser;
+pub mod stats;
+pub mod step_registry;
+#[cfg(test)]
+mod test_util;
 mod token;
 mod token_formatter_builder;
 mod token_matcher;
 mod token_scanner;
+mod tokens_builder;
+mod uri;
+pub mod visit;
+pub mod visit_mut;
 
 pub struct IncludeOptions {
     pub source: bool,
@@ -46,23 +76,115 @@ where
     P: IntoIterator,
     P::Item: AsRef<Path>,
 {
+    let (envelopes, _diagnostics) =
+        parse_paths_with_diagnostics(paths, include_options, id_generator)?;
+    Ok(envelopes)
+}
+
+/// Like `parse_paths`, but also returns the diagnostics of the scenario outlines
+/// which were reported while compiling the pickles.
+pub fn parse_paths_with_diagnostics<P>(
+    paths: P,
+    include_options: IncludeOptions,
+    id_generator: &mut dyn IdGenerator,
+) -> io::Result<(Vec<Envelope>, Vec<cuke::Diagnostic>)>
+where
+    P: IntoIterator,
+    P::Item: AsRef<Path>,
+{
+    let envelopes = paths
+        .into_iter()
+        .map(|path| create_envelope_from_path(path.as_ref()));
+
+    let mut diagnostics = Vec::new();
+    let messages =
+        parse_source_envelopes(envelopes, &include_options, id_generator, &mut diagnostics)?;
+    Ok((messages, diagnostics))
+}
+
+/// A `GherkinDocument` with the keyword types of its steps, which `Compiler::compile_with`
+/// needs for documents of custom dialects.
+#[derive(Debug, Clone)]
+pub struct ParsedDocument {
+    pub gherkin_document: ast::GherkinDocument,
+    pub step_keyword_types: StepKeywordTypes,
+}
+
+/// Parses the feature files into documents which can be compiled with the keyword types
+/// of their steps, and returns the errors of the feature files which could not be parsed.
+pub fn parse_documents<P>(
+    paths: P,
+    id_generator: &mut dyn IdGenerator,
+) -> io::Result<(Vec<ParsedDocument>, Vec<ParseError>)>
+where
+    P: IntoIterator,
+    P::Item: AsRef<Path>,
+{
+    let mut parsed_documents = Vec::new();
     let mut messages = Vec::new();
 
     let builder = DocumentBuilder::with_id_generator(id_generator);
-    let mut parser = Parser::with_builder(builder);
+    let mut parser = SourceParser::new(builder);
 
     for path in paths {
-        let envelope = create_envelope_from_path(path.as_ref())?;
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        let uri = path.display().to_string();
+
+        match parser.parse_str(&source, media_type(path)) {
+            Ok(mut gherkin_document) => {
+                gherkin_document.uri = uri;
+                parsed_documents.push(ParsedDocument {
+                    gherkin_document,
+                    step_keyword_types: parser.builder_mut().take_step_keyword_types(),
+                });
+            }
+            Err(error) => add_error_attachments(&mut messages, error, &uri)?,
+        }
+    }
+
+    let parse_errors = messages
+        .into_iter()
+        .filter_map(|envelope| match envelope.message {
+            Some(Message::ParseError(parse_error)) => Some(parse_error),
+            _ => None,
+        })
+        .collect();
+    Ok((parsed_documents, parse_errors))
+}
+
+fn parse_source_envelopes<E>(
+    envelopes: E,
+    include_options: &IncludeOptions,
+    id_generator: &mut dyn IdGenerator,
+    diagnostics: &mut Vec<cuke::Diagnostic>,
+) -> io::Result<Vec<Envelope>>
+where
+    E: IntoIterator<Item = io::Result<Envelope>>,
+{
+    let mut messages = Vec::new();
+
+    let builder = DocumentBuilder::with_id_generator(id_generator);
+    let mut parser = SourceParser::new(builder);
+
+    for envelope in envelopes {
+        let envelope = envelope?;
 
         if include_options.source {
             messages.push(envelope);
             messages.extend(parse_envelope(
                 &mut parser,
-                &include_options,
+                include_options,
                 messages.last().unwrap(),
+                diagnostics,
             )?);
         } else {
-            messages.extend(parse_envelope(&mut parser, &include_options, &envelope)?);
+            messages.extend(parse_envelope(
+                &mut parser,
+                include_options,
+                &envelope,
+                diagnostics,
+            )?);
         }
     }
 
@@ -80,15 +202,27 @@ fn create_source_envelope(data: String, path: &Path) -> Envelope {
         message: Some(Message::Source(Source {
             data,
             uri: path.display().to_string(),
-            media_type: String::from("text/x.cucumber.gherkin+plain"),
+            media_type: String::from(media_type(path)),
         })),
     }
 }
 
+/// Returns the media type of a source by the suffix of its file name:
+/// Markdown with Gherkin for `.feature.md` files and plain Gherkin for all others.
+fn media_type(path: &Path) -> &'static str {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    if file_name.ends_with(constant::MARKDOWN_FILE_SUFFIX) {
+        constant::MEDIA_TYPE_MARKDOWN
+    } else {
+        constant::MEDIA_TYPE_PLAIN
+    }
+}
+
 fn parse_envelope(
-    parser: &mut Parser<DocumentBuilder>,
+    parser: &mut SourceParser,
     include_options: &IncludeOptions,
     envelope: &Envelope,
+    diagnostics: &mut Vec<cuke::Diagnostic>,
 ) -> io::Result<Vec<Envelope>> {
     let mut messages = Vec::new();
 
@@ -104,7 +238,7 @@ fn parse_envelope(
     };
 
     if include_options.gherkin_document || include_options.pickles {
-        let mut gherkin_document = match parser.parse_str(&source.data) {
+        let mut gherkin_document = match parser.parse_str(&source.data, &source.media_type) {
             Ok(gherkin_document) => gherkin_document,
             Err(error) => {
                 add_error_attachments(&mut messages, error, &source.uri)?;
@@ -116,11 +250,19 @@ fn parse_envelope(
 
         let pickles = if include_options.pickles {
             let builder = parser.builder_mut();
+            let step_keyword_types = builder.take_step_keyword_types();
             let id_generator = builder.id_generator_mut();
             let mut compiler = crate::cuke::Compiler::new(id_generator);
 
-            let cukes = compiler.compile(&gherkin_document);
-            cukes.into_iter().map(Pickle::from).collect::<Vec<Pickle>>()
+            let pickles = match compiler.compile_with(&gherkin_document, &step_keyword_types) {
+                Ok(cukes) => cukes.into_iter().map(Pickle::from).collect::<Vec<Pickle>>(),
+                Err(error) => {
+                    add_error_attachments(&mut messages, error, &source.uri)?;
+                    Vec::new()
+                }
+            };
+            diagnostics.extend(compiler.take_diagnostics());
+            pickles
         } else {
             Vec::new()
         };
@@ -153,7 +295,11 @@ fn add_error_attachments(messages: &mut Vec<Envelope>, error: Error, uri: &str)
         | Error::NoSuchLanguage { location, .. }
         | Error::UnexpectedToken { location, .. }
         | Error::UnexpectedEof { location, .. } => {
-            messages.push(create_parse_error_envelope(&error, uri, location));
+            messages.push(create_parse_error_envelope(&error, uri, Some(location)));
+            Ok(())
+        }
+        Error::InvalidDocument { .. } => {
+            messages.push(create_parse_error_envelope(&error, uri, None));
             Ok(())
         }
         Error::Io(io_error) => Err(io_error),
@@ -161,7 +307,7 @@ fn add_error_attachments(messages: &mut Vec<Envelope>, error: Error, uri: &str)
     }
 }
 
-fn create_parse_error_envelope(error: &Error, uri: &str, location: Location) -> Envelope {
+fn create_parse_error_envelope(error: &Error, uri: &str, location: Option<Location>) -> Envelope {
     Envelope {
         message: Some(Message::ParseError(ParseError {
             source: Some(create_source_reference(uri, location)),
@@ -170,9 +316,9 @@ fn create_parse_error_envelope(error: &Error, uri: &str, location: Location) ->
     }
 }
 
-fn create_source_reference(uri: &str, location: Location) -> SourceReference {
+fn create_source_reference(uri: &str, location: Option<Location>) -> SourceReference {
     SourceReference {
-        location: Some(ast::Location::from(location)),
+        location: location.map(ast::Location::from),
         reference: Some(SourceReferenceType::Uri(uri.to_string())),
     }
 }
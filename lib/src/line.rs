@@ -1,33 +1,61 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
 use crate::constant;
 use crate::{Error, Location, Result};
 
+/// A single line of a gherkin document.
+///
+/// The line text is borrowed from the source when it is parsed from a `&str`, so the
+/// texts matched by the token matcher can be borrowed from the source as well.
 #[derive(Debug, Clone)]
-pub struct Line {
-    text: String,
-    trimmed_text: String,
+pub struct Line<'s> {
+    text: Cow<'s, str>,
+    trimmed_start: usize,
     ident: u32,
     line: u32,
 }
 
-#[derive(PartialEq, Eq, Hash, Debug, Clone)]
-pub struct LineSpan {
+/// A part of a line, like a tag or the value of a table cell.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone)]
+pub struct LineSpan<'s> {
     pub column: u32,
-    pub text: String,
+    pub text: Cow<'s, str>,
+}
+
+impl LineSpan<'_> {
+    pub fn into_owned(self) -> LineSpan<'static> {
+        LineSpan {
+            column: self.column,
+            text: Cow::Owned(self.text.into_owned()),
+        }
+    }
 }
 
-impl Line {
-    pub fn new(text: String, line: u32) -> Line {
-        let trimmed_text = text.trim_start().to_string();
-        let ident = text.chars().count() as u32 - trimmed_text.chars().count() as u32;
+impl<'s> Line<'s> {
+    pub fn new<T: Into<Cow<'s, str>>>(text: T, line: u32) -> Line<'s> {
+        let text = text.into();
+        let trimmed_start = text.len() - text.trim_start().len();
+        let ident = text[..trimmed_start].chars().count() as u32;
 
         Line {
             text,
-            trimmed_text,
+            trimmed_start,
             ident,
             line,
         }
     }
 
+    pub fn into_owned(self) -> Line<'static> {
+        Line {
+            text: Cow::Owned(self.text.into_owned()),
+            trimmed_start: self.trimmed_start,
+            ident: self.ident,
+            line: self.line,
+        }
+    }
+
     pub fn indent(&self) -> u32 {
         self.ident
     }
@@ -36,7 +64,7 @@ impl Line {
 
     pub fn get_text(&self, indent_to_remove: isize) -> &str {
         if indent_to_remove < 0 || indent_to_remove > self.indent() as isize {
-            &self.trimmed_text
+            self.trimmed_text()
         } else {
             let mut chars = self.text.chars();
             for _ in 0..indent_to_remove {
@@ -46,28 +74,45 @@ impl Line {
         }
     }
 
+    /// Like `get_text`, but borrows the text from the source when possible.
+    pub fn to_text(&self, indent_to_remove: isize) -> Cow<'s, str> {
+        self.to_sub_text(self.get_text(indent_to_remove))
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.trimmed_text.is_empty()
+        self.trimmed_text().is_empty()
     }
 
     pub fn starts_with(&self, prefix: &str) -> bool {
-        self.trimmed_text.starts_with(prefix)
+        self.trimmed_text().starts_with(prefix)
     }
 
     pub fn get_rest_trimmed(&self, length: usize) -> &str {
-        let mut chars = self.trimmed_text.chars();
+        let mut chars = self.trimmed_text().chars();
         for _ in 0..length {
             chars.next();
         }
         chars.as_str().trim()
     }
 
-    pub fn get_tags(&self) -> Result<Vec<LineSpan>> {
+    /// Like `get_rest_trimmed`, but borrows the text from the source when possible.
+    pub fn to_rest_trimmed(&self, length: usize) -> Cow<'s, str> {
+        self.to_sub_text(self.get_rest_trimmed(length))
+    }
+
+    /// Returns the keyword the trimmed line starts with, borrowed from the source when possible.
+    pub fn to_keyword(&self, keyword: &str) -> Cow<'s, str> {
+        debug_assert!(self.starts_with(keyword));
+        self.to_sub_text(&self.trimmed_text()[..keyword.len()])
+    }
+
+    pub fn get_tags(&self) -> Result<Vec<LineSpan<'s>>> {
         let mut tags = Vec::new();
+        let trimmed_text = self.trimmed_text();
 
         let mut uncommented_line_len = 0;
         let mut prev_was_whitespace = false;
-        for char in self.trimmed_text.chars() {
+        for char in trimmed_text.chars() {
             if !prev_was_whitespace {
                 if char.is_whitespace() {
                     prev_was_whitespace = true;
@@ -81,18 +126,22 @@ impl Line {
             uncommented_line_len += char.len_utf8();
         }
 
-        let uncommented_line = &self.trimmed_text[0..uncommented_line_len];
-        let mut index_in_uncommented_line = 0;
+        let uncommented_line = &trimmed_text[0..uncommented_line_len];
+        let mut element_start = 0;
 
         let elements = uncommented_line.split(constant::TAG_PREFIX);
         for element in elements {
+            let start = element_start;
+            element_start += element.len() + constant::TAG_PREFIX.len();
+
             let token = element.trim_end();
             if token.is_empty() {
                 continue;
             }
-            let symbol_length = uncommented_line[0..index_in_uncommented_line]
-                .chars()
-                .count() as u32;
+            // the tag prefix precedes every element except the first one, which is empty
+            // if the line starts with the tag prefix
+            let tag_start = start.saturating_sub(constant::TAG_PREFIX.len());
+            let symbol_length = uncommented_line[..tag_start].chars().count() as u32;
             let column = self.indent() + symbol_length + 1;
             if token.contains(|c: char| c.is_whitespace()) {
                 return Err(Error::DocumentBuilder {
@@ -101,48 +150,45 @@ impl Line {
                 });
             }
 
-            let mut text = String::with_capacity(token.len() + 1);
-            text.push_str(constant::TAG_PREFIX);
-            text.push_str(token);
+            let text = if start == 0 {
+                Cow::Owned(format!("{}{}", constant::TAG_PREFIX, token))
+            } else {
+                self.to_sub_text(&uncommented_line[tag_start..start + token.len()])
+            };
 
             tags.push(LineSpan { column, text });
-            index_in_uncommented_line += element.len() + 1;
         }
 
         Ok(tags)
     }
 
     pub fn starts_with_title_keyword(&self, text: &str) -> bool {
-        let text_chars_count = text.chars().count();
+        let trimmed_text = self.trimmed_text();
 
-        let mut chars = self.trimmed_text.chars();
-        for _ in 0..text_chars_count {
-            chars.next();
-        }
-        let separator_chars_count = constant::TITLE_KEYWORD_SEPARATOR.chars().count();
-        let separator: String = chars.take(separator_chars_count).collect();
-
-        self.trimmed_text.chars().count() > text_chars_count
-            && self.trimmed_text.starts_with(text)
-            && separator == constant::TITLE_KEYWORD_SEPARATOR
+        trimmed_text.starts_with(text)
+            && trimmed_text[text.len()..].starts_with(constant::TITLE_KEYWORD_SEPARATOR)
     }
 
-    pub fn get_table_cells(&self) -> Vec<LineSpan> {
-        let mut line_spans: Vec<LineSpan> = Vec::new();
-        let mut cell = String::new();
+    pub fn get_table_cells(&self) -> Vec<LineSpan<'s>> {
+        let trimmed_text = self.trimmed_text();
+        let mut line_spans = Vec::new();
+        // only used for cells containing escape sequences, the others are borrowed
+        let mut unescaped_cell = String::new();
+        let mut is_escaped = false;
+        let mut cell_start = 0;
         let mut before_first = true;
         let mut start_col = 0;
         let mut after_backslash = false;
 
-        for (col, c) in self.trimmed_text.chars().enumerate() {
+        for (col, (index, c)) in trimmed_text.char_indices().enumerate() {
             if after_backslash {
                 if c == 'n' {
-                    cell.push('\n');
+                    unescaped_cell.push('\n');
                 } else {
                     if c != '|' && c != '\\' {
-                        cell.push('\\');
+                        unescaped_cell.push('\\');
                     }
-                    cell.push(c);
+                    unescaped_cell.push(c);
                 }
 
                 after_backslash = false;
@@ -151,6 +197,11 @@ impl Line {
                     // Skip the first empty span
                     before_first = false;
                 } else {
+                    let cell = if is_escaped {
+                        unescaped_cell.as_str()
+                    } else {
+                        &trimmed_text[cell_start..index]
+                    };
                     let content_start = cell
                         .chars()
                         .enumerate()
@@ -162,32 +213,57 @@ impl Line {
                         .unwrap_or(0) as u32;
 
                     let column = self.indent() + start_col + content_start + 2;
-                    let text = cell
-                        .trim_matches(|c: char| c != '\n' && c.is_whitespace())
-                        .to_owned();
+                    let cell_text = cell.trim_matches(|c: char| c != '\n' && c.is_whitespace());
+                    let text = if is_escaped {
+                        Cow::Owned(cell_text.to_owned())
+                    } else {
+                        self.to_sub_text(cell_text)
+                    };
                     line_spans.push(LineSpan { column, text });
 
                     start_col = col as u32;
                 }
-                cell.clear();
+                unescaped_cell.clear();
+                is_escaped = false;
+                cell_start = index + c.len_utf8();
             } else if c == '\\' {
+                if !is_escaped {
+                    unescaped_cell.push_str(&trimmed_text[cell_start..index]);
+                    is_escaped = true;
+                }
                 after_backslash = true;
                 continue;
-            } else {
-                cell.push(c);
+            } else if is_escaped {
+                unescaped_cell.push(c);
             }
         }
 
         line_spans
     }
+
+    pub fn trimmed_text(&self) -> &str {
+        &self.text[self.trimmed_start..]
+    }
+
+    /// Returns a part of the line text as borrowed from the source if the line is borrowed,
+    /// or as an owned copy otherwise.
+    pub fn to_sub_text(&self, sub_text: &str) -> Cow<'s, str> {
+        match self.text {
+            Cow::Borrowed(text) => {
+                let start = sub_text.as_ptr() as usize - text.as_ptr() as usize;
+                Cow::Borrowed(&text[start..start + sub_text.len()])
+            }
+            Cow::Owned(_) => Cow::Owned(sub_text.to_owned()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn get_line_tags(line_text: &str) -> Result<Vec<LineSpan>> {
-        Line::new(line_text.to_string(), 12).get_tags()
+    fn get_line_tags(line_text: &str) -> Result<Vec<LineSpan<'_>>> {
+        Line::new(line_text, 12).get_tags()
     }
 
     #[test]
@@ -197,19 +273,19 @@ mod tests {
             vec![
                 LineSpan {
                     column: 1,
-                    text: "@this".to_owned(),
+                    text: "@this".into(),
                 },
                 LineSpan {
                     column: 7,
-                    text: "@is".to_owned()
+                    text: "@is".into()
                 },
                 LineSpan {
                     column: 11,
-                    text: "@a".to_owned()
+                    text: "@a".into()
                 },
                 LineSpan {
                     column: 14,
-                    text: "@tag".to_owned()
+                    text: "@tag".into()
                 },
             ]
         );
@@ -242,19 +318,19 @@ mod tests {
             vec![
                 LineSpan {
                     column: 5,
-                    text: "@this".to_owned(),
+                    text: "@this".into(),
                 },
                 LineSpan {
                     column: 11,
-                    text: "@is".to_owned()
+                    text: "@is".into()
                 },
                 LineSpan {
                     column: 16,
-                    text: "@a".to_owned()
+                    text: "@a".into()
                 },
                 LineSpan {
                     column: 19,
-                    text: "@tag".to_owned()
+                    text: "@tag".into()
                 },
             ]
         );
@@ -267,11 +343,11 @@ mod tests {
             vec![
                 LineSpan {
                     column: 1,
-                    text: "@this".to_owned(),
+                    text: "@this".into(),
                 },
                 LineSpan {
                     column: 7,
-                    text: "@is#not_a_comment".to_owned()
+                    text: "@is#not_a_comment".into()
                 },
             ]
         );
@@ -284,11 +360,11 @@ mod tests {
             vec![
                 LineSpan {
                     column: 1,
-                    text: "@this".to_owned(),
+                    text: "@this".into(),
                 },
                 LineSpan {
                     column: 7,
-                    text: "@is".to_owned()
+                    text: "@is".into()
                 },
             ]
         );
@@ -301,11 +377,11 @@ mod tests {
             vec![
                 LineSpan {
                     column: 1,
-                    text: "@this".to_owned(),
+                    text: "@this".into(),
                 },
                 LineSpan {
                     column: 7,
-                    text: "@is".to_owned()
+                    text: "@is".into()
                 },
             ]
         );
@@ -318,13 +394,96 @@ mod tests {
             vec![
                 LineSpan {
                     column: 1,
-                    text: "@this".to_owned(),
+                    text: "@this".into(),
                 },
                 LineSpan {
                     column: 7,
-                    text: "@is".to_owned()
+                    text: "@is".into()
                 },
             ]
         );
     }
+
+    #[test]
+    fn finds_tags_after_empty_tags_and_multi_byte_chars() {
+        assert_eq!(
+            get_line_tags("@@é@x").unwrap(),
+            vec![
+                LineSpan {
+                    column: 2,
+                    text: "@é".into(),
+                },
+                LineSpan {
+                    column: 4,
+                    text: "@x".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_tags_without_leading_tag_prefix() {
+        assert_eq!(
+            get_line_tags("  this @is").unwrap(),
+            vec![
+                LineSpan {
+                    column: 3,
+                    text: "@this".into(),
+                },
+                LineSpan {
+                    column: 8,
+                    text: "@is".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn borrows_tags_from_source() {
+        let tags = get_line_tags("  @this @is").unwrap();
+        assert!(tags.iter().all(|tag| match tag.text {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false,
+        }));
+    }
+
+    #[test]
+    fn finds_table_cells() {
+        let line = Line::new(r"  | a |  b\|c | \n | d\\e\x |", 3);
+        assert_eq!(
+            line.get_table_cells(),
+            vec![
+                LineSpan {
+                    column: 5,
+                    text: "a".into(),
+                },
+                LineSpan {
+                    column: 10,
+                    text: "b|c".into(),
+                },
+                LineSpan {
+                    column: 17,
+                    text: "\n".into(),
+                },
+                LineSpan {
+                    column: 22,
+                    text: "d\\e\\x".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn borrows_only_unescaped_table_cells() {
+        let line = Line::new(r"| plain | esc\|aped |", 1);
+        let is_borrowed: Vec<bool> = line
+            .get_table_cells()
+            .iter()
+            .map(|cell| match cell.text {
+                Cow::Borrowed(_) => true,
+                Cow::Owned(_) => false,
+            })
+            .collect();
+        assert_eq!(is_borrowed, vec![true, false]);
+    }
 }
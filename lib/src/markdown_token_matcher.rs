@@ -0,0 +1,568 @@
+use std::borrow::Cow;
+use std::default::Default;
+use std::sync::Arc;
+
+use crate::constant;
+use crate::dialect::Dialect;
+use crate::dialect_provider::BuiltInDialectProvider;
+use crate::error::Result;
+use crate::line::{Line, LineSpan};
+use crate::location::Location;
+use crate::parser::DialectProvider;
+use crate::parser::{TokenMatch, TokenType};
+use crate::token::Token;
+
+const STAR_STEP_KEYWORD: &str = "* ";
+const MIN_TABLE_ROW_INDENT: u32 = 2;
+const MAX_TABLE_ROW_INDENT: u32 = 5;
+
+/// Matches the tokens of Markdown with Gherkin (`text/x.cucumber.gherkin+markdown`).
+///
+/// Features, rules, backgrounds, scenarios and examples are headings (`## Scenario: title`),
+/// steps are list items (`* Given step`), tags are code spans (`` `@tag` ``), data tables and
+/// examples tables are tables indented by two to five spaces and doc strings are fenced code
+/// blocks. The separator rows of tables are comments and every other line is empty, so the
+/// prose around the scenarios is ignored.
+///
+/// The language cannot be changed by a `# language:` line, which is a heading in Markdown.
+pub struct MarkdownTokenMatcher<DP: DialectProvider> {
+    dialect_provider: DP,
+    current_dialect: Arc<Dialect>,
+    active_doc_string_separator: Option<String>,
+    indent_to_remove: u32,
+}
+
+impl Default for MarkdownTokenMatcher<BuiltInDialectProvider> {
+    fn default() -> MarkdownTokenMatcher<BuiltInDialectProvider> {
+        MarkdownTokenMatcher::with_dialect_provider(BuiltInDialectProvider::default())
+    }
+}
+
+impl MarkdownTokenMatcher<BuiltInDialectProvider> {
+    pub fn with_default_dialect_name<S>(default: S) -> MarkdownTokenMatcher<BuiltInDialectProvider>
+    where
+        S: Into<String>,
+    {
+        let dialect_provider = BuiltInDialectProvider::with_default_dialect_name(default);
+        MarkdownTokenMatcher::with_dialect_provider(dialect_provider)
+    }
+}
+
+impl<DP: DialectProvider> MarkdownTokenMatcher<DP> {
+    pub fn with_dialect_provider(dialect_provider: DP) -> MarkdownTokenMatcher<DP> {
+        let default_dialect = dialect_provider
+            .get_default_dialect()
+            .expect("get default dialect");
+
+        MarkdownTokenMatcher {
+            dialect_provider,
+            active_doc_string_separator: None,
+            indent_to_remove: 0,
+            current_dialect: default_dialect,
+        }
+    }
+
+    fn set_token_matched<'s>(
+        &self,
+        token: &mut Token<'s>,
+        matched_type: TokenType,
+        text: Option<Cow<'s, str>>,
+        keyword: Option<Cow<'s, str>>,
+        indent: Option<u32>,
+        items: Vec<LineSpan<'s>>,
+    ) {
+        token.matched_type = Some(matched_type);
+        token.matched_keyword = keyword;
+        token.matched_text = text;
+        token.matched_items = items;
+        token.matched_dialect = Some(self.current_dialect.clone());
+        token.matched_indent = indent.or_else(|| match token.line {
+            Some(ref line) => Some(line.indent()),
+            None => Some(0),
+        });
+        let token_location = {
+            let location = token.location.as_ref().expect("token location");
+            let line = location.line;
+            let column = token.matched_indent.unwrap() + 1;
+            Location::new(line, column)
+        };
+        token.location = Some(token_location);
+    }
+
+    /// Returns the length of the heading prefix and the longest title keyword
+    /// the heading starts with.
+    fn find_title_keyword<'k, K>(&self, line: &Line<'_>, keywords: K) -> Option<(usize, &'k str)>
+    where
+        K: IntoIterator<Item = &'k String>,
+    {
+        let text = line.trimmed_text();
+        let prefix_len = heading_prefix_len(text)?;
+        let heading = &text[prefix_len..];
+
+        // a keyword may be a prefix of another one, so the longest matching keyword wins
+        let keyword = keywords
+            .into_iter()
+            .filter(|keyword| {
+                heading.starts_with(keyword.as_str())
+                    && heading[keyword.len()..].starts_with(constant::TITLE_KEYWORD_SEPARATOR)
+            })
+            .max_by_key(|keyword| keyword.len())?;
+
+        Some((prefix_len, keyword.as_str()))
+    }
+
+    /// Returns the length of the list item prefix and the step keyword the item starts with.
+    fn find_step_keyword(&self, line: &Line<'_>) -> Option<(usize, &str)> {
+        let text = line.trimmed_text();
+        let prefix_len = bullet_prefix_len(text)?;
+        let item = &text[prefix_len..];
+
+        // ordered longest keyword first, a bullet on its own is no step
+        self.current_dialect
+            .get_step_keywords()
+            .iter()
+            .filter(|keyword| keyword.as_str() != STAR_STEP_KEYWORD)
+            .find(|keyword| item.starts_with(keyword.as_str()))
+            .map(|keyword| (prefix_len, keyword.as_str()))
+    }
+
+    fn match_title_line<'k, K>(
+        &self,
+        token: &mut Token<'_>,
+        token_type: TokenType,
+        keywords: K,
+    ) -> bool
+    where
+        K: IntoIterator<Item = &'k String>,
+    {
+        let (title, keyword, indent) = {
+            let line = token.unwrap_line();
+            let (prefix_len, keyword) = match self.find_title_keyword(line, keywords) {
+                Some(title_keyword) => title_keyword,
+                None => return false,
+            };
+            let text = line.trimmed_text();
+            let heading = &text[prefix_len..];
+            let title = heading[keyword.len() + constant::TITLE_KEYWORD_SEPARATOR.len()..].trim();
+            (
+                line.to_sub_text(title),
+                line.to_sub_text(&heading[..keyword.len()]),
+                line.indent() + text[..prefix_len].chars().count() as u32,
+            )
+        };
+
+        self.set_token_matched(
+            token,
+            token_type,
+            Some(title),
+            Some(keyword),
+            Some(indent),
+            Vec::new(),
+        );
+        true
+    }
+
+    /// Returns the separator a line opens or closes a doc string with.
+    fn find_doc_string_separator<'l>(&self, line: &'l Line<'_>) -> Option<&'l str> {
+        let text = line.trimmed_text();
+        match &self.active_doc_string_separator {
+            Some(separator) if text.trim_end() == separator => Some(text.trim_end()),
+            Some(_) => None,
+            None if text.starts_with(constant::DOCSTRING_ALTERNATIVE_SEPARATOR) => {
+                let separator_len = text.len() - text.trim_start_matches('`').len();
+                Some(&text[..separator_len])
+            }
+            None => None,
+        }
+    }
+
+    /// Whether a line would be matched as anything else than empty or other, without
+    /// changing the state of the matcher.
+    fn is_gherkin_line(&self, line: &Line<'_>) -> bool {
+        let dialect = &self.current_dialect;
+        let title_keywords = dialect
+            .get_feature_keywords()
+            .iter()
+            .chain(dialect.get_rule_keywords())
+            .chain(dialect.get_background_keywords())
+            .chain(dialect.get_scenario_keywords())
+            .chain(dialect.get_scenario_outline_keywords())
+            .chain(dialect.get_examples_keywords());
+
+        is_table_row(line)
+            || is_table_separator_row(line)
+            || !find_tags(line).is_empty()
+            || self.find_doc_string_separator(line).is_some()
+            || self.find_step_keyword(line).is_some()
+            || self.find_title_keyword(line, title_keywords).is_some()
+    }
+}
+
+impl<DP: DialectProvider> TokenMatch for MarkdownTokenMatcher<DP> {
+    fn match_eof(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        if token.is_eof() {
+            self.set_token_matched(token, TokenType::Eof, None, None, None, Vec::new());
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn match_empty(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        // prose is ignored like empty lines
+        let line = token.unwrap_line();
+        if line.is_empty() || !self.is_gherkin_line(line) {
+            self.set_token_matched(token, TokenType::Empty, None, None, None, Vec::new());
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn match_comment(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        if is_table_separator_row(token.unwrap_line()) {
+            // take the entire line
+            let text = token.unwrap_line().to_text(0);
+            self.set_token_matched(
+                token,
+                TokenType::Comment,
+                Some(text),
+                None,
+                Some(0),
+                Vec::new(),
+            );
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn match_tag_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let tags = find_tags(token.unwrap_line());
+        if !tags.is_empty() {
+            self.set_token_matched(token, TokenType::TagLine, None, None, None, tags);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn match_feature_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let keywords = self.current_dialect.get_feature_keywords();
+        let is_match = self.match_title_line(token, TokenType::FeatureLine, keywords);
+        Ok(is_match)
+    }
+
+    fn match_rule_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let keywords = self.current_dialect.get_rule_keywords();
+        let is_match = self.match_title_line(token, TokenType::RuleLine, keywords);
+        Ok(is_match)
+    }
+
+    fn match_background_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let keywords = self.current_dialect.get_background_keywords();
+        let is_match = self.match_title_line(token, TokenType::BackgroundLine, keywords);
+        Ok(is_match)
+    }
+
+    fn match_scenario_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let keywords = self
+            .current_dialect
+            .get_scenario_keywords()
+            .iter()
+            .chain(self.current_dialect.get_scenario_outline_keywords());
+        let is_match = self.match_title_line(token, TokenType::ScenarioLine, keywords);
+        Ok(is_match)
+    }
+
+    fn match_examples_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let keywords = self.current_dialect.get_examples_keywords();
+        let is_match = self.match_title_line(token, TokenType::ExamplesLine, keywords);
+        Ok(is_match)
+    }
+
+    fn match_step_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let (step_text, matched_keyword, keyword_type, indent) = {
+            let line = token.unwrap_line();
+            let (prefix_len, keyword) = match self.find_step_keyword(line) {
+                Some(step_keyword) => step_keyword,
+                None => return Ok(false),
+            };
+            let text = line.trimmed_text();
+            let item = &text[prefix_len..];
+            (
+                line.to_sub_text(item[keyword.len()..].trim()),
+                line.to_sub_text(&item[..keyword.len()]),
+                self.current_dialect.get_step_keyword_type(keyword),
+                line.indent() + text[..prefix_len].chars().count() as u32,
+            )
+        };
+
+        self.set_token_matched(
+            token,
+            TokenType::StepLine,
+            Some(step_text),
+            Some(matched_keyword),
+            Some(indent),
+            Vec::new(),
+        );
+        token.matched_keyword_type = Some(keyword_type);
+        Ok(true)
+    }
+
+    fn match_doc_string_separator(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let (separator, content_type) = {
+            let line = token.unwrap_line();
+            let separator = match self.find_doc_string_separator(line) {
+                Some(separator) => separator,
+                None => return Ok(false),
+            };
+
+            if self.active_doc_string_separator.is_some() {
+                // close
+                self.active_doc_string_separator = None;
+                self.indent_to_remove = 0;
+                (line.to_sub_text(separator), None)
+            } else {
+                // open
+                self.active_doc_string_separator = Some(separator.to_owned());
+                self.indent_to_remove = line.indent();
+                let content_type = line.trimmed_text()[separator.len()..].trim();
+                (
+                    line.to_sub_text(separator),
+                    Some(line.to_sub_text(content_type)),
+                )
+            }
+        };
+
+        self.set_token_matched(
+            token,
+            TokenType::DocStringSeparator,
+            content_type,
+            Some(separator),
+            None,
+            Vec::new(),
+        );
+        Ok(true)
+    }
+
+    fn match_table_row(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        if is_table_row(token.unwrap_line()) {
+            let table_cells = token.unwrap_line().get_table_cells();
+            self.set_token_matched(token, TokenType::TableRow, None, None, None, table_cells);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn match_language(&mut self, _token: &mut Token<'_>) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn match_other(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        // take the entire line, except removing DocString indents
+        let text = token.unwrap_line().to_text(self.indent_to_remove as isize);
+        self.set_token_matched(
+            token,
+            TokenType::Other,
+            Some(text),
+            None,
+            Some(0),
+            Vec::new(),
+        );
+        Ok(true)
+    }
+
+    fn reset(&mut self) {
+        self.active_doc_string_separator = None;
+        self.indent_to_remove = 0;
+        self.current_dialect = self
+            .dialect_provider
+            .get_default_dialect()
+            .expect("get default dialect");
+    }
+}
+
+/// Returns the length of the `#` to `######` prefix of a heading, including the whitespace
+/// after it.
+fn heading_prefix_len(text: &str) -> Option<usize> {
+    let level = text.len() - text.trim_start_matches('#').len();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    whitespace_suffix_len(text, level)
+}
+
+/// Returns the length of the `*`, `+` or `-` prefix of a list item, including the whitespace
+/// after it.
+fn bullet_prefix_len(text: &str) -> Option<usize> {
+    if !text.starts_with(|c| c == '*' || c == '+' || c == '-') {
+        return None;
+    }
+    whitespace_suffix_len(text, 1)
+}
+
+fn whitespace_suffix_len(text: &str, prefix_len: usize) -> Option<usize> {
+    let rest = &text[prefix_len..];
+    let whitespace_len = rest.len() - rest.trim_start().len();
+    if whitespace_len == 0 {
+        return None;
+    }
+    Some(prefix_len + whitespace_len)
+}
+
+/// Finds the tags of a line, which are code spans starting with the tag prefix.
+fn find_tags<'s>(line: &Line<'s>) -> Vec<LineSpan<'s>> {
+    let text = line.trimmed_text();
+    let mut tags = Vec::new();
+    let mut search_start = 0;
+
+    while let Some(code_start) = text[search_start..].find('`') {
+        let tag_start = search_start + code_start + 1;
+        let tag_end = match text[tag_start..].find('`') {
+            Some(tag_len) => tag_start + tag_len,
+            None => break,
+        };
+
+        let tag = &text[tag_start..tag_end];
+        if tag.starts_with(constant::TAG_PREFIX) && tag.len() > constant::TAG_PREFIX.len() {
+            let column = line.indent() + text[..tag_start].chars().count() as u32 + 1;
+            let text = line.to_sub_text(tag);
+            tags.push(LineSpan { column, text });
+            search_start = tag_end + 1;
+        } else {
+            // the closing backtick may open the next code span
+            search_start = tag_end;
+        }
+    }
+
+    tags
+}
+
+/// Whether a line is a row of a table which is indented like the continuation of a list item,
+/// as tables which are not are no Gherkin.
+fn is_table_row(line: &Line<'_>) -> bool {
+    let indent = line.indent();
+    indent >= MIN_TABLE_ROW_INDENT
+        && indent <= MAX_TABLE_ROW_INDENT
+        && line.starts_with(constant::TABLE_CELL_SEPARATOR)
+        && !is_table_separator_row(line)
+}
+
+/// Whether a line is the row separating the header of a Markdown table from its body.
+fn is_table_separator_row(line: &Line<'_>) -> bool {
+    if !line.starts_with(constant::TABLE_CELL_SEPARATOR) {
+        return false;
+    }
+    let table_cells = line.get_table_cells();
+    if table_cells.is_empty() {
+        return false;
+    }
+    table_cells.iter().all(|table_cell| {
+        let text = table_cell.text.as_ref();
+        let text = if text.starts_with(':') {
+            &text[1..]
+        } else {
+            text
+        };
+        let text = if text.ends_with(':') {
+            &text[..text.len() - 1]
+        } else {
+            text
+        };
+        !text.is_empty() && text.chars().all(|c| c == '-')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_headings_with_title_keywords() {
+        let mut token_matcher = MarkdownTokenMatcher::default();
+
+        let mut token = create_token("## Scenario Outline: eating cukes");
+        assert!(token_matcher.match_scenario_line(&mut token).unwrap());
+        assert_eq!(token.matched_keyword.as_deref(), Some("Scenario Outline"));
+        assert_eq!(token.matched_text.as_deref(), Some("eating cukes"));
+        assert_eq!(token.location.unwrap().column, 4);
+
+        let mut token = create_token("Scenario: not a heading");
+        assert!(!token_matcher.match_scenario_line(&mut token).unwrap());
+        let mut token = create_token("####### Scenario: too deep");
+        assert!(!token_matcher.match_scenario_line(&mut token).unwrap());
+    }
+
+    #[test]
+    fn matches_list_items_with_step_keywords() {
+        let mut token_matcher = MarkdownTokenMatcher::default();
+
+        let mut token = create_token("  - Given I have 3 cukes");
+        assert!(token_matcher.match_step_line(&mut token).unwrap());
+        assert_eq!(token.matched_keyword.as_deref(), Some("Given "));
+        assert_eq!(token.matched_text.as_deref(), Some("I have 3 cukes"));
+        assert_eq!(token.location.unwrap().column, 5);
+
+        let mut token = create_token("* some list item");
+        assert!(!token_matcher.match_step_line(&mut token).unwrap());
+        assert!(token_matcher.match_empty(&mut token).unwrap());
+    }
+
+    #[test]
+    fn matches_code_spans_as_tags() {
+        let mut token_matcher = MarkdownTokenMatcher::default();
+        let mut token = create_token("  `@smoke` `code` `@slow`");
+
+        assert!(token_matcher.match_tag_line(&mut token).unwrap());
+        let tags: Vec<_> = token
+            .matched_items
+            .iter()
+            .map(|tag| (tag.column, tag.text.as_ref()))
+            .collect();
+        assert_eq!(tags, vec![(4, "@smoke"), (20, "@slow")]);
+    }
+
+    #[test]
+    fn matches_indented_tables_without_separator_rows() {
+        let mut token_matcher = MarkdownTokenMatcher::default();
+
+        let mut token = create_token("  | name | count |");
+        assert!(token_matcher.match_table_row(&mut token).unwrap());
+        assert_eq!(token.matched_items.len(), 2);
+
+        let mut token = create_token("  | ---- | ----: |");
+        assert!(!token_matcher.match_table_row(&mut token).unwrap());
+        assert!(token_matcher.match_comment(&mut token).unwrap());
+
+        let mut token = create_token("  | name | --- | :-: |");
+        assert!(token_matcher.match_table_row(&mut token).unwrap());
+        assert_eq!(token.matched_items.len(), 3);
+
+        let mut token = create_token("| not | gherkin |");
+        assert!(!token_matcher.match_table_row(&mut token).unwrap());
+    }
+
+    #[test]
+    fn matches_fenced_code_blocks_as_doc_strings() {
+        let mut token_matcher = MarkdownTokenMatcher::default();
+
+        let mut token = create_token("  ````json");
+        assert!(token_matcher
+            .match_doc_string_separator(&mut token)
+            .unwrap());
+        assert_eq!(token.matched_keyword.as_deref(), Some("````"));
+        assert_eq!(token.matched_text.as_deref(), Some("json"));
+
+        let mut token = create_token("  ```");
+        assert!(!token_matcher
+            .match_doc_string_separator(&mut token)
+            .unwrap());
+        let mut token = create_token("  ````");
+        assert!(token_matcher
+            .match_doc_string_separator(&mut token)
+            .unwrap());
+        assert_eq!(token.matched_text, None);
+    }
+
+    fn create_token(text: &str) -> Token<'_> {
+        let line = Line::new(text, 1);
+        Token::new(Some(line), Some(Location::new(1, 0)))
+    }
+}
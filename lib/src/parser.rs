@@ -9,14 +9,17 @@ use std::fmt;
 use std::io::Read;
 use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
 use crate::dialect::Dialect;
 use crate::error::{Error, Result};
 use crate::location::Location;
+use crate::markdown_token_matcher::MarkdownTokenMatcher;
 use crate::token::Token;
 use crate::token_matcher::TokenMatcher;
-use crate::token_scanner::TokenScanner;
+use crate::token_scanner::{StrTokenScanner, TokenScanner};
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum TokenType {
     None,
     Eof,
@@ -144,6 +147,19 @@ impl<B: Builder> ParserOptions<B> {
         self.token_matcher(TokenMatcher::with_dialect_provider(dialect_provider))
     }
 
+    /// Parses Markdown with Gherkin instead of plain Gherkin.
+    pub fn markdown(self) -> ParserOptions<B> {
+        self.token_matcher(MarkdownTokenMatcher::default())
+    }
+
+    /// Parses Markdown with Gherkin in the given language.
+    pub fn markdown_language<S>(self, language: S) -> ParserOptions<B>
+    where
+        S: Into<String>,
+    {
+        self.token_matcher(MarkdownTokenMatcher::with_default_dialect_name(language))
+    }
+
     pub(crate) fn token_matcher<TM>(mut self, token_match: TM) -> ParserOptions<B>
     where
         TM: TokenMatch + 'static,
@@ -168,9 +184,9 @@ impl<B: Builder> ParserOptions<B> {
     }
 }
 
-struct ParserContext<'a> {
-    token_scan: &'a mut dyn TokenScan,
-    token_queue: VecDeque<Token>,
+struct ParserContext<'a, 's> {
+    token_scan: &'a mut dyn TokenScan<'s>,
+    token_queue: VecDeque<Token<'s>>,
     errors: Vec<Error>,
 }
 
@@ -183,15 +199,23 @@ impl<B: Builder> Parser<B> {
         &mut self.builder
     }
 
+    /// Replaces the token matcher and returns the previous one.
+    pub(crate) fn replace_token_match(
+        &mut self,
+        token_match: Box<dyn TokenMatch>,
+    ) -> Box<dyn TokenMatch> {
+        std::mem::replace(&mut self.token_match, token_match)
+    }
+
     pub fn parse_str<S: AsRef<str>>(&mut self, source: S) -> Result<B::BuilderResult> {
-        self.parse(&mut TokenScanner::from(source.as_ref()))
+        self.parse(&mut StrTokenScanner::from(source.as_ref()))
     }
 
     pub fn parse_reader<R: Read>(&mut self, source: R) -> Result<B::BuilderResult> {
         self.parse(&mut TokenScanner::from(source))
     }
 
-    fn parse<TS: TokenScan>(&mut self, token_scan: &mut TS) -> Result<B::BuilderResult> {
+    fn parse<'s, TS: TokenScan<'s>>(&mut self, token_scan: &mut TS) -> Result<B::BuilderResult> {
         self.builder.reset();
         self.token_match.reset();
 
@@ -233,10 +257,10 @@ impl<B: Builder> Parser<B> {
             return Err(Error::Composite(context.errors));
         }
 
-        Ok(self.builder.get_result())
+        self.builder.get_result()
     }
 
-    fn add_error(&mut self, context: &mut ParserContext<'_>, error: Error) {
+    fn add_error(&mut self, context: &mut ParserContext<'_, '_>, error: Error) {
         context.errors.push(error);
         // if (context.errors.size() > 10)
         //     throw new ParserException.CompositeParserException(context.errors);
@@ -244,7 +268,7 @@ impl<B: Builder> Parser<B> {
 
     fn handle_ast_result(
         &mut self,
-        context: &mut ParserContext<'_>,
+        context: &mut ParserContext<'_, '_>,
         result: Result<()>,
     ) -> Result<()> {
         self.handle_external_result(context, result, ())
@@ -252,7 +276,7 @@ impl<B: Builder> Parser<B> {
 
     fn handle_external_result<V>(
         &mut self,
-        context: &mut ParserContext<'_>,
+        context: &mut ParserContext<'_, '_>,
         result: Result<V>,
         default_value: V,
     ) -> Result<V> {
@@ -275,22 +299,26 @@ impl<B: Builder> Parser<B> {
         Ok(default_value)
     }
 
-    fn build(&mut self, context: &mut ParserContext<'_>, token: Token) -> Result<()> {
+    fn build(&mut self, context: &mut ParserContext<'_, '_>, token: Token<'_>) -> Result<()> {
         let result = self.builder.build(token);
         self.handle_ast_result(context, result)
     }
 
-    fn start_rule(&mut self, context: &mut ParserContext<'_>, rule_type: RuleType) -> Result<()> {
+    fn start_rule(
+        &mut self,
+        context: &mut ParserContext<'_, '_>,
+        rule_type: RuleType,
+    ) -> Result<()> {
         let result = self.builder.start_rule(rule_type);
         self.handle_ast_result(context, result)
     }
 
-    fn end_rule(&mut self, context: &mut ParserContext<'_>, rule_type: RuleType) -> Result<()> {
+    fn end_rule(&mut self, context: &mut ParserContext<'_, '_>, rule_type: RuleType) -> Result<()> {
         let result = self.builder.end_rule(rule_type);
         self.handle_ast_result(context, result)
     }
 
-    fn read_token(&mut self, context: &mut ParserContext<'_>) -> Result<Token> {
+    fn read_token<'s>(&mut self, context: &mut ParserContext<'_, 's>) -> Result<Token<'s>> {
         match context.token_queue.pop_front() {
             Some(token) => Ok(token),
             None => context.token_scan.next(),
@@ -300,8 +328,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_eof(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         let result = self.token_match.match_eof(token);
         self.handle_external_result(context, result, false)
@@ -310,8 +338,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_empty(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -323,8 +351,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_comment(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -336,8 +364,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_tag_line(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -349,8 +377,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_feature_line(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -362,8 +390,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_rule_line(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -375,8 +403,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_background_line(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -388,8 +416,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_scenario_line(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -401,8 +429,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_examples_line(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -414,8 +442,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_step_line(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -427,8 +455,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_doc_string_separator(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -440,8 +468,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_table_row(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -453,8 +481,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_language(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -466,8 +494,8 @@ impl<B: Builder> Parser<B> {
     #[rustfmt::skip] // because the generated lengths differ
     fn match_other(
         &mut self,
-        context: &mut ParserContext<'_>,
-        token: &mut Token,
+        context: &mut ParserContext<'_, '_>,
+        token: &mut Token<'_>,
     ) -> Result<bool> {
         if token.is_eof() {
             return Ok(false);
@@ -479,8 +507,8 @@ impl<B: Builder> Parser<B> {
     fn match_token(
         &mut self,
         state: u32,
-        token: Token,
-        context: &mut ParserContext<'_>,
+        token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         match state {
             0 => self.match_token_at_0(token, context),
@@ -539,8 +567,8 @@ impl<B: Builder> Parser<B> {
     // Start
     fn match_token_at_0(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.build(context, token)?;
@@ -610,7 +638,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -626,8 +654,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:0>FeatureHeader:0>#Language:0
     fn match_token_at_1(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_tag_line(context, &mut token)? {
             self.start_rule(context, RuleType::Tags)?;
@@ -681,7 +709,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -697,8 +725,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:0>FeatureHeader:1>Tags:0>#TagLine:0
     fn match_token_at_2(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_tag_line(context, &mut token)? {
             self.build(context, token)?;
@@ -752,7 +780,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -768,8 +796,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:0>FeatureHeader:2>#FeatureLine:0
     fn match_token_at_3(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::FeatureHeader)?;
@@ -856,7 +884,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -872,8 +900,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:0>FeatureHeader:3>DescriptionHelper:1>Description:0>#Other:0
     fn match_token_at_4(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Description)?;
@@ -960,7 +988,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -976,8 +1004,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:0>FeatureHeader:3>DescriptionHelper:2>#Comment:0
     fn match_token_at_5(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::FeatureHeader)?;
@@ -1058,7 +1086,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1074,8 +1102,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:1>Background:0>#BackgroundLine:0
     fn match_token_at_6(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Background)?;
@@ -1161,7 +1189,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1177,8 +1205,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:1>Background:1>DescriptionHelper:1>Description:0>#Other:0
     fn match_token_at_7(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Description)?;
@@ -1264,7 +1292,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1280,8 +1308,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:1>Background:1>DescriptionHelper:2>#Comment:0
     fn match_token_at_8(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Background)?;
@@ -1361,7 +1389,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1377,8 +1405,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:1>Background:2>Step:0>#StepLine:0
     fn match_token_at_9(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Step)?;
@@ -1475,7 +1503,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1491,8 +1519,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:1>Background:2>Step:1>StepArg:0>__alt0:0>DataTable:0>#TableRow:0
     fn match_token_at_10(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::DataTable)?;
@@ -1587,7 +1615,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1603,8 +1631,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:0>Tags:0>#TagLine:0
     fn match_token_at_11(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_tag_line(context, &mut token)? {
             self.build(context, token)?;
@@ -1659,7 +1687,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1675,8 +1703,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:0>#ScenarioLine:0
     fn match_token_at_12(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Scenario)?;
@@ -1781,7 +1809,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1797,8 +1825,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:1>DescriptionHelper:1>Description:0>#Other:0
     fn match_token_at_13(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Description)?;
@@ -1905,7 +1933,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -1921,8 +1949,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:1>DescriptionHelper:2>#Comment:0
     fn match_token_at_14(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Scenario)?;
@@ -2021,7 +2049,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -2037,8 +2065,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:2>Step:0>#StepLine:0
     fn match_token_at_15(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Step)?;
@@ -2156,7 +2184,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -2172,8 +2200,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:2>Step:1>StepArg:0>__alt0:0>DataTable:0>#TableRow:0
     fn match_token_at_16(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::DataTable)?;
@@ -2291,7 +2319,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -2307,8 +2335,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:0>Tags:0>#TagLine:0
     fn match_token_at_17(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_tag_line(context, &mut token)? {
             self.build(context, token)?;
@@ -2363,7 +2391,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -2379,8 +2407,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:1>Examples:0>#ExamplesLine:0
     fn match_token_at_18(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Examples)?;
@@ -2497,7 +2525,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -2513,8 +2541,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:1>Examples:1>DescriptionHelper:1>Description:0>#Other:0
     fn match_token_at_19(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Description)?;
@@ -2633,7 +2661,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -2649,8 +2677,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:1>Examples:1>DescriptionHelper:2>#Comment:0
     fn match_token_at_20(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Examples)?;
@@ -2761,7 +2789,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -2777,8 +2805,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:1>Examples:2>ExamplesTable:0>#TableRow:0
     fn match_token_at_21(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::ExamplesTable)?;
@@ -2894,7 +2922,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -2910,8 +2938,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:0>RuleHeader:0>#RuleLine:0
     fn match_token_at_22(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::RuleHeader)?;
@@ -3000,7 +3028,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3016,8 +3044,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:0>RuleHeader:1>DescriptionHelper:1>Description:0>#Other:0
     fn match_token_at_23(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Description)?;
@@ -3106,7 +3134,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3122,8 +3150,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:0>RuleHeader:1>DescriptionHelper:2>#Comment:0
     fn match_token_at_24(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::RuleHeader)?;
@@ -3206,7 +3234,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3222,8 +3250,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:1>Background:0>#BackgroundLine:0
     fn match_token_at_25(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Background)?;
@@ -3311,7 +3339,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3327,8 +3355,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:1>Background:1>DescriptionHelper:1>Description:0>#Other:0
     fn match_token_at_26(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Description)?;
@@ -3416,7 +3444,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3432,8 +3460,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:1>Background:1>DescriptionHelper:2>#Comment:0
     fn match_token_at_27(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Background)?;
@@ -3515,7 +3543,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3531,8 +3559,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:1>Background:2>Step:0>#StepLine:0
     fn match_token_at_28(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Step)?;
@@ -3631,7 +3659,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3647,8 +3675,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:1>Background:2>Step:1>StepArg:0>__alt0:0>DataTable:0>#TableRow:0
     fn match_token_at_29(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::DataTable)?;
@@ -3745,7 +3773,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3761,8 +3789,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:0>Tags:0>#TagLine:0
     fn match_token_at_30(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_tag_line(context, &mut token)? {
             self.build(context, token)?;
@@ -3817,7 +3845,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3833,8 +3861,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:0>#ScenarioLine:0
     fn match_token_at_31(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Scenario)?;
@@ -3941,7 +3969,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -3957,8 +3985,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:1>DescriptionHelper:1>Description:0>#Other:0
     fn match_token_at_32(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Description)?;
@@ -4067,7 +4095,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -4083,8 +4111,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:1>DescriptionHelper:2>#Comment:0
     fn match_token_at_33(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Scenario)?;
@@ -4185,7 +4213,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -4201,8 +4229,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:2>Step:0>#StepLine:0
     fn match_token_at_34(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Step)?;
@@ -4322,7 +4350,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -4338,8 +4366,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:2>Step:1>StepArg:0>__alt0:0>DataTable:0>#TableRow:0
     fn match_token_at_35(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::DataTable)?;
@@ -4459,7 +4487,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -4475,8 +4503,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:0>Tags:0>#TagLine:0
     fn match_token_at_36(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_tag_line(context, &mut token)? {
             self.build(context, token)?;
@@ -4531,7 +4559,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -4547,8 +4575,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:1>Examples:0>#ExamplesLine:0
     fn match_token_at_37(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Examples)?;
@@ -4667,7 +4695,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -4683,8 +4711,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:1>Examples:1>DescriptionHelper:1>Description:0>#Other:0
     fn match_token_at_38(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Description)?;
@@ -4805,7 +4833,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -4821,8 +4849,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:1>Examples:1>DescriptionHelper:2>#Comment:0
     fn match_token_at_39(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::Examples)?;
@@ -4935,7 +4963,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -4951,8 +4979,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:3>ExamplesDefinition:1>Examples:2>ExamplesTable:0>#TableRow:0
     fn match_token_at_40(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::ExamplesTable)?;
@@ -5070,7 +5098,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5086,8 +5114,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:2>Step:1>StepArg:0>__alt0:1>DocString:0>#DocStringSeparator:0
     fn match_token_at_42(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_doc_string_separator(context, &mut token)? {
             self.build(context, token)?;
@@ -5130,7 +5158,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5146,8 +5174,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:2>ScenarioDefinition:1>Scenario:2>Step:1>StepArg:0>__alt0:1>DocString:2>#DocStringSeparator:0
     fn match_token_at_43(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::DocString)?;
@@ -5262,7 +5290,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5278,8 +5306,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:1>Background:2>Step:1>StepArg:0>__alt0:1>DocString:0>#DocStringSeparator:0
     fn match_token_at_44(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_doc_string_separator(context, &mut token)? {
             self.build(context, token)?;
@@ -5322,7 +5350,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5338,8 +5366,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:3>Rule:1>Background:2>Step:1>StepArg:0>__alt0:1>DocString:2>#DocStringSeparator:0
     fn match_token_at_45(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::DocString)?;
@@ -5431,7 +5459,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5447,8 +5475,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:2>Step:1>StepArg:0>__alt0:1>DocString:0>#DocStringSeparator:0
     fn match_token_at_46(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_doc_string_separator(context, &mut token)? {
             self.build(context, token)?;
@@ -5491,7 +5519,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5507,8 +5535,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:2>ScenarioDefinition:1>Scenario:2>Step:1>StepArg:0>__alt0:1>DocString:2>#DocStringSeparator:0
     fn match_token_at_47(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::DocString)?;
@@ -5621,7 +5649,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5637,8 +5665,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:1>Background:2>Step:1>StepArg:0>__alt0:1>DocString:0>#DocStringSeparator:0
     fn match_token_at_48(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_doc_string_separator(context, &mut token)? {
             self.build(context, token)?;
@@ -5681,7 +5709,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5697,8 +5725,8 @@ impl<B: Builder> Parser<B> {
     // GherkinDocument:0>Feature:1>Background:2>Step:1>StepArg:0>__alt0:1>DocString:2>#DocStringSeparator:0
     fn match_token_at_49(
         &mut self,
-        mut token: Token,
-        context: &mut ParserContext<'_>,
+        mut token: Token<'_>,
+        context: &mut ParserContext<'_, '_>,
     ) -> Result<u32> {
         if self.match_eof(context, &mut token)? {
             self.end_rule(context, RuleType::DocString)?;
@@ -5788,7 +5816,7 @@ impl<B: Builder> Parser<B> {
             Error::UnexpectedToken {
                 location,
                 state_comment,
-                received_token: Box::new(token),
+                received_token: Box::new(token.into_owned()),
                 expected_tokens,
             }
         };
@@ -5803,7 +5831,7 @@ impl<B: Builder> Parser<B> {
 
     #[allow(clippy::nonminimal_bool)] // simplifies the parser template
     #[rustfmt::skip] // simplifies the parser template
-    fn lookahead_0(&mut self, context: &mut ParserContext<'_>, current_token: &Token) -> bool {
+    fn lookahead_0(&mut self, context: &mut ParserContext<'_, '_>, current_token: &Token<'_>) -> bool {
         current_token.detach();
         let mut token: Token;
         let mut queue: VecDeque<Token> = VecDeque::new();
@@ -5838,32 +5866,32 @@ impl<B: Builder> Parser<B> {
 pub trait Builder {
     type BuilderResult;
 
-    fn build(&mut self, token: Token) -> Result<()>;
+    fn build(&mut self, token: Token<'_>) -> Result<()>;
     fn start_rule(&mut self, rule_type: RuleType) -> Result<()>;
     fn end_rule(&mut self, rule_type: RuleType) -> Result<()>;
-    fn get_result(&mut self) -> Self::BuilderResult;
+    fn get_result(&mut self) -> Result<Self::BuilderResult>;
     fn reset(&mut self);
 }
 
-pub trait TokenScan {
-    fn next(&mut self) -> Result<Token>;
+pub trait TokenScan<'s> {
+    fn next(&mut self) -> Result<Token<'s>>;
 }
 
 pub trait TokenMatch {
-    fn match_eof(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_empty(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_comment(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_tag_line(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_feature_line(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_rule_line(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_background_line(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_scenario_line(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_examples_line(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_step_line(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_doc_string_separator(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_table_row(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_language(&mut self, token: &mut Token) -> Result<bool>;
-    fn match_other(&mut self, token: &mut Token) -> Result<bool>;
+    fn match_eof(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_empty(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_comment(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_tag_line(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_feature_line(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_rule_line(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_background_line(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_scenario_line(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_examples_line(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_step_line(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_doc_string_separator(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_table_row(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_language(&mut self, token: &mut Token<'_>) -> Result<bool>;
+    fn match_other(&mut self, token: &mut Token<'_>) -> Result<bool>;
     fn reset(&mut self);
 }
 
@@ -0,0 +1,430 @@
+use std::collections::HashMap;
+
+use cucumber_messages::ast;
+use cucumber_messages::pickle;
+use cucumber_messages::{Envelope, Message};
+
+/// A node of a `GherkinDocument`, which can be looked up by its id except for the feature.
+#[derive(Debug, Copy, Clone)]
+pub enum AstNode<'d> {
+    Feature(&'d ast::Feature),
+    Rule(&'d ast::Rule),
+    Background(&'d ast::Background),
+    Scenario(&'d ast::Scenario),
+    Examples(&'d ast::Examples),
+    TableRow(&'d ast::TableRow),
+    Step(&'d ast::Step),
+    Tag(&'d ast::Tag),
+}
+
+impl<'d> AstNode<'d> {
+    pub fn id(&self) -> Option<&'d str> {
+        match *self {
+            AstNode::Feature(_) => None,
+            AstNode::Rule(rule) => Some(&rule.id),
+            AstNode::Background(background) => Some(&background.id),
+            AstNode::Scenario(scenario) => Some(&scenario.id),
+            AstNode::Examples(examples) => Some(&examples.id),
+            AstNode::TableRow(table_row) => Some(&table_row.id),
+            AstNode::Step(step) => Some(&step.id),
+            AstNode::Tag(tag) => Some(&tag.id),
+        }
+    }
+
+    pub fn location(&self) -> Option<ast::Location> {
+        match *self {
+            AstNode::Feature(feature) => feature.location,
+            AstNode::Rule(rule) => rule.location,
+            AstNode::Background(background) => background.location,
+            AstNode::Scenario(scenario) => scenario.location,
+            AstNode::Examples(examples) => examples.location,
+            AstNode::TableRow(table_row) => table_row.location,
+            AstNode::Step(step) => step.location,
+            AstNode::Tag(tag) => tag.location,
+        }
+    }
+}
+
+/// An index over `GherkinDocument`s and their pickles to go from pickles back to the AST
+/// and vice versa without walking the documents.
+///
+/// The lookups of the AST only accept AST node ids and the lookups of pickles only pickle
+/// and pickle step ids, because the ids of the documents and the pickles may be generated
+/// by different id generators and collide. `pickle_ast_node` goes from a pickle or pickle step
+/// to the scenario or step it was compiled from.
+#[derive(Debug, Default)]
+pub struct GherkinQuery<'d> {
+    ast_nodes: HashMap<&'d str, AstNode<'d>>,
+    parents: HashMap<&'d str, AstNode<'d>>,
+    uris: HashMap<&'d str, &'d str>,
+    pickles: HashMap<&'d str, &'d pickle::Pickle>,
+    pickle_steps: HashMap<&'d str, (&'d pickle::Step, &'d pickle::Pickle)>,
+    pickle_ids: HashMap<&'d str, Vec<&'d str>>,
+    pickle_step_ids: HashMap<&'d str, Vec<&'d str>>,
+}
+
+impl<'d> GherkinQuery<'d> {
+    /// Creates an index of a document and the pickles compiled from it.
+    pub fn new(
+        gherkin_document: &'d ast::GherkinDocument,
+        pickles: &'d [pickle::Pickle],
+    ) -> GherkinQuery<'d> {
+        let mut query = GherkinQuery::default();
+        query.add_gherkin_document(gherkin_document);
+        for pickle in pickles {
+            query.add_pickle(pickle);
+        }
+        query
+    }
+
+    /// Adds the documents and pickles of the envelopes, like the ones of `parse_paths`.
+    pub fn update(&mut self, envelope: &'d Envelope) {
+        match &envelope.message {
+            Some(Message::GherkinDocument(gherkin_document)) => {
+                self.add_gherkin_document(gherkin_document)
+            }
+            Some(Message::Pickle(pickle)) => self.add_pickle(pickle),
+            _ => {}
+        }
+    }
+
+    pub fn add_gherkin_document(&mut self, gherkin_document: &'d ast::GherkinDocument) {
+        let feature = match &gherkin_document.feature {
+            Some(feature) => feature,
+            None => return,
+        };
+        let mut indexer = Indexer {
+            query: self,
+            uri: &gherkin_document.uri,
+        };
+
+        let feature_node = AstNode::Feature(feature);
+        for tag in &feature.tags {
+            indexer.add(AstNode::Tag(tag), feature_node);
+        }
+        for feature_child in &feature.children {
+            match &feature_child.value {
+                Some(ast::FeatureChildValue::Rule(rule)) => {
+                    let rule_node = AstNode::Rule(rule);
+                    indexer.add(rule_node, feature_node);
+                    for rule_child in &rule.children {
+                        match &rule_child.value {
+                            Some(ast::RuleChildValue::Background(background)) => {
+                                indexer.add_background(background, rule_node)
+                            }
+                            Some(ast::RuleChildValue::Scenario(scenario)) => {
+                                indexer.add_scenario(scenario, rule_node)
+                            }
+                            None => {}
+                        }
+                    }
+                }
+                Some(ast::FeatureChildValue::Background(background)) => {
+                    indexer.add_background(background, feature_node)
+                }
+                Some(ast::FeatureChildValue::Scenario(scenario)) => {
+                    indexer.add_scenario(scenario, feature_node)
+                }
+                None => {}
+            }
+        }
+    }
+
+    pub fn add_pickle(&mut self, pickle: &'d pickle::Pickle) {
+        self.pickles.insert(&pickle.id, pickle);
+        for ast_node_id in &pickle.ast_node_ids {
+            self.pickle_ids
+                .entry(ast_node_id)
+                .or_insert_with(Vec::new)
+                .push(&pickle.id);
+        }
+
+        for step in &pickle.steps {
+            self.pickle_steps.insert(&step.id, (step, pickle));
+            for ast_node_id in &step.ast_node_ids {
+                self.pickle_step_ids
+                    .entry(ast_node_id)
+                    .or_insert_with(Vec::new)
+                    .push(&step.id);
+            }
+        }
+    }
+
+    pub fn ast_node(&self, ast_node_id: &str) -> Option<AstNode<'d>> {
+        self.ast_nodes.get(ast_node_id).cloned()
+    }
+
+    pub fn pickle(&self, pickle_id: &str) -> Option<&'d pickle::Pickle> {
+        self.pickles.get(pickle_id).cloned()
+    }
+
+    pub fn pickle_step(&self, pickle_step_id: &str) -> Option<&'d pickle::Step> {
+        self.pickle_steps.get(pickle_step_id).map(|(step, _)| *step)
+    }
+
+    /// Returns the pickle which contains the pickle step.
+    pub fn pickle_of_step(&self, pickle_step_id: &str) -> Option<&'d pickle::Pickle> {
+        self.pickle_steps
+            .get(pickle_step_id)
+            .map(|(_, pickle)| *pickle)
+    }
+
+    /// Returns the pickles of a scenario, or of an examples row.
+    pub fn pickles(&self, ast_node_id: &str) -> Vec<&'d pickle::Pickle> {
+        self.pickle_ids
+            .get(ast_node_id)
+            .map(|pickle_ids| {
+                pickle_ids
+                    .iter()
+                    .map(|pickle_id| self.pickles[pickle_id])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the pickle steps of a step, or of an examples row.
+    ///
+    /// Background steps have a pickle step in every pickle of their feature or rule.
+    pub fn pickle_steps(&self, ast_node_id: &str) -> Vec<&'d pickle::Step> {
+        self.pickle_step_ids
+            .get(ast_node_id)
+            .map(|pickle_step_ids| {
+                pickle_step_ids
+                    .iter()
+                    .map(|pickle_step_id| self.pickle_steps[pickle_step_id].0)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the scenario that the pickle, or the step that the pickle step was compiled from.
+    pub fn pickle_ast_node(&self, id: &str) -> Option<AstNode<'d>> {
+        let ast_node_ids = match self.pickles.get(id) {
+            Some(pickle) => &pickle.ast_node_ids,
+            None => &self.pickle_steps.get(id)?.0.ast_node_ids,
+        };
+        ast_node_ids
+            .first()
+            .and_then(|ast_node_id| self.ast_node(ast_node_id))
+    }
+
+    /// Returns the location of the step that the pickle step was compiled from.
+    pub fn pickle_step_location(&self, pickle_step_id: &str) -> Option<ast::Location> {
+        let (step, _) = self.pickle_steps.get(pickle_step_id)?;
+        step.ast_node_ids
+            .first()
+            .and_then(|ast_node_id| self.ast_node(ast_node_id))
+            .and_then(|ast_node| ast_node.location())
+    }
+
+    pub fn uri(&self, ast_node_id: &str) -> Option<&'d str> {
+        self.uris.get(ast_node_id).cloned()
+    }
+
+    pub fn parent(&self, ast_node_id: &str) -> Option<AstNode<'d>> {
+        self.parents.get(ast_node_id).cloned()
+    }
+
+    /// Returns the scenario of a node, which is the node itself if it is a scenario.
+    pub fn scenario(&self, ast_node_id: &str) -> Option<&'d ast::Scenario> {
+        self.ancestors(ast_node_id)
+            .find_map(|ast_node| match ast_node {
+                AstNode::Scenario(scenario) => Some(scenario),
+                _ => None,
+            })
+    }
+
+    /// Returns the rule of a node, which is the node itself if it is a rule.
+    pub fn rule(&self, ast_node_id: &str) -> Option<&'d ast::Rule> {
+        self.ancestors(ast_node_id)
+            .find_map(|ast_node| match ast_node {
+                AstNode::Rule(rule) => Some(rule),
+                _ => None,
+            })
+    }
+
+    pub fn feature(&self, ast_node_id: &str) -> Option<&'d ast::Feature> {
+        self.ancestors(ast_node_id)
+            .find_map(|ast_node| match ast_node {
+                AstNode::Feature(feature) => Some(feature),
+                _ => None,
+            })
+    }
+
+    /// Returns the node and its parents up to the feature.
+    fn ancestors<'q>(&'q self, ast_node_id: &str) -> impl Iterator<Item = AstNode<'d>> + 'q {
+        std::iter::successors(self.ast_node(ast_node_id), move |ast_node| {
+            ast_node
+                .id()
+                .and_then(|ast_node_id| self.parents.get(ast_node_id).cloned())
+        })
+    }
+}
+
+struct Indexer<'q, 'd> {
+    query: &'q mut GherkinQuery<'d>,
+    uri: &'d str,
+}
+
+impl<'q, 'd> Indexer<'q, 'd> {
+    fn add(&mut self, ast_node: AstNode<'d>, parent: AstNode<'d>) {
+        if let Some(ast_node_id) = ast_node.id() {
+            self.query.ast_nodes.insert(ast_node_id, ast_node);
+            self.query.parents.insert(ast_node_id, parent);
+            self.query.uris.insert(ast_node_id, self.uri);
+        }
+    }
+
+    fn add_background(&mut self, background: &'d ast::Background, parent: AstNode<'d>) {
+        let background_node = AstNode::Background(background);
+        self.add(background_node, parent);
+        for step in &background.steps {
+            self.add(AstNode::Step(step), background_node);
+        }
+    }
+
+    fn add_scenario(&mut self, scenario: &'d ast::Scenario, parent: AstNode<'d>) {
+        let scenario_node = AstNode::Scenario(scenario);
+        self.add(scenario_node, parent);
+        for tag in &scenario.tags {
+            self.add(AstNode::Tag(tag), scenario_node);
+        }
+        for step in &scenario.steps {
+            self.add(AstNode::Step(step), scenario_node);
+        }
+
+        for examples in &scenario.examples {
+            let examples_node = AstNode::Examples(examples);
+            self.add(examples_node, scenario_node);
+            for tag in &examples.tags {
+                self.add(AstNode::Tag(tag), examples_node);
+            }
+            for table_row in examples.table_header.iter().chain(&examples.table_body) {
+                self.add(AstNode::TableRow(table_row), examples_node);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::cuke::Compiler;
+    use crate::{DocumentBuilder, Parser};
+
+    use super::*;
+
+    #[test]
+    fn maps_pickles_to_ast_nodes() {
+        let source = r#"
+Feature: Query
+  Background:
+    Given a background step
+
+  Rule: Outlines
+    Scenario Outline: Eating
+      When I eat <count> cukes
+
+      Examples:
+        | count |
+        | 1     |
+        | 2     |
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let mut gherkin_document = parser.parse_str(source).unwrap();
+        gherkin_document.uri = "query.feature".to_string();
+
+        // the ids of the pickles collide with the ones of the document
+        let mut id_generator = IncrementingIdGenerator::new();
+        let pickles = Compiler::new(&mut id_generator)
+            .compile(&gherkin_document)
+            .unwrap()
+            .into_iter()
+            .map(pickle::Pickle::from)
+            .collect::<Vec<pickle::Pickle>>();
+        let query = GherkinQuery::new(&gherkin_document, &pickles);
+
+        let feature = gherkin_document.feature.as_ref().unwrap();
+        let background = match &feature.children[0].value {
+            Some(ast::FeatureChildValue::Background(background)) => background,
+            value => panic!("Expected a background, got {:?}", value),
+        };
+        let rule = match &feature.children[1].value {
+            Some(ast::FeatureChildValue::Rule(rule)) => rule,
+            value => panic!("Expected a rule, got {:?}", value),
+        };
+        let scenario = match &rule.children[0].value {
+            Some(ast::RuleChildValue::Scenario(scenario)) => scenario,
+            value => panic!("Expected a scenario, got {:?}", value),
+        };
+        let step = &scenario.steps[0];
+        let second_row = &scenario.examples[0].table_body[1];
+
+        fn pickle_ids<'p>(pickles: Vec<&'p pickle::Pickle>) -> Vec<&'p str> {
+            pickles.iter().map(|pickle| pickle.id.as_str()).collect()
+        }
+        assert_eq!(
+            pickle_ids(query.pickles(&scenario.id)),
+            vec![pickles[0].id.as_str(), pickles[1].id.as_str()]
+        );
+        assert_eq!(
+            pickle_ids(query.pickles(&second_row.id)),
+            vec![pickles[1].id.as_str()]
+        );
+
+        let pickle_steps = query.pickle_steps(&step.id);
+        assert_eq!(pickle_steps.len(), 2);
+        assert_eq!(pickle_steps[1].text, "I eat 2 cukes");
+        assert_eq!(query.pickle_steps(&background.steps[0].id).len(), 2);
+
+        let pickle_step_id = &pickle_steps[1].id;
+        assert_eq!(query.pickle_step_location(pickle_step_id), step.location);
+        assert_eq!(
+            query.pickle_of_step(pickle_step_id).unwrap().id,
+            pickles[1].id
+        );
+        let ast_step_id = match query.pickle_ast_node(pickle_step_id) {
+            Some(AstNode::Step(ast_step)) => ast_step.id.as_str(),
+            ast_node => panic!("Expected a step, got {:?}", ast_node),
+        };
+        assert_eq!(ast_step_id, step.id);
+        assert_eq!(query.scenario(ast_step_id).unwrap().id, scenario.id);
+        assert_eq!(query.rule(ast_step_id).unwrap().id, rule.id);
+        assert_eq!(query.feature(ast_step_id).unwrap().name, "Query");
+        assert_eq!(query.uri(ast_step_id), Some("query.feature"));
+        match query.pickle_ast_node(&pickles[0].id) {
+            Some(AstNode::Scenario(ast_scenario)) => assert_eq!(ast_scenario.id, scenario.id),
+            ast_node => panic!("Expected a scenario, got {:?}", ast_node),
+        }
+
+        assert!(query.rule(&background.steps[0].id).is_none());
+        match query.parent(&second_row.id) {
+            Some(AstNode::Examples(examples)) => assert_eq!(examples.id, scenario.examples[0].id),
+            parent => panic!("Expected examples, got {:?}", parent),
+        }
+        match query.ast_node(&step.id) {
+            Some(AstNode::Step(ast_step)) => assert_eq!(ast_step.text, "I eat <count> cukes"),
+            ast_node => panic!("Expected a step, got {:?}", ast_node),
+        }
+        assert!(query.ast_node("unknown").is_none());
+    }
+
+    #[test]
+    fn indexes_feature_tags() {
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = parser.parse_str("@query\nFeature: Query\n").unwrap();
+        let query = GherkinQuery::new(&gherkin_document, &[]);
+
+        let feature_tag_id = &gherkin_document.feature.as_ref().unwrap().tags[0].id;
+        match query.ast_node(feature_tag_id) {
+            Some(AstNode::Tag(tag)) => assert_eq!(tag.name, "@query"),
+            ast_node => panic!("Expected a tag, got {:?}", ast_node),
+        }
+        assert_eq!(query.feature(feature_tag_id).unwrap().name, "Query");
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use cucumber_messages::ast::GherkinDocument;
+
+use crate::constant;
+use crate::dialect_provider::BuiltInDialectProvider;
+use crate::error::Result;
+use crate::markdown_token_matcher::MarkdownTokenMatcher;
+use crate::parser::{DialectProvider, TokenMatch};
+use crate::token_matcher::TokenMatcher;
+use crate::{DocumentBuilder, Parser, ParserOptions};
+
+/// A parser of sources with the token matcher of their media type.
+///
+/// The token matchers of both media types share the dialect provider and are only created once,
+/// the one which is not used by the parser is kept until a source of its media type is parsed.
+pub(crate) struct SourceParser<'id_gen> {
+    parser: Parser<DocumentBuilder<'id_gen>>,
+    media_type: &'static str,
+    idle_token_match: Option<Box<dyn TokenMatch>>,
+}
+
+impl<'id_gen> SourceParser<'id_gen> {
+    pub(crate) fn new(builder: DocumentBuilder<'id_gen>) -> SourceParser<'id_gen> {
+        SourceParser::with_dialect_provider(builder, BuiltInDialectProvider::default())
+    }
+
+    pub(crate) fn with_dialect_provider<DP>(
+        builder: DocumentBuilder<'id_gen>,
+        dialect_provider: DP,
+    ) -> SourceParser<'id_gen>
+    where
+        DP: DialectProvider + 'static,
+    {
+        let dialect_provider = Arc::new(dialect_provider);
+        let token_matcher = TokenMatcher::with_dialect_provider(Arc::clone(&dialect_provider));
+        let parser = ParserOptions::with_builder(builder)
+            .token_matcher(token_matcher)
+            .create();
+        let markdown_token_matcher = MarkdownTokenMatcher::with_dialect_provider(dialect_provider);
+        let idle_token_match: Box<dyn TokenMatch> = Box::new(markdown_token_matcher);
+
+        SourceParser {
+            parser,
+            media_type: constant::MEDIA_TYPE_PLAIN,
+            idle_token_match: Some(idle_token_match),
+        }
+    }
+
+    pub(crate) fn builder_mut(&mut self) -> &mut DocumentBuilder<'id_gen> {
+        self.parser.builder_mut()
+    }
+
+    /// Parses the source with the token matcher of the media type,
+    /// which is plain Gherkin unless it is `constant::MEDIA_TYPE_MARKDOWN`.
+    pub(crate) fn parse_str(&mut self, source: &str, media_type: &str) -> Result<GherkinDocument> {
+        let media_type = if media_type == constant::MEDIA_TYPE_MARKDOWN {
+            constant::MEDIA_TYPE_MARKDOWN
+        } else {
+            constant::MEDIA_TYPE_PLAIN
+        };
+
+        if media_type != self.media_type {
+            if let Some(idle_token_match) = self.idle_token_match.take() {
+                let token_match = self.parser.replace_token_match(idle_token_match);
+                self.idle_token_match = Some(token_match);
+            }
+            self.media_type = media_type;
+        }
+
+        self.parser.parse_str(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use super::*;
+
+    #[test]
+    fn keeps_dialect_provider_for_all_media_types() {
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let dialect_provider = BuiltInDialectProvider::with_default_dialect_name("de");
+        let mut parser = SourceParser::with_dialect_provider(builder, dialect_provider);
+
+        let sources = [
+            ("Funktionalität: Gherkin\n", constant::MEDIA_TYPE_PLAIN),
+            (
+                "# Funktionalität: Markdown\n",
+                constant::MEDIA_TYPE_MARKDOWN,
+            ),
+            (
+                "Funktionalität: Gherkin again\n",
+                constant::MEDIA_TYPE_PLAIN,
+            ),
+        ];
+        let names = sources
+            .iter()
+            .map(|(source, media_type)| {
+                let gherkin_document = parser.parse_str(source, media_type).unwrap();
+                gherkin_document.feature.unwrap().name
+            })
+            .collect::<Vec<String>>();
+
+        assert_eq!(names, vec!["Gherkin", "Markdown", "Gherkin again"]);
+    }
+}
@@ -0,0 +1,424 @@
+//! Statistics about parsed documents and their pickles.
+//!
+//! A `StatisticsBuilder` is fed with the `GherkinDocument`s first and their `Pickle`s afterwards,
+//! as the pickles are attributed to the features, rules and scenario outlines by their ast node
+//! ids. The resulting `Statistics` serialize to JSON and display as plain text tables.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use cucumber_messages::ast;
+use cucumber_messages::pickle::Pickle;
+use serde::Serialize;
+
+use crate::uri::folder;
+use crate::visit::{self, Context, Visitor};
+
+mod table;
+
+#[derive(Serialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Counts {
+    pub features: usize,
+    pub rules: usize,
+    pub backgrounds: usize,
+    /// The scenarios without examples.
+    pub scenarios: usize,
+    pub scenario_outlines: usize,
+    pub examples: usize,
+    /// The rows of the examples tables, without the header rows.
+    pub examples_rows: usize,
+    /// The steps of the backgrounds, scenarios and scenario outlines.
+    pub steps: usize,
+    pub tags: usize,
+    pub pickles: usize,
+    pub pickle_steps: usize,
+}
+
+impl Counts {
+    /// Returns the average number of steps of a pickle, including the background steps.
+    pub fn average_steps_per_scenario(&self) -> f64 {
+        if self.pickles == 0 {
+            0.0
+        } else {
+            self.pickle_steps as f64 / self.pickles as f64
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Statistics {
+    pub totals: Counts,
+    pub average_steps_per_scenario: f64,
+    /// The counts of the feature files by the directory of their uri.
+    pub directories: BTreeMap<String, Counts>,
+    pub features: Vec<FeatureStatistics>,
+    pub tags: BTreeMap<String, TagStatistics>,
+    /// The step texts as written in the sources, the most frequent first.
+    pub step_texts: Vec<StepTextFrequency>,
+    pub scenario_outlines: Vec<OutlineStatistics>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureStatistics {
+    pub uri: String,
+    pub name: String,
+    pub counts: Counts,
+    pub rules: Vec<RuleStatistics>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleStatistics {
+    pub name: String,
+    pub counts: Counts,
+}
+
+#[derive(Serialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagStatistics {
+    /// How often the tag is written in the sources.
+    pub usages: usize,
+    /// The pickles which have the tag, including the pickles which inherit it.
+    pub pickles: usize,
+    pub pickle_steps: usize,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepTextFrequency {
+    pub text: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineStatistics {
+    pub uri: String,
+    pub name: String,
+    pub line: u32,
+    pub steps: usize,
+    pub examples: usize,
+    /// The number of pickles compiled from the outline.
+    pub expansion_factor: usize,
+    pub pickle_steps: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct StatisticsBuilder {
+    totals: Counts,
+    directories: BTreeMap<String, Counts>,
+    features: Vec<FeatureStatistics>,
+    tags: BTreeMap<String, TagStatistics>,
+    step_texts: HashMap<String, usize>,
+    scenario_outlines: Vec<OutlineStatistics>,
+    /// The scenarios by uri and id.
+    scenarios: HashMap<(String, String), ScenarioIndexes>,
+}
+
+/// The indexes of the feature, rule and outline statistics of a scenario.
+#[derive(Debug, Copy, Clone)]
+struct ScenarioIndexes {
+    feature: usize,
+    rule: Option<usize>,
+    outline: Option<usize>,
+}
+
+impl StatisticsBuilder {
+    /// Counts the nodes of a document.
+    pub fn add_gherkin_document(&mut self, gherkin_document: &ast::GherkinDocument) {
+        let mut collector = DocumentCollector {
+            builder: self,
+            uri: &gherkin_document.uri,
+            directory: folder(&gherkin_document.uri),
+            feature: 0,
+            rule: None,
+        };
+        collector.visit_gherkin_document(gherkin_document);
+    }
+
+    /// Counts a pickle of a document which was added before.
+    ///
+    /// Pickles of unknown scenarios only count towards the totals and directories.
+    pub fn add_pickle(&mut self, pickle: &Pickle) {
+        let steps = pickle.steps.len();
+        let add = |counts: &mut Counts| {
+            counts.pickles += 1;
+            counts.pickle_steps += steps;
+        };
+
+        add(&mut self.totals);
+        add(self
+            .directories
+            .entry(folder(&pickle.uri))
+            .or_insert_with(Counts::default));
+
+        let indexes = pickle.ast_node_ids.first().and_then(|scenario_id| {
+            let key = (pickle.uri.clone(), scenario_id.clone());
+            self.scenarios.get(&key).copied()
+        });
+        if let Some(indexes) = indexes {
+            let feature = &mut self.features[indexes.feature];
+            add(&mut feature.counts);
+            if let Some(rule) = indexes.rule {
+                add(&mut feature.rules[rule].counts);
+            }
+            if let Some(outline) = indexes.outline {
+                let outline = &mut self.scenario_outlines[outline];
+                outline.expansion_factor += 1;
+                outline.pickle_steps += steps;
+            }
+        }
+
+        let tag_names = pickle
+            .tags
+            .iter()
+            .map(|tag| tag.name.as_str())
+            .collect::<HashSet<&str>>();
+        for tag_name in tag_names {
+            let tag = self
+                .tags
+                .entry(tag_name.to_string())
+                .or_insert_with(TagStatistics::default);
+            tag.pickles += 1;
+            tag.pickle_steps += steps;
+        }
+    }
+
+    pub fn build(self) -> Statistics {
+        let mut step_texts = self
+            .step_texts
+            .into_iter()
+            .map(|(text, count)| StepTextFrequency { text, count })
+            .collect::<Vec<StepTextFrequency>>();
+        step_texts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.text.cmp(&b.text)));
+
+        Statistics {
+            totals: self.totals,
+            average_steps_per_scenario: self.totals.average_steps_per_scenario(),
+            directories: self.directories,
+            features: self.features,
+            tags: self.tags,
+            step_texts,
+            scenario_outlines: self.scenario_outlines,
+        }
+    }
+}
+
+/// Counts the nodes of a document towards the totals, its directory, feature and rule.
+struct DocumentCollector<'b> {
+    builder: &'b mut StatisticsBuilder,
+    uri: &'b str,
+    directory: String,
+    feature: usize,
+    rule: Option<usize>,
+}
+
+impl DocumentCollector<'_> {
+    fn count<F: Fn(&mut Counts)>(&mut self, add: F) {
+        let builder = &mut *self.builder;
+        add(&mut builder.totals);
+        add(builder
+            .directories
+            .entry(self.directory.clone())
+            .or_insert_with(Counts::default));
+
+        let feature = &mut builder.features[self.feature];
+        add(&mut feature.counts);
+        if let Some(rule) = self.rule {
+            add(&mut feature.rules[rule].counts);
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for DocumentCollector<'_> {
+    fn visit_feature(&mut self, feature: &'a ast::Feature, context: &Context<'a>) {
+        self.feature = self.builder.features.len();
+        self.builder.features.push(FeatureStatistics {
+            uri: self.uri.to_string(),
+            name: feature.name.clone(),
+            counts: Counts::default(),
+            rules: Vec::new(),
+        });
+        self.count(|counts| counts.features += 1);
+        visit::walk_feature(self, feature, context);
+    }
+
+    fn visit_rule(&mut self, rule: &'a ast::Rule, context: &Context<'a>) {
+        let rules = &mut self.builder.features[self.feature].rules;
+        rules.push(RuleStatistics {
+            name: rule.name.clone(),
+            counts: Counts::default(),
+        });
+        self.rule = Some(rules.len() - 1);
+        self.count(|counts| counts.rules += 1);
+        visit::walk_rule(self, rule, context);
+        self.rule = None;
+    }
+
+    fn visit_background(&mut self, background: &'a ast::Background, context: &Context<'a>) {
+        self.count(|counts| counts.backgrounds += 1);
+        visit::walk_background(self, background, context);
+    }
+
+    fn visit_scenario(&mut self, scenario: &'a ast::Scenario, context: &Context<'a>) {
+        let outline = if scenario.examples.is_empty() {
+            self.count(|counts| counts.scenarios += 1);
+            None
+        } else {
+            self.count(|counts| counts.scenario_outlines += 1);
+            let outlines = &mut self.builder.scenario_outlines;
+            outlines.push(OutlineStatistics {
+                uri: self.uri.to_string(),
+                name: scenario.name.clone(),
+                line: scenario.location.unwrap_or_default().line,
+                steps: scenario.steps.len(),
+                examples: scenario.examples.len(),
+                expansion_factor: 0,
+                pickle_steps: 0,
+            });
+            Some(outlines.len() - 1)
+        };
+
+        let key = (self.uri.to_string(), scenario.id.clone());
+        let indexes = ScenarioIndexes {
+            feature: self.feature,
+            rule: self.rule,
+            outline,
+        };
+        self.builder.scenarios.insert(key, indexes);
+
+        visit::walk_scenario(self, scenario, context);
+    }
+
+    fn visit_examples(&mut self, examples: &'a ast::Examples, context: &Context<'a>) {
+        let rows = examples.table_body.len();
+        self.count(|counts| {
+            counts.examples += 1;
+            counts.examples_rows += rows;
+        });
+        visit::walk_examples(self, examples, context);
+    }
+
+    fn visit_step(&mut self, step: &'a ast::Step, _context: &Context<'a>) {
+        self.count(|counts| counts.steps += 1);
+        *self
+            .builder
+            .step_texts
+            .entry(step.text.clone())
+            .or_insert(0) += 1;
+    }
+
+    fn visit_tag(&mut self, tag: &'a ast::Tag, _context: &Context<'a>) {
+        self.count(|counts| counts.tags += 1);
+        self.builder
+            .tags
+            .entry(tag.name.clone())
+            .or_insert_with(TagStatistics::default)
+            .usages += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::cuke::Compiler;
+    use crate::test_util::parse_document;
+
+    use super::*;
+
+    fn add(builder: &mut StatisticsBuilder, uri: &str, source: &str) {
+        let gherkin_document = parse_document(uri, source);
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut compiler = Compiler::new(&mut id_generator);
+        let pickles = compiler
+            .compile(&gherkin_document)
+            .unwrap()
+            .into_iter()
+            .map(Pickle::from)
+            .collect::<Vec<Pickle>>();
+
+        builder.add_gherkin_document(&gherkin_document);
+        for pickle in &pickles {
+            builder.add_pickle(pickle);
+        }
+    }
+
+    #[test]
+    fn counts_documents_and_pickles() {
+        let mut builder = StatisticsBuilder::default();
+        add(
+            &mut builder,
+            "features/eating.feature",
+            r#"
+@food
+Feature: Eating
+  Background:
+    Given a basket
+
+  Scenario: one cuke
+    When I eat a cuke
+
+  Rule: Many
+    @slow
+    Scenario Outline: <count> cukes
+      When I eat a cuke
+      And I eat <count> cukes
+
+      Examples:
+        | count |
+        | 2     |
+        | 3     |
+"#,
+        );
+        add(
+            &mut builder,
+            "features/basket.feature",
+            "Feature: Basket\n  Scenario: empty\n    When I eat a cuke\n",
+        );
+        let statistics = builder.build();
+
+        let totals = statistics.totals;
+        assert_eq!(totals.features, 2);
+        assert_eq!(totals.rules, 1);
+        assert_eq!(totals.backgrounds, 1);
+        assert_eq!(totals.scenarios, 2);
+        assert_eq!(totals.scenario_outlines, 1);
+        assert_eq!(totals.examples_rows, 2);
+        assert_eq!(totals.steps, 5);
+        assert_eq!(totals.tags, 2);
+        assert_eq!(totals.pickles, 4);
+        assert_eq!(totals.pickle_steps, 9);
+        assert!((statistics.average_steps_per_scenario - 2.25).abs() < std::f64::EPSILON);
+        assert_eq!(statistics.directories["features"], totals);
+
+        let eating = &statistics.features[0];
+        assert_eq!(eating.counts.pickles, 3);
+        assert_eq!(eating.rules[0].name, "Many");
+        assert_eq!(eating.rules[0].counts.steps, 2);
+        assert_eq!(eating.rules[0].counts.pickle_steps, 6);
+
+        let food = statistics.tags["@food"];
+        assert_eq!((food.usages, food.pickles, food.pickle_steps), (1, 3, 8));
+        assert_eq!(statistics.tags["@slow"].pickles, 2);
+
+        assert_eq!(
+            statistics.step_texts[0],
+            StepTextFrequency {
+                text: String::from("I eat a cuke"),
+                count: 3,
+            }
+        );
+
+        let outline = &statistics.scenario_outlines[0];
+        assert_eq!((outline.line, outline.steps), (12, 2));
+        assert_eq!(outline.expansion_factor, 2);
+
+        let table = statistics.to_string();
+        assert!(table.starts_with("Average steps per scenario: 2.25\n\nDirectories\n"));
+        assert!(table.contains("\nI eat a cuke      3\n"));
+    }
+}
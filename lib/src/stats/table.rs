@@ -0,0 +1,154 @@
+use std::fmt;
+
+use crate::stats::{Counts, Statistics};
+
+const COUNTS_HEADER: [&str; 9] = [
+    "features",
+    "rules",
+    "backgrounds",
+    "scenarios",
+    "outlines",
+    "examples rows",
+    "steps",
+    "tags",
+    "pickles",
+];
+
+/// The number of step texts listed in the duplication table.
+const MAX_STEP_TEXTS: usize = 20;
+
+/// Displays the statistics as plain text tables.
+///
+/// Only the most frequent step texts which are used more than once are listed.
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Average steps per scenario: {:.2}",
+            self.average_steps_per_scenario
+        )?;
+
+        let mut rows = self
+            .directories
+            .iter()
+            .map(|(directory, counts)| counts_row(directory.clone(), counts))
+            .collect::<Vec<Vec<String>>>();
+        rows.push(counts_row(String::from("total"), &self.totals));
+        write_table(f, "Directories", "directory", &COUNTS_HEADER, &rows)?;
+
+        let mut rows = Vec::new();
+        for feature in &self.features {
+            rows.push(counts_row(feature.uri.clone(), &feature.counts));
+            for rule in &feature.rules {
+                rows.push(counts_row(format!("  rule: {}", rule.name), &rule.counts));
+            }
+        }
+        write_table(f, "Features", "feature", &COUNTS_HEADER, &rows)?;
+
+        let rows = self
+            .tags
+            .iter()
+            .map(|(name, tag)| {
+                let counts = [tag.usages, tag.pickles, tag.pickle_steps];
+                row(name.clone(), &counts)
+            })
+            .collect::<Vec<Vec<String>>>();
+        write_table(
+            f,
+            "Tags",
+            "tag",
+            &["usages", "pickles", "pickle steps"],
+            &rows,
+        )?;
+
+        let rows = self
+            .step_texts
+            .iter()
+            .filter(|step_text| step_text.count > 1)
+            .take(MAX_STEP_TEXTS)
+            .map(|step_text| row(step_text.text.clone(), &[step_text.count]))
+            .collect::<Vec<Vec<String>>>();
+        write_table(f, "Repeated step texts", "step text", &["count"], &rows)?;
+
+        let rows = self
+            .scenario_outlines
+            .iter()
+            .map(|outline| {
+                let counts = [outline.steps, outline.examples, outline.expansion_factor];
+                row(format!("{}:{}", outline.uri, outline.line), &counts)
+            })
+            .collect::<Vec<Vec<String>>>();
+        let header = ["steps", "examples", "expansion factor"];
+        write_table(f, "Scenario outlines", "outline", &header, &rows)
+    }
+}
+
+fn counts_row(name: String, counts: &Counts) -> Vec<String> {
+    let counts = [
+        counts.features,
+        counts.rules,
+        counts.backgrounds,
+        counts.scenarios,
+        counts.scenario_outlines,
+        counts.examples_rows,
+        counts.steps,
+        counts.tags,
+        counts.pickles,
+    ];
+    row(name, &counts)
+}
+
+fn row(name: String, counts: &[usize]) -> Vec<String> {
+    let mut row = Vec::with_capacity(counts.len() + 1);
+    row.push(name);
+    row.extend(counts.iter().map(usize::to_string));
+    row
+}
+
+/// Writes a table with a left aligned name column and right aligned count columns.
+/// Tables without rows are skipped.
+fn write_table(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    name_header: &str,
+    count_headers: &[&str],
+    rows: &[Vec<String>],
+) -> fmt::Result {
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let header = Some(name_header)
+        .into_iter()
+        .chain(count_headers.iter().cloned())
+        .map(str::to_string)
+        .collect::<Vec<String>>();
+    let widths = (0..header.len())
+        .map(|column| {
+            Some(&header)
+                .into_iter()
+                .chain(rows)
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<usize>>();
+
+    writeln!(f, "\n{}\n", title)?;
+    for row in Some(&header).into_iter().chain(rows) {
+        let mut line = String::new();
+        for (column, (value, width)) in row.iter().zip(&widths).enumerate() {
+            let padding = " ".repeat(width - value.chars().count());
+            if column == 0 {
+                line.push_str(value);
+                line.push_str(&padding);
+            } else {
+                line.push_str("  ");
+                line.push_str(&padding);
+                line.push_str(value);
+            }
+        }
+        writeln!(f, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! The messages of a dry run, in the structure of the Cucumber messages protocol.
+
+use serde::{Deserialize, Serialize};
+
+/// An envelope which serializes like the `Envelope`s of `parse_paths`,
+/// so both can be written to the same NDJSON stream.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum StepEnvelope {
+    StepDefinition(StepDefinition),
+    TestCase(TestCase),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepDefinition {
+    pub id: String,
+    pub pattern: StepDefinitionPattern,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepDefinitionPattern {
+    pub source: String,
+    #[serde(rename = "type")]
+    pub pattern_type: StepDefinitionPatternType,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StepDefinitionPatternType {
+    CucumberExpression,
+    RegularExpression,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCase {
+    pub id: String,
+    pub pickle_id: String,
+    pub test_steps: Vec<TestStep>,
+}
+
+/// A step of a test case, which is undefined without step definition ids
+/// and ambiguous with more than one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestStep {
+    pub id: String,
+    pub pickle_step_id: String,
+    pub step_definition_ids: Vec<String>,
+    /// The arguments for each of the step definitions.
+    pub step_match_arguments_lists: Vec<StepMatchArgumentsList>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepMatchArgumentsList {
+    pub step_match_arguments: Vec<StepMatchArgument>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepMatchArgument {
+    pub parameter_type_name: String,
+    pub group: Group,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    /// The byte offset of the value in the step text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub children: Vec<Group>,
+}
@@ -0,0 +1,398 @@
+//! Matching the steps of compiled documents against step definitions without executing them,
+//! and generating snippets for the steps which are still undefined.
+
+pub use self::messages::*;
+pub use self::snippet::*;
+
+use regex::Regex;
+
+use cucumber_messages::id_generator::IdGenerator;
+
+use crate::cucumber_expressions::{
+    self, Argument, CucumberExpression, ParameterTypeRegistry, RegularExpression, Result,
+};
+use crate::cuke;
+
+mod messages;
+mod snippet;
+
+/// The pattern of a step definition.
+#[derive(Debug)]
+pub enum StepPattern {
+    CucumberExpression(CucumberExpression),
+    RegularExpression(RegularExpression),
+}
+
+impl StepPattern {
+    pub fn source(&self) -> &str {
+        match self {
+            StepPattern::CucumberExpression(expression) => expression.source(),
+            StepPattern::RegularExpression(expression) => expression.source(),
+        }
+    }
+
+    pub fn match_step<'s>(&self, step: &'s cuke::Step<'_>) -> Option<Vec<Argument<'s>>> {
+        match self {
+            StepPattern::CucumberExpression(expression) => expression.match_step(step),
+            StepPattern::RegularExpression(expression) => expression.match_step(step),
+        }
+    }
+
+    fn pattern_type(&self) -> StepDefinitionPatternType {
+        match self {
+            StepPattern::CucumberExpression(_) => StepDefinitionPatternType::CucumberExpression,
+            StepPattern::RegularExpression(_) => StepDefinitionPatternType::RegularExpression,
+        }
+    }
+}
+
+/// A step definition, which is only a pattern since nothing is executed.
+#[derive(Debug)]
+pub struct StepDefinition {
+    pub id: String,
+    pub pattern: StepPattern,
+}
+
+/// The result of matching a step against all step definitions.
+#[derive(Debug)]
+pub enum StepMatch<'r, 's> {
+    Undefined,
+    Defined(StepDefinitionMatch<'r, 's>),
+    /// Multiple step definitions match, in the order of their definition.
+    Ambiguous(Vec<StepDefinitionMatch<'r, 's>>),
+}
+
+#[derive(Debug)]
+pub struct StepDefinitionMatch<'r, 's> {
+    pub step_definition: &'r StepDefinition,
+    pub arguments: Vec<Argument<'s>>,
+}
+
+/// A step that does not match any step definition.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UndefinedStep {
+    pub uri: String,
+    pub location: cuke::Location,
+    pub pickle_id: String,
+    pub pickle_step_id: String,
+    pub text: String,
+}
+
+/// A step that matches multiple step definitions.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AmbiguousStep {
+    pub uri: String,
+    pub location: cuke::Location,
+    pub pickle_id: String,
+    pub pickle_step_id: String,
+    pub text: String,
+    pub pattern_sources: Vec<String>,
+}
+
+/// The result of a dry run over cukes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRun {
+    /// The step definitions followed by one test case per cuke.
+    pub envelopes: Vec<StepEnvelope>,
+    pub undefined_steps: Vec<UndefinedStep>,
+    pub ambiguous_steps: Vec<AmbiguousStep>,
+}
+
+impl DryRun {
+    /// Returns `true` if every step matches exactly one step definition.
+    pub fn is_successful(&self) -> bool {
+        self.undefined_steps.is_empty() && self.ambiguous_steps.is_empty()
+    }
+}
+
+/// The step definitions to match steps against.
+#[derive(Debug, Default)]
+pub struct StepRegistry {
+    parameter_type_registry: ParameterTypeRegistry,
+    step_definitions: Vec<StepDefinition>,
+}
+
+impl StepRegistry {
+    pub fn new() -> StepRegistry {
+        StepRegistry::default()
+    }
+
+    /// Creates a registry whose cucumber expressions can use custom parameter types.
+    pub fn with_parameter_type_registry(
+        parameter_type_registry: ParameterTypeRegistry,
+    ) -> StepRegistry {
+        StepRegistry {
+            parameter_type_registry,
+            step_definitions: Vec::new(),
+        }
+    }
+
+    pub fn step_definitions(&self) -> &[StepDefinition] {
+        &self.step_definitions
+    }
+
+    pub fn define_cucumber_expression(
+        &mut self,
+        expression: &str,
+        id_generator: &mut dyn IdGenerator,
+    ) -> Result<&StepDefinition> {
+        let expression = CucumberExpression::new(expression, &self.parameter_type_registry)?;
+        Ok(self.define(StepPattern::CucumberExpression(expression), id_generator))
+    }
+
+    pub fn define_regular_expression(
+        &mut self,
+        regex: Regex,
+        id_generator: &mut dyn IdGenerator,
+    ) -> &StepDefinition {
+        let expression = RegularExpression::new(regex, &self.parameter_type_registry);
+        self.define(StepPattern::RegularExpression(expression), id_generator)
+    }
+
+    fn define(
+        &mut self,
+        pattern: StepPattern,
+        id_generator: &mut dyn IdGenerator,
+    ) -> &StepDefinition {
+        self.step_definitions.push(StepDefinition {
+            id: id_generator.new_id(),
+            pattern,
+        });
+        self.step_definitions.last().unwrap()
+    }
+
+    pub fn match_step<'r, 's>(&'r self, step: &'s cuke::Step<'_>) -> StepMatch<'r, 's> {
+        let mut matches = self
+            .step_definitions
+            .iter()
+            .filter_map(|step_definition| {
+                let arguments = step_definition.pattern.match_step(step)?;
+                Some(StepDefinitionMatch {
+                    step_definition,
+                    arguments,
+                })
+            })
+            .collect::<Vec<StepDefinitionMatch<'r, 's>>>();
+
+        match matches.len() {
+            0 => StepMatch::Undefined,
+            1 => StepMatch::Defined(matches.remove(0)),
+            _ => StepMatch::Ambiguous(matches),
+        }
+    }
+
+    /// Returns the messages of the step definitions.
+    pub fn step_definition_envelopes(&self) -> Vec<StepEnvelope> {
+        self.step_definitions
+            .iter()
+            .map(|step_definition| {
+                StepEnvelope::StepDefinition(messages::StepDefinition {
+                    id: step_definition.id.clone(),
+                    pattern: StepDefinitionPattern {
+                        source: step_definition.pattern.source().to_string(),
+                        pattern_type: step_definition.pattern.pattern_type(),
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Matches every step of the cukes and collects the undefined and ambiguous ones.
+    pub fn dry_run(&self, cukes: &[cuke::Cuke<'_>], id_generator: &mut dyn IdGenerator) -> DryRun {
+        let mut dry_run = DryRun {
+            envelopes: self.step_definition_envelopes(),
+            undefined_steps: Vec::new(),
+            ambiguous_steps: Vec::new(),
+        };
+
+        for cuke in cukes {
+            let test_case = self.test_case(cuke, &mut dry_run, id_generator);
+            dry_run.envelopes.push(StepEnvelope::TestCase(test_case));
+        }
+
+        dry_run
+    }
+
+    fn test_case(
+        &self,
+        cuke: &cuke::Cuke<'_>,
+        dry_run: &mut DryRun,
+        id_generator: &mut dyn IdGenerator,
+    ) -> TestCase {
+        let test_case_id = id_generator.new_id();
+        let mut test_steps = Vec::new();
+
+        for step in cuke.steps() {
+            let step_match = self.match_step(step);
+            let location = *step.locations.last().unwrap();
+            match &step_match {
+                StepMatch::Undefined => dry_run.undefined_steps.push(UndefinedStep {
+                    uri: cuke.uri.to_string(),
+                    location,
+                    pickle_id: cuke.id.clone(),
+                    pickle_step_id: step.id.clone(),
+                    text: step.text.to_string(),
+                }),
+                StepMatch::Defined(_) => {}
+                StepMatch::Ambiguous(matches) => dry_run.ambiguous_steps.push(AmbiguousStep {
+                    uri: cuke.uri.to_string(),
+                    location,
+                    pickle_id: cuke.id.clone(),
+                    pickle_step_id: step.id.clone(),
+                    text: step.text.to_string(),
+                    pattern_sources: matches
+                        .iter()
+                        .map(|step_match| step_match.step_definition.pattern.source().to_string())
+                        .collect(),
+                }),
+            }
+
+            let matches = match step_match {
+                StepMatch::Undefined => Vec::new(),
+                StepMatch::Defined(step_match) => vec![step_match],
+                StepMatch::Ambiguous(matches) => matches,
+            };
+            test_steps.push(TestStep {
+                id: id_generator.new_id(),
+                pickle_step_id: step.id.clone(),
+                step_definition_ids: matches
+                    .iter()
+                    .map(|step_match| step_match.step_definition.id.clone())
+                    .collect(),
+                step_match_arguments_lists: matches
+                    .iter()
+                    .map(|step_match| StepMatchArgumentsList {
+                        step_match_arguments: step_match
+                            .arguments
+                            .iter()
+                            .map(step_match_argument)
+                            .collect(),
+                    })
+                    .collect(),
+            });
+        }
+
+        TestCase {
+            id: test_case_id,
+            pickle_id: cuke.id.clone(),
+            test_steps,
+        }
+    }
+}
+
+fn step_match_argument(argument: &Argument<'_>) -> StepMatchArgument {
+    StepMatchArgument {
+        parameter_type_name: argument.parameter_type().name().to_string(),
+        group: group(argument.group()),
+    }
+}
+
+fn group(group: &cucumber_expressions::Group<'_>) -> Group {
+    Group {
+        start: group.span.as_ref().map(|span| span.start),
+        value: group.value.map(str::to_string),
+        children: group.children.iter().map(self::group).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::cuke::Compiler;
+    use crate::{DocumentBuilder, Parser};
+
+    use super::*;
+
+    #[test]
+    fn dry_runs_cukes() {
+        let source = r#"
+Feature: Dry run
+  Background:
+    Given 3 cukes
+
+  Scenario: Eating
+    When I eat 2 "green" cukes
+    Then I am full
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = parser.parse_str(source).unwrap();
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let cukes = Compiler::new(&mut id_generator)
+            .compile(&gherkin_document)
+            .unwrap();
+
+        let mut registry = StepRegistry::new();
+        registry
+            .define_cucumber_expression("{int} cukes", &mut id_generator)
+            .unwrap();
+        registry
+            .define_regular_expression(Regex::new(r"^(\d+) cukes$").unwrap(), &mut id_generator);
+        let eat_id = registry
+            .define_cucumber_expression("I eat {int} {string} cukes", &mut id_generator)
+            .unwrap()
+            .id
+            .clone();
+
+        let dry_run = registry.dry_run(&cukes, &mut id_generator);
+        assert!(!dry_run.is_successful());
+
+        assert_eq!(dry_run.undefined_steps.len(), 1);
+        let undefined_step = &dry_run.undefined_steps[0];
+        assert_eq!(undefined_step.text, "I am full");
+        assert_eq!(
+            undefined_step.location,
+            cuke::Location {
+                line: 8,
+                column: 10
+            }
+        );
+        assert_eq!(undefined_step.pickle_id, cukes[0].id);
+
+        assert_eq!(dry_run.ambiguous_steps.len(), 1);
+        let ambiguous_step = &dry_run.ambiguous_steps[0];
+        assert_eq!(ambiguous_step.text, "3 cukes");
+        assert_eq!(
+            ambiguous_step.pattern_sources,
+            vec!["{int} cukes", r"^(\d+) cukes$"]
+        );
+
+        assert_eq!(dry_run.envelopes.len(), 4);
+        let test_case = match &dry_run.envelopes[3] {
+            StepEnvelope::TestCase(test_case) => test_case,
+            envelope => panic!("Expected a test case, got {:?}", envelope),
+        };
+        assert_eq!(test_case.pickle_id, cukes[0].id);
+        assert_eq!(test_case.test_steps.len(), 3);
+        assert_eq!(test_case.test_steps[0].step_definition_ids.len(), 2);
+        assert!(test_case.test_steps[2].step_definition_ids.is_empty());
+
+        let eat_step = &test_case.test_steps[1];
+        assert_eq!(eat_step.step_definition_ids, vec![eat_id]);
+        let arguments = &eat_step.step_match_arguments_lists[0].step_match_arguments;
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[0].parameter_type_name, "int");
+        assert_eq!(arguments[0].group.value.as_ref().unwrap(), "2");
+        assert_eq!(arguments[1].parameter_type_name, "string");
+        assert_eq!(arguments[1].group.start, Some(8));
+        assert_eq!(arguments[1].group.value.as_ref().unwrap(), "\"green\"");
+    }
+
+    #[test]
+    fn serializes_envelopes_like_messages() {
+        let mut id_generator = IncrementingIdGenerator::new();
+        let mut registry = StepRegistry::new();
+        registry.define_regular_expression(Regex::new(r"^a (\w+)$").unwrap(), &mut id_generator);
+
+        let json = serde_json::to_string(&registry.step_definition_envelopes()[0]).unwrap();
+        assert_eq!(
+            json,
+            r#"{"stepDefinition":{"id":"0","pattern":{"source":"^a (\\w+)$","type":"REGULAR_EXPRESSION"}}}"#
+        );
+        let envelope: StepEnvelope = serde_json::from_str(&json).unwrap();
+        assert_eq!(envelope, registry.step_definition_envelopes()[0]);
+    }
+}
@@ -0,0 +1,274 @@
+use crate::cucumber_expressions::generate_expression;
+use crate::cuke;
+use crate::StepKeywordType;
+
+/// The template of a snippet with the placeholders `{{keyword}}` (`given`, `when` or `then`),
+/// `{{expression}}` (a string literal), `{{function_name}}`, `{{world}}` and `{{parameters}}`
+/// (the parameters after the world, each preceded by `, `).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SnippetTemplate {
+    pub source: String,
+    /// The parameter for a step with a data table.
+    pub table_parameter: String,
+    /// The parameter for a step with a doc string.
+    pub doc_string_parameter: String,
+}
+
+impl SnippetTemplate {
+    /// The attribute macro style of `cucumber-rust`, which passes data tables and doc strings
+    /// with the step.
+    pub fn attribute_macro() -> SnippetTemplate {
+        SnippetTemplate {
+            source: concat!(
+                "#[{{keyword}}(expr = {{expression}})]\n",
+                "fn {{function_name}}(world: &mut {{world}}{{parameters}}) {\n",
+                "    todo!()\n",
+                "}\n",
+            )
+            .to_string(),
+            table_parameter: "step: &Step".to_string(),
+            doc_string_parameter: "step: &Step".to_string(),
+        }
+    }
+
+    /// A closure which is registered with a method named after the keyword.
+    pub fn closure() -> SnippetTemplate {
+        SnippetTemplate {
+            source: concat!(
+                "steps.{{keyword}}({{expression}}, |world: &mut {{world}}{{parameters}}| {\n",
+                "    todo!()\n",
+                "});\n",
+            )
+            .to_string(),
+            table_parameter: "table: &Table".to_string(),
+            doc_string_parameter: "doc_string: &str".to_string(),
+        }
+    }
+}
+
+/// Generates ready-to-paste step definitions for steps which don't match any step definition.
+#[derive(Debug, Clone)]
+pub struct SnippetGenerator {
+    template: SnippetTemplate,
+    world_type: String,
+}
+
+impl Default for SnippetGenerator {
+    fn default() -> Self {
+        SnippetGenerator::new(SnippetTemplate::attribute_macro())
+    }
+}
+
+impl SnippetGenerator {
+    pub fn new(template: SnippetTemplate) -> SnippetGenerator {
+        SnippetGenerator {
+            template,
+            world_type: "World".to_string(),
+        }
+    }
+
+    /// Sets the type of the world, which is `World` by default.
+    pub fn with_world_type(mut self, world_type: &str) -> SnippetGenerator {
+        self.world_type = world_type.to_string();
+        self
+    }
+
+    pub fn snippet(&self, step: &cuke::Step<'_>) -> String {
+        let expression = generate_expression(&step.text);
+
+        let mut parameters = String::new();
+        for parameter in &expression.parameters {
+            let parameter_type = match parameter.type_name {
+                "int" => "i32",
+                "float" => "f64",
+                _ => "String",
+            };
+            parameters.push_str(&format!(", {}: {}", parameter.name, parameter_type));
+        }
+        match &step.argument {
+            Some(cuke::Argument::Table(_)) => {
+                parameters.push_str(", ");
+                parameters.push_str(&self.template.table_parameter);
+            }
+            Some(cuke::Argument::String(_)) => {
+                parameters.push_str(", ");
+                parameters.push_str(&self.template.doc_string_parameter);
+            }
+            None => {}
+        }
+
+        let keyword = match step.keyword_type {
+            StepKeywordType::Action => "when",
+            StepKeywordType::Outcome => "then",
+            _ => "given",
+        };
+
+        render(&self.template.source, |placeholder| match placeholder {
+            "keyword" => Some(keyword.to_string()),
+            "expression" => Some(string_literal(&expression.source)),
+            "function_name" => Some(function_name(&expression.source)),
+            "world" => Some(self.world_type.clone()),
+            "parameters" => Some(parameters.clone()),
+            _ => None,
+        })
+    }
+}
+
+/// Replaces the `{{placeholder}}`s of the template, keeping unknown ones.
+fn render<F>(template: &str, value: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let replacement = rest
+            .find("}}")
+            .and_then(|end| value(&rest[2..end]).map(|value| (value, end + 2)));
+        match replacement {
+            Some((value, end)) => {
+                result.push_str(&value);
+                rest = &rest[end..];
+            }
+            None => {
+                result.push_str("{{");
+                rest = &rest[2..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Uses a raw string literal if the text contains backslashes or quotes.
+fn string_literal(text: &str) -> String {
+    if (text.contains('\\') || text.contains('"')) && !text.contains("\"#") {
+        format!("r#\"{}\"#", text)
+    } else {
+        format!("{:?}", text)
+    }
+}
+
+/// Joins the ASCII words of the expression without its parameters in snake case.
+fn function_name(expression: &str) -> String {
+    let text = ["{int}", "{float}", "{string}"]
+        .iter()
+        .fold(expression.to_string(), |text, parameter| {
+            text.replace(parameter, "")
+        });
+    let words = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<String>>();
+
+    let name = words.join("_");
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => name,
+        Some(_) => format!("step_{}", name),
+        None => "step".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::cuke::Compiler;
+    use crate::{DocumentBuilder, Parser};
+
+    use super::*;
+
+    fn snippets(source: &str, generator: &SnippetGenerator) -> Vec<String> {
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = parser.parse_str(source).unwrap();
+
+        let mut id_generator = IncrementingIdGenerator::new();
+        let cukes = Compiler::new(&mut id_generator)
+            .compile(&gherkin_document)
+            .unwrap();
+        cukes[0]
+            .steps()
+            .map(|step| generator.snippet(step))
+            .collect()
+    }
+
+    #[test]
+    fn renders_attribute_macros() {
+        let source = r#"
+Feature: Snippets
+  Scenario: Eating
+    Given I have 3 "green" cukes
+    When I eat 1.5 cukes (slowly)
+    Then the basket contains:
+      | color |
+      | green |
+"#;
+        let snippets = snippets(source, &SnippetGenerator::default());
+        assert_eq!(
+            snippets,
+            vec![
+                concat!(
+                    "#[given(expr = \"I have {int} {string} cukes\")]\n",
+                    "fn i_have_cukes(world: &mut World, int: i32, string: String) {\n",
+                    "    todo!()\n",
+                    "}\n",
+                ),
+                concat!(
+                    "#[when(expr = r#\"I eat {float} cukes \\(slowly)\"#)]\n",
+                    "fn i_eat_cukes_slowly(world: &mut World, float: f64) {\n",
+                    "    todo!()\n",
+                    "}\n",
+                ),
+                concat!(
+                    "#[then(expr = \"the basket contains:\")]\n",
+                    "fn the_basket_contains(world: &mut World, step: &Step) {\n",
+                    "    todo!()\n",
+                    "}\n",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_closures() {
+        let source = r#"
+Feature: Snippets
+  Scenario: Reading
+    * 2 books with the text:
+      """
+      Once upon a time
+      """
+"#;
+        let generator =
+            SnippetGenerator::new(SnippetTemplate::closure()).with_world_type("Library");
+        assert_eq!(
+            snippets(source, &generator),
+            vec![concat!(
+                "steps.given(\"{int} books with the text:\", ",
+                "|world: &mut Library, int: i32, doc_string: &str| {\n",
+                "    todo!()\n",
+                "});\n",
+            )]
+        );
+    }
+
+    #[test]
+    fn renders_custom_templates() {
+        assert_eq!(
+            render("{{a}} {{unknown}} {{b", |placeholder| match placeholder {
+                "a" => Some("A".to_string()),
+                _ => None,
+            }),
+            "A {{unknown}} {{b"
+        );
+        assert_eq!(function_name("{int} cukes"), "cukes");
+        assert_eq!(function_name("3d cukes"), "step_3d_cukes");
+        assert_eq!(function_name("€"), "step");
+    }
+}
@@ -0,0 +1,14 @@
+use cucumber_messages::ast;
+use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+use crate::{DocumentBuilder, Parser};
+
+/// Parses the source with fresh incrementing ids and sets the uri of the document.
+pub(crate) fn parse_document(uri: &str, source: &str) -> ast::GherkinDocument {
+    let mut id_generator = IncrementingIdGenerator::new();
+    let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+    let mut parser = Parser::with_builder(builder);
+    let mut gherkin_document = parser.parse_str(source).unwrap();
+    gherkin_document.uri = uri.to_string();
+    gherkin_document
+}
@@ -1,29 +1,43 @@
+use std::borrow::Cow;
 use std::sync::Arc;
 
-use crate::dialect::Dialect;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use crate::dialect::{Dialect, StepKeywordType};
+use crate::dialect_provider::BuiltInDialectProvider;
 use crate::line::{Line, LineSpan};
-use crate::parser::TokenType;
+use crate::parser::{DialectProvider, TokenType};
 use crate::Location;
 
+/// A line of a source matched by the token matcher.
+///
+/// Tokens serialize to an object with the matched parts, the text of the line and the
+/// language of the dialect, e.g.
+/// `{"type":"StepLine","location":{"line":3,"column":5},"line":"    Given a cuke",`
+/// `"keyword":"Given ","keywordType":"Context","text":"a cuke","indent":4,"language":"en"}`.
+/// Only tokens of built-in dialects can be deserialized.
 #[derive(Debug, Clone)]
-pub struct Token {
-    pub line: Option<Line>,
+pub struct Token<'s> {
+    pub line: Option<Line<'s>>,
     pub matched_type: Option<TokenType>,
-    pub matched_keyword: Option<String>,
-    pub matched_text: Option<String>,
-    pub matched_items: Vec<LineSpan>,
+    pub matched_keyword: Option<Cow<'s, str>>,
+    pub matched_keyword_type: Option<StepKeywordType>,
+    pub matched_text: Option<Cow<'s, str>>,
+    pub matched_items: Vec<LineSpan<'s>>,
     pub matched_indent: Option<u32>,
     pub matched_dialect: Option<Arc<Dialect>>,
     pub location: Option<Location>,
 }
 
-impl Token {
-    pub fn new(line: Option<Line>, location: Option<Location>) -> Token {
+impl<'s> Token<'s> {
+    pub fn new(line: Option<Line<'s>>, location: Option<Location>) -> Token<'s> {
         Token {
             line,
             location,
             matched_type: None,
             matched_keyword: None,
+            matched_keyword_type: None,
             matched_text: None,
             matched_items: Vec::new(),
             matched_indent: None,
@@ -31,6 +45,25 @@ impl Token {
         }
     }
 
+    /// Copies the borrowed parts of the token, so it can outlive the source.
+    pub fn into_owned(self) -> Token<'static> {
+        Token {
+            line: self.line.map(Line::into_owned),
+            matched_type: self.matched_type,
+            matched_keyword: self.matched_keyword.map(into_owned_cow),
+            matched_keyword_type: self.matched_keyword_type,
+            matched_text: self.matched_text.map(into_owned_cow),
+            matched_items: self
+                .matched_items
+                .into_iter()
+                .map(LineSpan::into_owned)
+                .collect(),
+            matched_indent: self.matched_indent,
+            matched_dialect: self.matched_dialect,
+            location: self.location,
+        }
+    }
+
     pub fn is_eof(&self) -> bool {
         self.line.is_none()
     }
@@ -48,7 +81,99 @@ impl Token {
         }
     }
 
-    pub fn unwrap_line(&self) -> &Line {
+    pub fn unwrap_line(&self) -> &Line<'s> {
         &self.line.as_ref().expect("token line")
     }
 }
+
+fn into_owned_cow(text: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(text.into_owned())
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SerializedToken<'a> {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    matched_type: Option<TokenType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keyword: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keyword_type: Option<StepKeywordType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    items: &'a [LineSpan<'a>],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    indent: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<&'a str>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeserializedToken {
+    #[serde(rename = "type")]
+    matched_type: Option<TokenType>,
+    location: Option<Location>,
+    line: Option<String>,
+    keyword: Option<String>,
+    keyword_type: Option<StepKeywordType>,
+    text: Option<String>,
+    #[serde(default)]
+    items: Vec<LineSpan<'static>>,
+    indent: Option<u32>,
+    language: Option<String>,
+}
+
+impl Serialize for Token<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedToken {
+            matched_type: self.matched_type,
+            location: self.location,
+            line: self.line.as_ref().map(|line| line.get_text(0)),
+            keyword: self.matched_keyword.as_deref(),
+            keyword_type: self.matched_keyword_type,
+            text: self.matched_text.as_deref(),
+            items: &self.matched_items,
+            indent: self.matched_indent,
+            language: self
+                .matched_dialect
+                .as_ref()
+                .map(|dialect| dialect.get_language().as_str()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Token<'static> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let token = DeserializedToken::deserialize(deserializer)?;
+        let location = token.location.unwrap_or_else(|| Location::new(0, 0));
+
+        let matched_dialect = match token.language {
+            Some(language) => {
+                let dialect = BuiltInDialectProvider::default()
+                    .get_dialect(&language, location)
+                    .map_err(de::Error::custom)?;
+                Some(dialect)
+            }
+            None => None,
+        };
+
+        Ok(Token {
+            line: token.line.map(|text| Line::new(text, location.line)),
+            matched_type: token.matched_type,
+            matched_keyword: token.keyword.map(Cow::Owned),
+            matched_keyword_type: token.keyword_type,
+            matched_text: token.text.map(Cow::Owned),
+            matched_items: token.items,
+            matched_indent: token.indent,
+            matched_dialect,
+            location: token.location,
+        })
+    }
+}
@@ -23,7 +23,7 @@ impl Default for TokenFormatterBuilder {
 impl parser::Builder for TokenFormatterBuilder {
     type BuilderResult = String;
 
-    fn build(&mut self, token: Token) -> Result<()> {
+    fn build(&mut self, token: Token<'_>) -> Result<()> {
         let formatted_token = format_token(token);
         self.tokens_text_builder += &formatted_token;
         self.tokens_text_builder += "\n";
@@ -38,14 +38,14 @@ impl parser::Builder for TokenFormatterBuilder {
         Ok(())
     }
 
-    fn get_result(&mut self) -> String {
-        mem::replace(&mut self.tokens_text_builder, String::new())
+    fn get_result(&mut self) -> Result<String> {
+        Ok(mem::replace(&mut self.tokens_text_builder, String::new()))
     }
 
     fn reset(&mut self) {}
 }
 
-fn format_token(token: Token) -> String {
+fn format_token(token: Token<'_>) -> String {
     if token.is_eof() {
         return String::from("EOF");
     }
@@ -74,7 +74,7 @@ fn format_option_string<S: ToString>(string: &Option<S>) -> String {
     }
 }
 
-fn format_line_spans(spans: &[LineSpan]) -> String {
+fn format_line_spans(spans: &[LineSpan<'_>]) -> String {
     if spans.is_empty() {
         String::new()
     } else {
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::default::Default;
 use std::sync::Arc;
 
@@ -57,14 +58,14 @@ impl<DP: DialectProvider> TokenMatcher<DP> {
         }
     }
 
-    fn set_token_matched(
+    fn set_token_matched<'s>(
         &self,
-        token: &mut Token,
+        token: &mut Token<'s>,
         matched_type: TokenType,
-        text: Option<String>,
-        keyword: Option<String>,
+        text: Option<Cow<'s, str>>,
+        keyword: Option<Cow<'s, str>>,
         indent: Option<u32>,
-        items: Vec<LineSpan>,
+        items: Vec<LineSpan<'s>>,
     ) {
         token.matched_type = Some(matched_type);
         token.matched_keyword = keyword;
@@ -84,13 +85,11 @@ impl<DP: DialectProvider> TokenMatcher<DP> {
         token.location = Some(token_location);
     }
 
-    fn unescape_doc_string(&self, text: &str) -> String {
-        match self.active_doc_string_separator.as_deref() {
-            Some(constant::DOCSTRING_SEPARATOR) => {
-                text.replace(r#"\"\"\""#, constant::DOCSTRING_SEPARATOR)
-            }
+    fn unescape_doc_string<'s>(&self, text: Cow<'s, str>) -> Cow<'s, str> {
+        let (escaped_separator, separator) = match self.active_doc_string_separator.as_deref() {
+            Some(constant::DOCSTRING_SEPARATOR) => (r#"\"\"\""#, constant::DOCSTRING_SEPARATOR),
             Some(constant::DOCSTRING_ALTERNATIVE_SEPARATOR) => {
-                text.replace(r#"\`\`\`"#, constant::DOCSTRING_ALTERNATIVE_SEPARATOR)
+                (r#"\`\`\`"#, constant::DOCSTRING_ALTERNATIVE_SEPARATOR)
             }
             Some(docstring_separator) => {
                 panic!(
@@ -98,43 +97,58 @@ impl<DP: DialectProvider> TokenMatcher<DP> {
                     docstring_separator
                 );
             }
-            None => text.to_owned(),
+            None => return text,
+        };
+
+        // only allocate for the rare lines which actually contain an escaped separator
+        if text.contains(escaped_separator) {
+            Cow::Owned(text.replace(escaped_separator, separator))
+        } else {
+            text
         }
     }
 
-    fn match_title_line(
+    fn match_title_line<'k, K>(
         &self,
-        token: &mut Token,
+        token: &mut Token<'_>,
         token_type: TokenType,
-        keywords: &[String],
-    ) -> bool {
-        for keyword in keywords {
-            if token.unwrap_line().starts_with_title_keyword(keyword) {
+        keywords: K,
+    ) -> bool
+    where
+        K: IntoIterator<Item = &'k String>,
+    {
+        // a keyword may be a prefix of another one, so the longest matching keyword wins
+        let keyword = keywords
+            .into_iter()
+            .filter(|keyword| token.unwrap_line().starts_with_title_keyword(keyword))
+            .max_by_key(|keyword| keyword.len());
+
+        match keyword {
+            Some(keyword) => {
                 let keyword_chars_count = keyword.chars().count();
                 let separator_chars_count = constant::TITLE_KEYWORD_SEPARATOR.chars().count();
-                let title = {
+                let (title, keyword) = {
                     let line = token.unwrap_line();
-                    line.get_rest_trimmed(keyword_chars_count + separator_chars_count)
-                        .to_owned()
+                    let title = line.to_rest_trimmed(keyword_chars_count + separator_chars_count);
+                    (title, line.to_keyword(keyword))
                 };
                 self.set_token_matched(
                     token,
                     token_type,
                     Some(title),
-                    Some(keyword.to_owned()),
+                    Some(keyword),
                     None,
                     Vec::new(),
                 );
-                return true;
+                true
             }
+            None => false,
         }
-
-        false
     }
 
     fn match_doc_string_separator(
         &mut self,
-        token: &mut Token,
+        token: &mut Token<'_>,
         separator: &str,
         is_open: bool,
     ) -> bool {
@@ -144,18 +158,19 @@ impl<DP: DialectProvider> TokenMatcher<DP> {
                 let separator_chars_count = separator.chars().count();
                 self.active_doc_string_separator = Some(separator.to_owned());
                 self.indent_to_remove = line.indent();
-                Some(line.get_rest_trimmed(separator_chars_count).to_owned())
+                Some(line.to_rest_trimmed(separator_chars_count))
             } else {
                 self.active_doc_string_separator = None;
                 self.indent_to_remove = 0;
                 None
             };
 
+            let keyword = token.unwrap_line().to_keyword(separator);
             self.set_token_matched(
                 token,
                 TokenType::DocStringSeparator,
                 content_type,
-                Some(separator.to_owned()),
+                Some(keyword),
                 None,
                 Vec::new(),
             );
@@ -167,7 +182,7 @@ impl<DP: DialectProvider> TokenMatcher<DP> {
 }
 
 impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
-    fn match_eof(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_eof(&mut self, token: &mut Token<'_>) -> Result<bool> {
         if token.is_eof() {
             self.set_token_matched(token, TokenType::Eof, None, None, None, Vec::new());
             return Ok(true);
@@ -175,7 +190,7 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
         Ok(false)
     }
 
-    fn match_empty(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_empty(&mut self, token: &mut Token<'_>) -> Result<bool> {
         if token.unwrap_line().is_empty() {
             self.set_token_matched(token, TokenType::Empty, None, None, None, Vec::new());
             return Ok(true);
@@ -183,10 +198,10 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
         Ok(false)
     }
 
-    fn match_comment(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_comment(&mut self, token: &mut Token<'_>) -> Result<bool> {
         if token.unwrap_line().starts_with(constant::COMMENT_PREFIX) {
             // take the entire line
-            let text = token.unwrap_line().get_text(0).to_owned();
+            let text = token.unwrap_line().to_text(0);
             self.set_token_matched(
                 token,
                 TokenType::Comment,
@@ -200,7 +215,7 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
         Ok(false)
     }
 
-    fn match_tag_line(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_tag_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
         if token.unwrap_line().starts_with(constant::TAG_PREFIX) {
             let tags = token.unwrap_line().get_tags()?;
             self.set_token_matched(token, TokenType::TagLine, None, None, None, tags);
@@ -209,56 +224,64 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
         Ok(false)
     }
 
-    fn match_feature_line(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_feature_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
         let keywords = self.current_dialect.get_feature_keywords();
         let is_match = self.match_title_line(token, TokenType::FeatureLine, keywords);
         Ok(is_match)
     }
 
-    fn match_rule_line(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_rule_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
         let keywords = self.current_dialect.get_rule_keywords();
         let is_match = self.match_title_line(token, TokenType::RuleLine, keywords);
         Ok(is_match)
     }
 
-    fn match_background_line(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_background_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
         let keywords = self.current_dialect.get_background_keywords();
         let is_match = self.match_title_line(token, TokenType::BackgroundLine, keywords);
         Ok(is_match)
     }
 
-    fn match_scenario_line(&mut self, token: &mut Token) -> Result<bool> {
-        let scenario_keywords = self.current_dialect.get_scenario_keywords();
-        let scenario_outline_keywords = self.current_dialect.get_scenario_outline_keywords();
-        let is_match = self.match_title_line(token, TokenType::ScenarioLine, scenario_keywords)
-            || self.match_title_line(token, TokenType::ScenarioLine, scenario_outline_keywords);
+    fn match_scenario_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        let keywords = self
+            .current_dialect
+            .get_scenario_keywords()
+            .iter()
+            .chain(self.current_dialect.get_scenario_outline_keywords());
+        let is_match = self.match_title_line(token, TokenType::ScenarioLine, keywords);
         Ok(is_match)
     }
 
-    fn match_examples_line(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_examples_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
         let keywords = self.current_dialect.get_examples_keywords();
         let is_match = self.match_title_line(token, TokenType::ExamplesLine, keywords);
         Ok(is_match)
     }
 
-    fn match_step_line(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_step_line(&mut self, token: &mut Token<'_>) -> Result<bool> {
+        // ordered longest keyword first
         let keywords = self.current_dialect.get_step_keywords();
 
         for keyword in keywords {
             if token.unwrap_line().starts_with(keyword) {
                 let keyword_chars_count = keyword.chars().count();
-                let step_text = token
-                    .unwrap_line()
-                    .get_rest_trimmed(keyword_chars_count)
-                    .to_owned();
+                let (step_text, matched_keyword) = {
+                    let line = token.unwrap_line();
+                    (
+                        line.to_rest_trimmed(keyword_chars_count),
+                        line.to_keyword(keyword),
+                    )
+                };
                 self.set_token_matched(
                     token,
                     TokenType::StepLine,
                     Some(step_text),
-                    Some(keyword.to_owned()),
+                    Some(matched_keyword),
                     None,
                     Vec::new(),
                 );
+                token.matched_keyword_type =
+                    Some(self.current_dialect.get_step_keyword_type(keyword));
                 return Ok(true);
             }
         }
@@ -266,7 +289,7 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
         Ok(false)
     }
 
-    fn match_doc_string_separator(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_doc_string_separator(&mut self, token: &mut Token<'_>) -> Result<bool> {
         let is_match = match self.active_doc_string_separator.to_owned() {
             Some(ref separator) => {
                 // close
@@ -285,7 +308,7 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
         Ok(is_match)
     }
 
-    fn match_table_row(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_table_row(&mut self, token: &mut Token<'_>) -> Result<bool> {
         if token
             .unwrap_line()
             .starts_with(constant::TABLE_CELL_SEPARATOR)
@@ -297,7 +320,7 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
         Ok(false)
     }
 
-    fn match_language(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_language(&mut self, token: &mut Token<'_>) -> Result<bool> {
         let language = {
             let line_text = token.unwrap_line().get_text(0);
             let captures = LANGUAGE_PATTERN.captures(line_text);
@@ -312,7 +335,7 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
             self.set_token_matched(
                 token,
                 TokenType::Language,
-                Some(language),
+                Some(Cow::Owned(language)),
                 None,
                 None,
                 Vec::new(),
@@ -329,11 +352,11 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
         Ok(false)
     }
 
-    fn match_other(&mut self, token: &mut Token) -> Result<bool> {
+    fn match_other(&mut self, token: &mut Token<'_>) -> Result<bool> {
         // take the entire line, except removing DocString indents
         let text = {
             let line = token.unwrap_line();
-            let line_text = line.get_text(self.indent_to_remove as isize);
+            let line_text = line.to_text(self.indent_to_remove as isize);
             self.unescape_doc_string(line_text)
         };
         self.set_token_matched(
@@ -356,3 +379,81 @@ impl<DP: DialectProvider> TokenMatch for TokenMatcher<DP> {
             .expect("get default dialect");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::line::Line;
+
+    use super::*;
+
+    #[test]
+    fn matches_every_built_in_keyword_to_itself() {
+        let dialect_provider = BuiltInDialectProvider::default();
+
+        for language in dialect_provider.get_languages() {
+            let dialect = dialect_provider
+                .get_dialect(language, Location::new(0, 0))
+                .unwrap();
+            let mut token_matcher = TokenMatcher::with_default_dialect_name(language);
+
+            let title_keywords: Vec<(&Vec<String>, TokenType)> = vec![
+                (dialect.get_feature_keywords(), TokenType::FeatureLine),
+                (dialect.get_rule_keywords(), TokenType::RuleLine),
+                (dialect.get_background_keywords(), TokenType::BackgroundLine),
+                (dialect.get_scenario_keywords(), TokenType::ScenarioLine),
+                (
+                    dialect.get_scenario_outline_keywords(),
+                    TokenType::ScenarioLine,
+                ),
+                (dialect.get_examples_keywords(), TokenType::ExamplesLine),
+            ];
+            for (keywords, token_type) in title_keywords {
+                for keyword in keywords {
+                    let text = format!("  {}: title", keyword);
+                    let mut token = create_token(&text);
+                    let is_match = match token_type {
+                        TokenType::FeatureLine => token_matcher.match_feature_line(&mut token),
+                        TokenType::RuleLine => token_matcher.match_rule_line(&mut token),
+                        TokenType::BackgroundLine => {
+                            token_matcher.match_background_line(&mut token)
+                        }
+                        TokenType::ScenarioLine => token_matcher.match_scenario_line(&mut token),
+                        TokenType::ExamplesLine => token_matcher.match_examples_line(&mut token),
+                        _ => unreachable!(),
+                    };
+                    assert!(is_match.unwrap(), "{}: {:?}", language, keyword);
+                    assert_eq!(token.matched_keyword.as_deref(), Some(keyword.as_str()));
+                    assert_eq!(token.matched_text.as_deref(), Some("title"));
+                }
+            }
+
+            for keyword in dialect.get_step_keywords() {
+                let text = format!("    {}step text", keyword);
+                let mut token = create_token(&text);
+                assert!(
+                    token_matcher.match_step_line(&mut token).unwrap(),
+                    "{}: {:?}",
+                    language,
+                    keyword
+                );
+                assert_eq!(token.matched_keyword.as_deref(), Some(keyword.as_str()));
+                assert_eq!(token.matched_text.as_deref(), Some("step text"));
+            }
+        }
+    }
+
+    #[test]
+    fn prefers_longest_step_keyword() {
+        let mut token_matcher = TokenMatcher::with_default_dialect_name("sk");
+        let mut token = create_token("    A tiež niečo");
+
+        assert!(token_matcher.match_step_line(&mut token).unwrap());
+        assert_eq!(token.matched_keyword.as_deref(), Some("A tiež "));
+        assert_eq!(token.matched_text.as_deref(), Some("niečo"));
+    }
+
+    fn create_token(text: &str) -> Token<'_> {
+        let line = Line::new(text, 1);
+        Token::new(Some(line), Some(Location::new(1, 0)))
+    }
+}
@@ -28,17 +28,8 @@ impl<R: Read> From<R> for TokenScanner<R> {
     }
 }
 
-impl<'a> From<&'a str> for TokenScanner<&'a [u8]> {
-    fn from(source: &'a str) -> TokenScanner<&'a [u8]> {
-        TokenScanner {
-            reader: BufReader::new(source.as_bytes()),
-            line_number: 0,
-        }
-    }
-}
-
-impl<R: Read> TokenScan for TokenScanner<R> {
-    fn next(&mut self) -> Result<Token> {
+impl<R: Read> TokenScan<'static> for TokenScanner<R> {
+    fn next(&mut self) -> Result<Token<'static>> {
         let mut line = String::new();
         self.reader.read_line(&mut line)?;
 
@@ -66,3 +57,92 @@ impl<R: Read> TokenScan for TokenScanner<R> {
         Ok(token)
     }
 }
+
+/// Like the `TokenScanner`, but scans a string slice without copying its lines,
+/// so the tokens borrow their texts from the source.
+pub struct StrTokenScanner<'s> {
+    rest: &'s str,
+    line_number: u32,
+}
+
+impl<'s> From<&'s str> for StrTokenScanner<'s> {
+    fn from(source: &'s str) -> Self {
+        StrTokenScanner {
+            rest: source,
+            line_number: 0,
+        }
+    }
+}
+
+impl<'s> TokenScan<'s> for StrTokenScanner<'s> {
+    fn next(&mut self) -> Result<Token<'s>> {
+        let is_eof = self.rest.is_empty();
+
+        let mut line = match self.rest.find('\n') {
+            Some(index) => {
+                let line = &self.rest[..index];
+                self.rest = &self.rest[index + 1..];
+                line
+            }
+            None => {
+                let line = self.rest;
+                self.rest = "";
+                line
+            }
+        };
+        if line.ends_with('\r') {
+            line = &line[..line.len() - 1];
+        }
+
+        self.line_number += 1;
+
+        let location = Location::new(self.line_number, 0);
+
+        let token = if is_eof {
+            Token::new(None, Some(location))
+        } else {
+            let line = Line::new(line, self.line_number);
+            Token::new(Some(line), Some(location))
+        };
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_str_like_reader() {
+        let sources = [
+            "",
+            "\n",
+            "Feature: one line",
+            "Feature: lines\n  Scenario: unix\n",
+            "Feature: lines\r\n  Scenario: windows\r\n\r\n",
+            "Feature: lines\n\n\n  Scenario: no end of line  ",
+        ];
+
+        for source in sources.iter() {
+            let mut str_scanner = StrTokenScanner::from(*source);
+            let mut reader_scanner = TokenScanner::from(source.as_bytes());
+
+            loop {
+                let str_token = str_scanner.next().unwrap();
+                let reader_token = reader_scanner.next().unwrap();
+
+                let line = |token: &Token<'_>| token.location.map(|location| location.line);
+                assert_eq!(line(&str_token), line(&reader_token), "{:?}", source);
+                assert_eq!(
+                    str_token.line.as_ref().map(|line| line.get_text(0)),
+                    reader_token.line.as_ref().map(|line| line.get_text(0)),
+                    "{:?}",
+                    source
+                );
+                if str_token.is_eof() {
+                    break;
+                }
+            }
+        }
+    }
+}
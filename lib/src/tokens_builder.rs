@@ -0,0 +1,36 @@
+use std::mem;
+
+use crate::error::Result;
+use crate::parser::{self, RuleType};
+use crate::token::Token;
+
+/// Collects the tokens of a source, so they can be inspected or serialized after parsing.
+#[derive(Default)]
+pub struct TokensBuilder {
+    tokens: Vec<Token<'static>>,
+}
+
+impl parser::Builder for TokensBuilder {
+    type BuilderResult = Vec<Token<'static>>;
+
+    fn build(&mut self, token: Token<'_>) -> Result<()> {
+        self.tokens.push(token.into_owned());
+        Ok(())
+    }
+
+    fn start_rule(&mut self, _rule_type: RuleType) -> Result<()> {
+        Ok(())
+    }
+
+    fn end_rule(&mut self, _rule_type: RuleType) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_result(&mut self) -> Result<Vec<Token<'static>>> {
+        Ok(mem::replace(&mut self.tokens, Vec::new()))
+    }
+
+    fn reset(&mut self) {
+        self.tokens.clear();
+    }
+}
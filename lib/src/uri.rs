@@ -0,0 +1,9 @@
+use std::path::Path;
+
+/// Returns the folder of the document at the uri, `.` for documents without one.
+pub(crate) fn folder(uri: &str) -> String {
+    match Path::new(uri).parent() {
+        Some(parent) if parent != Path::new("") => parent.display().to_string(),
+        _ => String::from("."),
+    }
+}
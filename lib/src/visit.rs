@@ -0,0 +1,293 @@
+//! Traversal of a `GherkinDocument` by shared reference.
+//!
+//! Each method of `Visitor` visits a node type and calls the matching `walk_*` function by
+//! default, which visits the children of the node. An implementation overrides the methods of
+//! the node types it is interested in and calls the `walk_*` function itself to keep visiting
+//! the children.
+
+use cucumber_messages::ast;
+
+/// The ancestors of the visited node.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Context<'a> {
+    pub feature: Option<&'a ast::Feature>,
+    pub rule: Option<&'a ast::Rule>,
+    pub background: Option<&'a ast::Background>,
+    pub scenario: Option<&'a ast::Scenario>,
+    pub examples: Option<&'a ast::Examples>,
+    pub step: Option<&'a ast::Step>,
+}
+
+pub trait Visitor<'a> {
+    fn visit_gherkin_document(&mut self, gherkin_document: &'a ast::GherkinDocument) {
+        walk_gherkin_document(self, gherkin_document);
+    }
+
+    fn visit_comment(&mut self, _comment: &'a ast::Comment) {}
+
+    fn visit_feature(&mut self, feature: &'a ast::Feature, context: &Context<'a>) {
+        walk_feature(self, feature, context);
+    }
+
+    fn visit_rule(&mut self, rule: &'a ast::Rule, context: &Context<'a>) {
+        walk_rule(self, rule, context);
+    }
+
+    fn visit_background(&mut self, background: &'a ast::Background, context: &Context<'a>) {
+        walk_background(self, background, context);
+    }
+
+    fn visit_scenario(&mut self, scenario: &'a ast::Scenario, context: &Context<'a>) {
+        walk_scenario(self, scenario, context);
+    }
+
+    fn visit_examples(&mut self, examples: &'a ast::Examples, context: &Context<'a>) {
+        walk_examples(self, examples, context);
+    }
+
+    fn visit_step(&mut self, step: &'a ast::Step, context: &Context<'a>) {
+        walk_step(self, step, context);
+    }
+
+    fn visit_doc_string(&mut self, _doc_string: &'a ast::DocString, _context: &Context<'a>) {}
+
+    fn visit_data_table(&mut self, data_table: &'a ast::DataTable, context: &Context<'a>) {
+        walk_data_table(self, data_table, context);
+    }
+
+    /// Visits a row of a data table or examples table.
+    fn visit_table_row(&mut self, table_row: &'a ast::TableRow, context: &Context<'a>) {
+        walk_table_row(self, table_row, context);
+    }
+
+    fn visit_table_cell(&mut self, _table_cell: &'a ast::TableCell, _context: &Context<'a>) {}
+
+    fn visit_tag(&mut self, _tag: &'a ast::Tag, _context: &Context<'a>) {}
+}
+
+pub fn walk_gherkin_document<'a, V>(visitor: &mut V, gherkin_document: &'a ast::GherkinDocument)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    if let Some(feature) = &gherkin_document.feature {
+        visitor.visit_feature(feature, &Context::default());
+    }
+    for comment in &gherkin_document.comments {
+        visitor.visit_comment(comment);
+    }
+}
+
+pub fn walk_feature<'a, V>(visitor: &mut V, feature: &'a ast::Feature, context: &Context<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    let context = Context {
+        feature: Some(feature),
+        ..*context
+    };
+
+    for tag in &feature.tags {
+        visitor.visit_tag(tag, &context);
+    }
+    for feature_child in &feature.children {
+        match &feature_child.value {
+            Some(ast::FeatureChildValue::Rule(rule)) => visitor.visit_rule(rule, &context),
+            Some(ast::FeatureChildValue::Background(background)) => {
+                visitor.visit_background(background, &context)
+            }
+            Some(ast::FeatureChildValue::Scenario(scenario)) => {
+                visitor.visit_scenario(scenario, &context)
+            }
+            None => {}
+        }
+    }
+}
+
+pub fn walk_rule<'a, V>(visitor: &mut V, rule: &'a ast::Rule, context: &Context<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    let context = Context {
+        rule: Some(rule),
+        ..*context
+    };
+
+    for rule_child in &rule.children {
+        match &rule_child.value {
+            Some(ast::RuleChildValue::Background(background)) => {
+                visitor.visit_background(background, &context)
+            }
+            Some(ast::RuleChildValue::Scenario(scenario)) => {
+                visitor.visit_scenario(scenario, &context)
+            }
+            None => {}
+        }
+    }
+}
+
+pub fn walk_background<'a, V>(
+    visitor: &mut V,
+    background: &'a ast::Background,
+    context: &Context<'a>,
+) where
+    V: Visitor<'a> + ?Sized,
+{
+    let context = Context {
+        background: Some(background),
+        ..*context
+    };
+
+    for step in &background.steps {
+        visitor.visit_step(step, &context);
+    }
+}
+
+pub fn walk_scenario<'a, V>(visitor: &mut V, scenario: &'a ast::Scenario, context: &Context<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    let context = Context {
+        scenario: Some(scenario),
+        ..*context
+    };
+
+    for tag in &scenario.tags {
+        visitor.visit_tag(tag, &context);
+    }
+    for step in &scenario.steps {
+        visitor.visit_step(step, &context);
+    }
+    for examples in &scenario.examples {
+        visitor.visit_examples(examples, &context);
+    }
+}
+
+pub fn walk_examples<'a, V>(visitor: &mut V, examples: &'a ast::Examples, context: &Context<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    let context = Context {
+        examples: Some(examples),
+        ..*context
+    };
+
+    for tag in &examples.tags {
+        visitor.visit_tag(tag, &context);
+    }
+    for table_row in examples.table_header.iter().chain(&examples.table_body) {
+        visitor.visit_table_row(table_row, &context);
+    }
+}
+
+pub fn walk_step<'a, V>(visitor: &mut V, step: &'a ast::Step, context: &Context<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    let context = Context {
+        step: Some(step),
+        ..*context
+    };
+
+    match &step.argument {
+        Some(ast::Argument::DocString(doc_string)) => {
+            visitor.visit_doc_string(doc_string, &context)
+        }
+        Some(ast::Argument::DataTable(data_table)) => {
+            visitor.visit_data_table(data_table, &context)
+        }
+        None => {}
+    }
+}
+
+pub fn walk_data_table<'a, V>(
+    visitor: &mut V,
+    data_table: &'a ast::DataTable,
+    context: &Context<'a>,
+) where
+    V: Visitor<'a> + ?Sized,
+{
+    for table_row in &data_table.rows {
+        visitor.visit_table_row(table_row, context);
+    }
+}
+
+pub fn walk_table_row<'a, V>(visitor: &mut V, table_row: &'a ast::TableRow, context: &Context<'a>)
+where
+    V: Visitor<'a> + ?Sized,
+{
+    for table_cell in &table_row.cells {
+        visitor.visit_table_cell(table_cell, context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::{DocumentBuilder, Parser};
+
+    use super::*;
+
+    /// Collects the step texts with the names of their rule and background.
+    #[derive(Default)]
+    struct StepCollector {
+        steps: Vec<String>,
+        cells: usize,
+    }
+
+    impl<'a> Visitor<'a> for StepCollector {
+        fn visit_step(&mut self, step: &'a ast::Step, context: &Context<'a>) {
+            let rule = context.rule.map_or("-", |rule| rule.name.as_str());
+            let background = context
+                .background
+                .map_or("-", |background| background.name.as_str());
+            self.steps
+                .push(format!("{} / {} / {}", rule, background, step.text));
+            walk_step(self, step, context);
+        }
+
+        fn visit_table_cell(&mut self, _table_cell: &'a ast::TableCell, context: &Context<'a>) {
+            if context.examples.is_none() {
+                self.cells += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn visits_nodes_with_context() {
+        let source = r#"
+Feature: Visitor
+  Background: Setup
+    Given a step
+      | a | b |
+
+  Rule: First
+    Background: Rule setup
+      Given a rule background step
+
+    Scenario Outline: Inside
+      When an <action>
+
+      Examples:
+        | action |
+        | eat    |
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let gherkin_document = parser.parse_str(source).unwrap();
+
+        let mut collector = StepCollector::default();
+        collector.visit_gherkin_document(&gherkin_document);
+
+        assert_eq!(
+            collector.steps,
+            vec![
+                "- / Setup / a step",
+                "First / Rule setup / a rule background step",
+                "First / - / an <action>",
+            ]
+        );
+        assert_eq!(collector.cells, 2);
+    }
+}
@@ -0,0 +1,275 @@
+//! Traversal of a `GherkinDocument` by mutable reference.
+//!
+//! `VisitorMut` works like `Visitor`, but the ancestors of the visited node are borrowed
+//! mutably themselves, so the `ContextMut` only identifies them.
+
+use cucumber_messages::ast;
+
+/// The ids of the ancestors of the visited node, and the language of the feature.
+#[derive(Debug, Clone, Default)]
+pub struct ContextMut {
+    pub language: Option<String>,
+    pub rule_id: Option<String>,
+    pub background_id: Option<String>,
+    pub scenario_id: Option<String>,
+    pub examples_id: Option<String>,
+    pub step_id: Option<String>,
+}
+
+pub trait VisitorMut {
+    fn visit_gherkin_document_mut(&mut self, gherkin_document: &mut ast::GherkinDocument) {
+        walk_gherkin_document_mut(self, gherkin_document);
+    }
+
+    fn visit_comment_mut(&mut self, _comment: &mut ast::Comment) {}
+
+    fn visit_feature_mut(&mut self, feature: &mut ast::Feature, context: &ContextMut) {
+        walk_feature_mut(self, feature, context);
+    }
+
+    fn visit_rule_mut(&mut self, rule: &mut ast::Rule, context: &ContextMut) {
+        walk_rule_mut(self, rule, context);
+    }
+
+    fn visit_background_mut(&mut self, background: &mut ast::Background, context: &ContextMut) {
+        walk_background_mut(self, background, context);
+    }
+
+    fn visit_scenario_mut(&mut self, scenario: &mut ast::Scenario, context: &ContextMut) {
+        walk_scenario_mut(self, scenario, context);
+    }
+
+    fn visit_examples_mut(&mut self, examples: &mut ast::Examples, context: &ContextMut) {
+        walk_examples_mut(self, examples, context);
+    }
+
+    fn visit_step_mut(&mut self, step: &mut ast::Step, context: &ContextMut) {
+        walk_step_mut(self, step, context);
+    }
+
+    fn visit_doc_string_mut(&mut self, _doc_string: &mut ast::DocString, _context: &ContextMut) {}
+
+    fn visit_data_table_mut(&mut self, data_table: &mut ast::DataTable, context: &ContextMut) {
+        walk_data_table_mut(self, data_table, context);
+    }
+
+    /// Visits a row of a data table or examples table.
+    fn visit_table_row_mut(&mut self, table_row: &mut ast::TableRow, context: &ContextMut) {
+        walk_table_row_mut(self, table_row, context);
+    }
+
+    fn visit_table_cell_mut(&mut self, _table_cell: &mut ast::TableCell, _context: &ContextMut) {}
+
+    fn visit_tag_mut(&mut self, _tag: &mut ast::Tag, _context: &ContextMut) {}
+}
+
+pub fn walk_gherkin_document_mut<V>(visitor: &mut V, gherkin_document: &mut ast::GherkinDocument)
+where
+    V: VisitorMut + ?Sized,
+{
+    if let Some(feature) = &mut gherkin_document.feature {
+        visitor.visit_feature_mut(feature, &ContextMut::default());
+    }
+    for comment in &mut gherkin_document.comments {
+        visitor.visit_comment_mut(comment);
+    }
+}
+
+pub fn walk_feature_mut<V>(visitor: &mut V, feature: &mut ast::Feature, context: &ContextMut)
+where
+    V: VisitorMut + ?Sized,
+{
+    let context = ContextMut {
+        language: Some(feature.language.clone()),
+        ..context.clone()
+    };
+
+    for tag in &mut feature.tags {
+        visitor.visit_tag_mut(tag, &context);
+    }
+    for feature_child in &mut feature.children {
+        match &mut feature_child.value {
+            Some(ast::FeatureChildValue::Rule(rule)) => visitor.visit_rule_mut(rule, &context),
+            Some(ast::FeatureChildValue::Background(background)) => {
+                visitor.visit_background_mut(background, &context)
+            }
+            Some(ast::FeatureChildValue::Scenario(scenario)) => {
+                visitor.visit_scenario_mut(scenario, &context)
+            }
+            None => {}
+        }
+    }
+}
+
+pub fn walk_rule_mut<V>(visitor: &mut V, rule: &mut ast::Rule, context: &ContextMut)
+where
+    V: VisitorMut + ?Sized,
+{
+    let context = ContextMut {
+        rule_id: Some(rule.id.clone()),
+        ..context.clone()
+    };
+
+    for rule_child in &mut rule.children {
+        match &mut rule_child.value {
+            Some(ast::RuleChildValue::Background(background)) => {
+                visitor.visit_background_mut(background, &context)
+            }
+            Some(ast::RuleChildValue::Scenario(scenario)) => {
+                visitor.visit_scenario_mut(scenario, &context)
+            }
+            None => {}
+        }
+    }
+}
+
+pub fn walk_background_mut<V>(
+    visitor: &mut V,
+    background: &mut ast::Background,
+    context: &ContextMut,
+) where
+    V: VisitorMut + ?Sized,
+{
+    let context = ContextMut {
+        background_id: Some(background.id.clone()),
+        ..context.clone()
+    };
+
+    for step in &mut background.steps {
+        visitor.visit_step_mut(step, &context);
+    }
+}
+
+pub fn walk_scenario_mut<V>(visitor: &mut V, scenario: &mut ast::Scenario, context: &ContextMut)
+where
+    V: VisitorMut + ?Sized,
+{
+    let context = ContextMut {
+        scenario_id: Some(scenario.id.clone()),
+        ..context.clone()
+    };
+
+    for tag in &mut scenario.tags {
+        visitor.visit_tag_mut(tag, &context);
+    }
+    for step in &mut scenario.steps {
+        visitor.visit_step_mut(step, &context);
+    }
+    for examples in &mut scenario.examples {
+        visitor.visit_examples_mut(examples, &context);
+    }
+}
+
+pub fn walk_examples_mut<V>(visitor: &mut V, examples: &mut ast::Examples, context: &ContextMut)
+where
+    V: VisitorMut + ?Sized,
+{
+    let context = ContextMut {
+        examples_id: Some(examples.id.clone()),
+        ..context.clone()
+    };
+
+    for tag in &mut examples.tags {
+        visitor.visit_tag_mut(tag, &context);
+    }
+    for table_row in examples
+        .table_header
+        .iter_mut()
+        .chain(&mut examples.table_body)
+    {
+        visitor.visit_table_row_mut(table_row, &context);
+    }
+}
+
+pub fn walk_step_mut<V>(visitor: &mut V, step: &mut ast::Step, context: &ContextMut)
+where
+    V: VisitorMut + ?Sized,
+{
+    let context = ContextMut {
+        step_id: Some(step.id.clone()),
+        ..context.clone()
+    };
+
+    match &mut step.argument {
+        Some(ast::Argument::DocString(doc_string)) => {
+            visitor.visit_doc_string_mut(doc_string, &context)
+        }
+        Some(ast::Argument::DataTable(data_table)) => {
+            visitor.visit_data_table_mut(data_table, &context)
+        }
+        None => {}
+    }
+}
+
+pub fn walk_data_table_mut<V>(
+    visitor: &mut V,
+    data_table: &mut ast::DataTable,
+    context: &ContextMut,
+) where
+    V: VisitorMut + ?Sized,
+{
+    for table_row in &mut data_table.rows {
+        visitor.visit_table_row_mut(table_row, context);
+    }
+}
+
+pub fn walk_table_row_mut<V>(visitor: &mut V, table_row: &mut ast::TableRow, context: &ContextMut)
+where
+    V: VisitorMut + ?Sized,
+{
+    for table_cell in &mut table_row.cells {
+        visitor.visit_table_cell_mut(table_cell, context);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cucumber_messages::id_generator::IncrementingIdGenerator;
+
+    use crate::{DocumentBuilder, Parser};
+
+    use super::*;
+
+    /// Strips leading zeros from the cells of data tables, but not from the ones of examples.
+    struct ZeroStripper;
+
+    impl VisitorMut for ZeroStripper {
+        fn visit_table_cell_mut(&mut self, table_cell: &mut ast::TableCell, context: &ContextMut) {
+            if context.examples_id.is_none() {
+                table_cell.value = table_cell.value.trim_start_matches('0').to_string();
+            }
+        }
+    }
+
+    #[test]
+    fn visits_nodes_mutably() {
+        let source = r#"
+Feature: Visitor
+  Scenario Outline: Numbers
+    Given the numbers <number>:
+      | 007 | 42 |
+
+    Examples:
+      | number |
+      | 0815   |
+"#;
+        let mut id_generator = IncrementingIdGenerator::new();
+        let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+        let mut parser = Parser::with_builder(builder);
+        let mut gherkin_document = parser.parse_str(source).unwrap();
+
+        ZeroStripper.visit_gherkin_document_mut(&mut gherkin_document);
+
+        let scenario = match &gherkin_document.feature.as_ref().unwrap().children[0].value {
+            Some(ast::FeatureChildValue::Scenario(scenario)) => scenario,
+            value => panic!("Expected a scenario, got {:?}", value),
+        };
+        let data_table = match &scenario.steps[0].argument {
+            Some(ast::Argument::DataTable(data_table)) => data_table,
+            argument => panic!("Expected a data table, got {:?}", argument),
+        };
+        assert_eq!(data_table.rows[0].cells[0].value, "7");
+        assert_eq!(data_table.rows[0].cells[1].value, "42");
+        assert_eq!(scenario.examples[0].table_body[0].cells[0].value, "0815");
+    }
+}
@@ -0,0 +1,130 @@
+use std::fs;
+
+use gherkin::cuke::{Compiler, Diagnostic, DiagnosticKind, Location};
+use gherkin::{ast, DocumentBuilder, Error, IncrementingIdGenerator, Parser, StepKeywordType};
+
+#[test]
+fn compiles_examples_after_examples_without_header() {
+    let path = "../testdata/good/examples_without_header.feature";
+    let mut id_generator = IncrementingIdGenerator::new();
+    let mut parser = Parser::with_builder(DocumentBuilder::with_id_generator(&mut id_generator));
+    let mut gherkin_document = parser.parse_str(fs::read_to_string(path).unwrap()).unwrap();
+    gherkin_document.uri = path.to_string();
+
+    let mut compiler = Compiler::new(&mut id_generator);
+    let cukes = compiler.compile(&gherkin_document).unwrap();
+
+    let step_texts = cukes
+        .iter()
+        .map(|cuke| cuke.scenario_steps[0].text.to_string())
+        .collect::<Vec<String>>();
+    assert_eq!(step_texts, vec!["the foo", "the bar", "the baz"]);
+
+    let diagnostic = |line, kind| Diagnostic {
+        uri: path.to_string(),
+        location: Location { line, column: 5 },
+        kind,
+    };
+    assert_eq!(
+        compiler.diagnostics(),
+        &[
+            diagnostic(6, DiagnosticKind::MissingExamplesHeader),
+            diagnostic(19, DiagnosticKind::MissingExamplesHeader),
+            diagnostic(21, DiagnosticKind::EmptyExamples),
+        ][..]
+    );
+}
+
+#[test]
+fn returns_errors_for_documents_without_locations() {
+    let scenario = ast::Scenario {
+        id: String::from("1"),
+        keyword: String::from("Scenario"),
+        name: String::from("hand-written"),
+        ..ast::Scenario::default()
+    };
+    let gherkin_document = ast::GherkinDocument {
+        uri: String::from("hand-written.feature"),
+        feature: Some(ast::Feature {
+            children: vec![ast::FeatureChild {
+                value: Some(ast::FeatureChildValue::Scenario(scenario)),
+            }],
+            ..ast::Feature::default()
+        }),
+        comments: Vec::new(),
+    };
+
+    let mut id_generator = IncrementingIdGenerator::new();
+    let mut compiler = Compiler::new(&mut id_generator);
+    match compiler.compile(&gherkin_document) {
+        Err(error @ Error::InvalidDocument { .. }) => assert_eq!(
+            error.to_string(),
+            "hand-written.feature: scenario \"1\" has no location"
+        ),
+        result => panic!("expected an invalid document error, got {:?}", result),
+    }
+}
+
+#[test]
+fn drops_the_diagnostics_of_documents_without_locations() {
+    let source = r#"
+Feature: Cukes
+  Scenario Outline: eating
+    Given I eat cukes
+
+    Examples:
+      | count |
+      | 5     |
+
+  Scenario: counting
+    Given I have 5 cukes
+"#;
+    let mut id_generator = IncrementingIdGenerator::new();
+    let mut parser = Parser::with_builder(DocumentBuilder::with_id_generator(&mut id_generator));
+    let mut gherkin_document = parser.parse_str(source).unwrap();
+    gherkin_document.uri = String::from("cukes.feature");
+    let step_id = match &mut gherkin_document.feature.as_mut().unwrap().children[1].value {
+        Some(ast::FeatureChildValue::Scenario(scenario)) => {
+            scenario.steps[0].location = None;
+            scenario.steps[0].id.clone()
+        }
+        value => panic!("expected a scenario, got {:?}", value),
+    };
+
+    let mut compiler = Compiler::new(&mut id_generator);
+    match compiler.compile(&gherkin_document) {
+        Err(error @ Error::InvalidDocument { .. }) => assert_eq!(
+            error.to_string(),
+            format!("cukes.feature: step {:?} has no location", step_id)
+        ),
+        result => panic!("expected an invalid document error, got {:?}", result),
+    }
+    assert!(compiler.diagnostics().is_empty());
+}
+
+#[test]
+fn compiles_parsed_documents_with_their_step_keyword_types() {
+    let paths = [
+        "../testdata/good/minimal.feature",
+        "../testdata/bad/single_parser_error.feature",
+    ];
+    let mut id_generator = IncrementingIdGenerator::new();
+    let (parsed_documents, parse_errors) =
+        gherkin::parse_documents(&paths, &mut id_generator).unwrap();
+    assert_eq!(parsed_documents.len(), 1);
+    assert_eq!(parse_errors.len(), 1);
+
+    let parsed_document = &parsed_documents[0];
+    assert_eq!(parsed_document.gherkin_document.uri, paths[0]);
+    let mut compiler = Compiler::new(&mut id_generator);
+    let cukes = compiler
+        .compile_with(
+            &parsed_document.gherkin_document,
+            &parsed_document.step_keyword_types,
+        )
+        .unwrap();
+    assert_eq!(
+        cukes[0].scenario_steps[0].keyword_type,
+        StepKeywordType::Context
+    );
+}
@@ -0,0 +1,69 @@
+use std::fs;
+
+use serde_json::Value;
+
+use gherkin::cucumber_expressions::{CucumberExpression, ParameterTypeRegistry};
+
+/// Reads the blocks of a fixture, which are separated by `---` lines.
+fn read_blocks(path: &str) -> Vec<Vec<String>> {
+    let content = fs::read_to_string(path).unwrap();
+    content
+        .split("\n---\n")
+        .map(|block| block.lines().map(str::to_string).collect())
+        .collect()
+}
+
+/// Makes integers and floats with the same value comparable.
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Number(number) => Value::from(number.as_f64().unwrap()),
+        Value::Array(values) => Value::Array(values.into_iter().map(normalize).collect()),
+        value => value,
+    }
+}
+
+#[test]
+fn matches_examples() {
+    let registry = ParameterTypeRegistry::new();
+
+    for block in read_blocks("../testdata/cucumber_expressions/examples.txt") {
+        let (expression, text, expected_args) = (&block[0], &block[1], &block[2]);
+        let cucumber_expression = CucumberExpression::new(expression, &registry).unwrap();
+
+        let args = match cucumber_expression.match_text(text) {
+            Some(arguments) => Value::Array(
+                arguments
+                    .iter()
+                    .map(|argument| argument.value().unwrap())
+                    .collect(),
+            ),
+            None => Value::Null,
+        };
+        let expected_args: Value = serde_json::from_str(expected_args).unwrap();
+
+        assert_eq!(
+            normalize(args),
+            normalize(expected_args),
+            "expression: {}, text: {}",
+            expression,
+            text
+        );
+    }
+}
+
+#[test]
+fn compiles_expressions_to_regexes() {
+    let registry = ParameterTypeRegistry::new();
+
+    for block in read_blocks("../testdata/cucumber_expressions/transformations.txt") {
+        let (expression, expected_regex) = (&block[0], &block[1]);
+        let cucumber_expression = CucumberExpression::new(expression, &registry).unwrap();
+
+        assert_eq!(
+            cucumber_expression.regex().as_str(),
+            expected_regex,
+            "expression: {}",
+            expression
+        );
+    }
+}
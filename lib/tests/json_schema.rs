@@ -0,0 +1,194 @@
+use std::fs;
+use std::path::PathBuf;
+
+use cucumber_messages::Message;
+use serde_json::{json, Value};
+
+use gherkin::export::json::{schema, to_json};
+use gherkin::{IncludeOptions, IncrementingIdGenerator};
+
+const SCHEMA_PATH: &str = "../schema/gherkin-document.schema.json";
+const GOOD_TESTDATA_PATH: &str = "../testdata/good";
+
+#[test]
+fn schema_file_is_up_to_date() {
+    let schema_file = fs::read_to_string(SCHEMA_PATH).unwrap();
+    let schema_file = serde_json::from_str::<Value>(&schema_file).unwrap();
+
+    // regenerate with `gherkin schema > schema/gherkin-document.schema.json`
+    assert_eq!(schema_file, schema());
+}
+
+#[test]
+fn good_testdata_matches_schema() {
+    let schema = schema();
+    let mut paths = fs::read_dir(GOOD_TESTDATA_PATH)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let file_name = path.file_name().unwrap().to_string_lossy();
+            file_name.ends_with(".feature") || file_name.ends_with(".feature.md")
+        })
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    let include_options = IncludeOptions {
+        source: false,
+        gherkin_document: true,
+        pickles: false,
+    };
+    let mut id_generator = IncrementingIdGenerator::new();
+    let messages = gherkin::parse_paths(&paths, include_options, &mut id_generator)
+        .unwrap()
+        .into_iter()
+        .filter_map(|envelope| envelope.message);
+
+    let mut count = 0;
+    for message in messages {
+        match message {
+            Message::GherkinDocument(gherkin_document) => {
+                let document = serde_json::to_value(to_json(&gherkin_document)).unwrap();
+                if let Err(error) = validate(&schema, &schema, &document, "") {
+                    panic!(
+                        "{} does not match the schema: {}",
+                        gherkin_document.uri, error
+                    );
+                }
+                count += 1;
+            }
+            message => panic!("unexpected message {:?}", message),
+        }
+    }
+    assert_eq!(count, paths.len());
+}
+
+#[test]
+fn validation_rejects_other_shapes() {
+    let schema = schema();
+    let document = json!({
+        "uri": "cukes.feature",
+        "feature": {
+            "location": { "line": 1, "column": 1 },
+            "tags": [],
+            "language": "en",
+            "keyword": "Feature",
+            "name": "Cukes",
+            "description": "",
+            "children": [{ "type": "examples" }],
+        },
+        "comments": [],
+    });
+
+    let error = validate(&schema, &schema, &document, "").unwrap_err();
+    assert_eq!(error, "/feature: matches none of the schemas");
+    assert!(validate(&schema, &schema, &json!({ "uri": "cukes.feature" }), "").is_err());
+}
+
+#[test]
+#[should_panic(expected = "unsupported schema keyword pattern")]
+fn validation_fails_on_unsupported_keywords() {
+    let schema = json!({ "type": "string", "pattern": "^cukes$" });
+    let _ = validate(&schema, &schema, &json!("cukes"), "");
+}
+
+/// The keywords of JSON Schema which are implemented by `validate`.
+const KEYWORDS: &[&str] = &[
+    "$schema",
+    "title",
+    "definitions",
+    "$ref",
+    "oneOf",
+    "const",
+    "type",
+    "minimum",
+    "items",
+    "properties",
+    "required",
+    "additionalProperties",
+];
+
+/// Validates a value against the subset of JSON Schema used by the document schema,
+/// and panics on the keywords of other schemas which are not implemented.
+fn validate(root: &Value, schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let error = |message: String| Err(format!("{}: {}", path, message));
+
+    for keyword in schema.as_object().unwrap().keys() {
+        if !KEYWORDS.contains(&keyword.as_str()) {
+            panic!("unsupported schema keyword {}", keyword);
+        }
+    }
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/definitions/");
+        return validate(root, &root["definitions"][name], value, path);
+    }
+
+    if let Some(schemas) = schema.get("oneOf").and_then(Value::as_array) {
+        let matches = schemas
+            .iter()
+            .filter(|schema| validate(root, schema, value, path).is_ok())
+            .count();
+        return match matches {
+            1 => Ok(()),
+            0 => error(String::from("matches none of the schemas")),
+            _ => error(String::from("matches more than one schema")),
+        };
+    }
+
+    if let Some(constant) = schema.get("const") {
+        if value != constant {
+            return error(format!("expected {}, got {}", constant, value));
+        }
+    }
+
+    let is_type = match schema.get("type").and_then(Value::as_str) {
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        Some("string") => value.is_string(),
+        Some("integer") => value.is_u64() || value.is_i64(),
+        Some("null") => value.is_null(),
+        Some(other) => panic!("unsupported type {}", other),
+        None => true,
+    };
+    if !is_type {
+        return error(format!("expected {}, got {}", schema["type"], value));
+    }
+
+    if let Some(minimum) = schema.get("minimum").and_then(Value::as_i64) {
+        if value.as_i64().map_or(false, |value| value < minimum) {
+            return error(format!("{} is less than {}", value, minimum));
+        }
+    }
+
+    if let Some(items) = schema.get("items") {
+        for (index, item) in value.as_array().unwrap().iter().enumerate() {
+            validate(root, items, item, &format!("{}/{}", path, index))?;
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for required in required {
+                if !object.contains_key(required.as_str().unwrap()) {
+                    return error(format!("missing property {}", required));
+                }
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let additional_properties = match schema.get("additionalProperties") {
+            Some(Value::Bool(additional_properties)) => *additional_properties,
+            Some(other) => panic!("unsupported additionalProperties {}", other),
+            None => true,
+        };
+        for (key, value) in object {
+            match properties.and_then(|properties| properties.get(key)) {
+                Some(property) => validate(root, property, value, &format!("{}/{}", path, key))?,
+                None if additional_properties => {}
+                None => return error(format!("unexpected property {}", key)),
+            }
+        }
+    }
+
+    Ok(())
+}
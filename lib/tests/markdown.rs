@@ -0,0 +1,65 @@
+use serde_json::Value;
+
+use gherkin::{IncludeOptions, IncrementingIdGenerator};
+
+const MARKDOWN_PATH: &str = "../testdata/good/markdown.feature.md";
+const PLAIN_PATH: &str = "../testdata/good/markdown.feature";
+
+#[test]
+fn parses_markdown_like_plain_gherkin() {
+    let markdown = parse_path(MARKDOWN_PATH);
+    let plain = parse_path(PLAIN_PATH);
+
+    assert_eq!(
+        markdown[0]["source"]["mediaType"],
+        "text/x.cucumber.gherkin+markdown"
+    );
+    assert_eq!(
+        plain[0]["source"]["mediaType"],
+        "text/x.cucumber.gherkin+plain"
+    );
+    assert_eq!(markdown.len(), plain.len());
+
+    // the documents only differ in the locations and the separator rows of the tables
+    for (markdown, plain) in markdown.into_iter().zip(plain).skip(1) {
+        assert_eq!(without_locations(markdown), without_locations(plain));
+    }
+}
+
+#[test]
+fn parses_other_markdown_files_as_plain_gherkin() {
+    let readme = parse_path("../README.md");
+
+    assert_eq!(
+        readme[0]["source"]["mediaType"],
+        "text/x.cucumber.gherkin+plain"
+    );
+}
+
+fn parse_path(path: &str) -> Vec<Value> {
+    let include_options = IncludeOptions {
+        source: true,
+        gherkin_document: true,
+        pickles: true,
+    };
+    let mut id_generator = IncrementingIdGenerator::new();
+    gherkin::parse_paths(vec![path], include_options, &mut id_generator)
+        .unwrap()
+        .into_iter()
+        .map(|envelope| serde_json::to_value(envelope).unwrap())
+        .collect()
+}
+
+fn without_locations(value: Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .filter(|(key, _value)| key != "location" && key != "uri" && key != "comments")
+                .map(|(key, value)| (key, without_locations(value)))
+                .collect(),
+        ),
+        Value::Array(array) => array.into_iter().map(without_locations).collect(),
+        value => value,
+    }
+}
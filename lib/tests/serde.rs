@@ -0,0 +1,97 @@
+use std::fs;
+use std::sync::Arc;
+
+use serde::de::DeserializeSeed;
+use serde_json::Value;
+
+use gherkin::cuke::{Compiler, OwnedCuke, OwnedCukeSeed};
+use gherkin::{ast, DocumentBuilder, IncrementingIdGenerator, Parser, Token, TokensBuilder};
+
+const FEATURE_PATH: &str = "../testdata/serde/cukes.feature";
+const CUKES_PATH: &str = "../testdata/serde/cukes.feature.cukes.json";
+const TOKENS_PATH: &str = "../testdata/serde/cukes.feature.tokens.json";
+
+#[test]
+fn serializes_cukes_like_the_fixture() {
+    let gherkin_document = parse_document();
+    let mut id_generator = IncrementingIdGenerator::new();
+    let mut compiler = Compiler::new(&mut id_generator);
+    let cukes = compiler.compile(&gherkin_document).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&cukes).unwrap(),
+        read_fixture(CUKES_PATH)
+    );
+}
+
+#[test]
+fn serializes_owned_cukes_like_borrowed_cukes() {
+    let gherkin_document = Arc::new(parse_document());
+    let mut id_generator = IncrementingIdGenerator::new();
+    let mut compiler = Compiler::new(&mut id_generator);
+    let cukes = compiler.compile_owned(&gherkin_document).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&cukes).unwrap(),
+        read_fixture(CUKES_PATH)
+    );
+}
+
+#[test]
+fn deserializes_owned_cukes() {
+    let gherkin_document = Arc::new(parse_document());
+    let fixture = read_fixture(CUKES_PATH);
+
+    let cukes = fixture
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|cuke| OwnedCukeSeed::new(&gherkin_document).deserialize(cuke))
+        .collect::<Result<Vec<OwnedCuke>, _>>()
+        .unwrap();
+
+    assert_eq!(cukes[0].rule().unwrap().name, "Eating");
+    assert_eq!(cukes[0].scenario().keyword, "Scenario Outline");
+    assert_eq!(cukes[0].steps().count(), 4);
+    assert_eq!(serde_json::to_value(&cukes).unwrap(), fixture);
+}
+
+#[test]
+fn rejects_owned_cukes_of_other_documents() {
+    let gherkin_document = Arc::new(parse_document());
+    let mut cuke = read_fixture(CUKES_PATH)[0].clone();
+    cuke["scenarioId"] = Value::from("unknown");
+
+    let error = OwnedCukeSeed::new(&gherkin_document)
+        .deserialize(&cuke)
+        .unwrap_err();
+    assert_eq!(error.to_string(), "unknown AST node id unknown");
+}
+
+#[test]
+fn serializes_tokens_like_the_fixture() {
+    let source = fs::read_to_string(FEATURE_PATH).unwrap();
+    let mut parser = Parser::with_builder(TokensBuilder::default());
+    let tokens = parser.parse_str(&source).unwrap();
+    let fixture = read_fixture(TOKENS_PATH);
+
+    assert_eq!(serde_json::to_value(&tokens).unwrap(), fixture);
+
+    let tokens = serde_json::from_value::<Vec<Token<'static>>>(fixture.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&tokens).unwrap(), fixture);
+}
+
+fn parse_document() -> ast::GherkinDocument {
+    let source = fs::read_to_string(FEATURE_PATH).unwrap();
+    let mut id_generator = IncrementingIdGenerator::new();
+    let builder = DocumentBuilder::with_id_generator(&mut id_generator);
+    let mut parser = Parser::with_builder(builder);
+    let mut gherkin_document = parser.parse_str(&source).unwrap();
+    gherkin_document.uri = String::from("testdata/serde/cukes.feature");
+    gherkin_document
+}
+
+fn read_fixture(path: &str) -> Value {
+    let json = fs::read_to_string(path).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
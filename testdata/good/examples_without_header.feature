@@ -0,0 +1,26 @@
+Feature: Examples without header
+
+  Scenario Outline: examples without header first
+    Given the <what>
+
+    Examples:
+
+    Examples:
+      | what |
+      | foo  |
+
+  Scenario Outline: examples without header in between
+    Given the <what>
+
+    Examples:
+      | what |
+      | bar  |
+
+    Examples:
+
+    Examples:
+      | what |
+
+    Examples:
+      | what |
+      | baz  |
@@ -0,0 +1,9 @@
+# language: sk
+Požiadavka: Najdlhšie kľúčové slovo
+
+  Scenár: Kľúčové slová s rovnakým začiatkom
+    Pokiaľ existuje krok
+    A tiež existuje ďalší krok
+    A taktiež existuje tretí krok
+    A zároveň existuje štvrtý krok
+    A existuje piaty krok
@@ -0,0 +1,26 @@
+Feature: Markdown with Gherkin
+
+  Background: Cukes
+    Given a basket
+
+  @eating @outline
+  Scenario Outline: eating cukes
+    Given there are <start> cukes
+    When I eat <eat> cukes
+    Then I should have <left> cukes
+
+    Examples: Some numbers
+      | start | eat | left |
+      |    12 |   5 |    7 |
+      |    20 |   5 |   15 |
+
+  Rule: Cukes are described
+
+    Scenario: a data table and a doc string
+      Given the cukes:
+        | name     | color |
+        | gherkin  | green |
+      And the description:
+        ```markdown
+        A *green* cuke
+        ```
@@ -0,0 +1,22 @@
+@food
+Feature: Serialized cukes
+  Background:
+    Given a basket:
+      | name    | color |
+      | gherkin | green |
+
+  Rule: Eating
+    Background:
+      Given I am hungry
+
+    @slow
+    Scenario Outline: eating <count> cukes
+      When I eat <count> cukes
+      Then the note reads:
+        """markdown
+        *yummy*
+        """
+
+      Examples:
+        | count |
+        | 5     |